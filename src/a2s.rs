@@ -0,0 +1,96 @@
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    time::{Duration, Instant},
+};
+
+use egui::{DragValue, Id, Ui};
+use serde::{Deserialize, Serialize};
+use tokio::net::UdpSocket;
+
+use crate::{
+    check::{Kind, Target},
+    game::{self, Players},
+    Reply,
+};
+
+const HEADER: [u8; 4] = [0xFF; 4];
+const QUERY: &[u8] = b"TSource Engine Query\0";
+const CHALLENGE: u8 = b'A';
+const INFO: u8 = b'I';
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct A2s {
+    pub port: u16,
+}
+
+impl A2s {
+    pub const DEFAULT: A2s = A2s { port: 27015 };
+}
+
+impl Kind for A2s {
+    fn label(&self) -> &'static str {
+        "Steam A2S"
+    }
+
+    fn ping(&self, target: &Target) -> Vec<Reply> {
+        game::ping(
+            target.address,
+            self.port,
+            target.probes,
+            target.timeout,
+            probe,
+        )
+    }
+
+    fn editor(&mut self, ui: &mut Ui, _id: Id, _address: &str, _bind: Option<&str>) {
+        ui.horizontal(|ui| {
+            ui.label("Puerto de consulta");
+            ui.add(DragValue::new(&mut self.port).clamp_range(1..=u16::MAX));
+        });
+    }
+}
+
+// Newer servers answer the first A2S_INFO with a challenge to echo back, only that last round
+// trip is timed.
+async fn probe(target: SocketAddr) -> Option<(Duration, Players)> {
+    let local = match target.ip() {
+        IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+        IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+    };
+
+    let socket = UdpSocket::bind((local, 0)).await.ok()?;
+    socket.connect(target).await.ok()?;
+
+    let mut request = [&HEADER[..], QUERY].concat();
+    let mut buf = [0; 1400];
+
+    loop {
+        let start = Instant::now();
+        socket.send(&request).await.ok()?;
+
+        let len = socket.recv(&mut buf).await.ok()?;
+        let packet = buf[..len].strip_prefix(&HEADER)?;
+
+        match packet.split_first()? {
+            (&CHALLENGE, challenge) if request.len() == HEADER.len() + QUERY.len() => {
+                request.extend(challenge.get(..4)?);
+            }
+            (&INFO, info) => return Some((start.elapsed(), players(info)?)),
+            _ => return None,
+        }
+    }
+}
+
+// Protocol byte, then name, map, folder and game as C strings and the app id ahead of the counts.
+fn players(info: &[u8]) -> Option<Players> {
+    let mut rest = info.get(1..)?;
+
+    for _ in 0..4 {
+        rest = &rest[rest.iter().position(|&b| b == 0)? + 1..];
+    }
+
+    Some(Players {
+        online: *rest.get(2)? as u32,
+        max: *rest.get(3)? as u32,
+    })
+}
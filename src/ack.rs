@@ -0,0 +1,91 @@
+use chrono::{DateTime, Local, Utc};
+use egui::{DragValue, TextEdit, Ui, WidgetText};
+use serde::{Deserialize, Serialize};
+
+use crate::ACKED;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Ack {
+    pub note: String,
+    pub until: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Draft {
+    note: String,
+    minutes: u32,
+}
+
+impl Ack {
+    pub fn expired(&self, now: DateTime<Utc>) -> bool {
+        self.until.is_some_and(|until| now >= until)
+    }
+}
+
+pub fn show(
+    ui: &mut Ui,
+    ack: &mut Option<Ack>,
+    draft: &mut Draft,
+    down: bool,
+    editable: bool,
+    now: DateTime<Utc>,
+) {
+    if let Some(current) = ack {
+        let mut text = "✔ Reconocido".to_owned();
+
+        if let Some(until) = current.until {
+            text += &format!(" hasta las {}", until.with_timezone(&Local).format("%H:%M"));
+        }
+
+        if !current.note.is_empty() {
+            text += &format!(": {}", current.note);
+        }
+
+        ui.horizontal(|ui| {
+            ui.colored_label(ACKED, text);
+
+            if editable && ui.small_button("Retirar").clicked() {
+                *ack = None;
+            }
+        });
+
+        return;
+    }
+
+    if !down || !editable {
+        return;
+    }
+
+    ui.menu_button("Reconocer caída", |ui| {
+        let note_input =
+            TextEdit::singleline(&mut draft.note).hint_text(WidgetText::italics("Nota".into()));
+
+        ui.add(note_input);
+
+        ui.horizontal(|ui| {
+            ui.label("Caduca en");
+
+            ui.add(
+                DragValue::new(&mut draft.minutes)
+                    .clamp_range(0..=10_080)
+                    .custom_formatter(|minutes, _| match minutes as u32 {
+                        0 => "nunca".into(),
+                        minutes => format!("{} min", minutes),
+                    }),
+            );
+        });
+
+        if ui.button("Reconocer").clicked() {
+            let until =
+                (draft.minutes > 0).then(|| now + chrono::Duration::minutes(draft.minutes as i64));
+
+            *ack = Some(Ack {
+                note: draft.note.trim().to_owned(),
+                until,
+            });
+
+            *draft = Draft::default();
+            ui.close_menu();
+        }
+    });
+}
@@ -0,0 +1,71 @@
+use std::f64::consts::TAU;
+
+use egui::{Color32, Context, Id};
+
+const FADE: f32 = 0.3;
+const FLASH: f64 = 0.4;
+const PULSE: f64 = 3.;
+
+// Times are taken from egui's input clock, so the effects last the same at any frame rate.
+#[derive(Clone, Copy, Default)]
+pub struct Marks {
+    sampled: Option<f64>,
+    down: Option<f64>,
+}
+
+impl Marks {
+    pub fn sampled(&mut self, ctx: &Context) {
+        self.sampled = Some(ctx.input(|input| input.time));
+    }
+
+    pub fn down(&mut self, ctx: &Context) {
+        self.down = Some(ctx.input(|input| input.time));
+    }
+
+    // Peaks right after a new sample and dies out quickly.
+    pub fn flash(&self, ctx: &Context) -> f32 {
+        decay(ctx, self.sampled, FLASH) as f32
+    }
+
+    // A few beats after going down, each one weaker than the last.
+    pub fn pulse(&self, ctx: &Context) -> f32 {
+        let left = decay(ctx, self.down, PULSE);
+        let beat = 0.5 - 0.5 * (TAU * left * PULSE).cos();
+        (left * beat) as f32
+    }
+}
+
+pub fn fade(ctx: &Context, id: Id, color: Color32) -> Color32 {
+    let channels = color
+        .to_array()
+        .map(f32::from)
+        .map(|channel| channel / 255.);
+
+    let [r, g, b, a] = [0, 1, 2, 3].map(|idx| {
+        let channel = ctx.animate_value_with_time(id.with(idx), channels[idx], FADE);
+        (channel * 255.).round() as u8
+    });
+
+    Color32::from_rgba_premultiplied(r, g, b, a)
+}
+
+pub fn mix(from: Color32, to: Color32, amount: f32) -> Color32 {
+    let [from, to] = [from, to].map(|color| color.to_array().map(f32::from));
+    let [r, g, b, a] = [0, 1, 2, 3].map(|idx| (from[idx] + (to[idx] - from[idx]) * amount) as u8);
+    Color32::from_rgba_premultiplied(r, g, b, a)
+}
+
+fn decay(ctx: &Context, since: Option<f64>, length: f64) -> f64 {
+    let Some(since) = since else {
+        return 0.;
+    };
+
+    let elapsed = ctx.input(|input| input.time) - since;
+
+    if !(0. ..length).contains(&elapsed) {
+        return 0.;
+    }
+
+    ctx.request_repaint();
+    1. - elapsed / length
+}
@@ -0,0 +1,210 @@
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::Path,
+    time::Duration,
+};
+
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, TimeZone, Timelike, Utc};
+use egui::{DragValue, TextEdit, TextStyle, Ui, WidgetText};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    clock::Clock,
+    csv,
+    store::{self, Store},
+    Canvas, PingWindow, Sample, FAIL, GROUPS,
+};
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Archive {
+    enabled: bool,
+    dir: String,
+
+    #[serde(default = "default_at")]
+    at: NaiveTime,
+
+    // Persisted so a restart after the export time does not write the same day twice.
+    #[serde(default)]
+    archived: Option<NaiveDate>,
+
+    #[serde(skip)]
+    error: Option<String>,
+}
+
+impl Default for Archive {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dir: String::new(),
+            at: default_at(),
+            archived: None,
+            error: None,
+        }
+    }
+}
+
+impl Archive {
+    // Nothing is armed until there is somewhere to write to.
+    fn armed(&self) -> bool {
+        self.enabled && !self.dir.trim().is_empty()
+    }
+
+    fn due(&self, now: DateTime<Local>) -> bool {
+        let today = now.date_naive();
+        self.armed() && now.time() >= self.at && self.archived.is_none_or(|day| day < today)
+    }
+
+    fn remaining(&self, now: DateTime<Local>) -> Duration {
+        if !self.armed() {
+            return Duration::MAX;
+        }
+
+        let today = now.date_naive();
+        let day = match self.archived == Some(today) {
+            true => today.succ_opt().unwrap_or(today),
+            false => today,
+        };
+        let next = Local.from_local_datetime(&day.and_time(self.at)).earliest();
+
+        next.map_or(Duration::MAX, |next| {
+            (next - now).to_std().unwrap_or_default()
+        })
+    }
+
+    // The next day to write, yesterday unless today has been written already.
+    fn pending(&self, now: DateTime<Local>) -> Option<NaiveDate> {
+        let yesterday = now.date_naive().pred_opt()?;
+        let pending = self.archived.map_or(yesterday, |day| day.max(yesterday));
+
+        self.armed().then_some(pending)
+    }
+}
+
+// Writes yesterday's samples of every window in the group to `pinga-grupoN-AAAA-MM-DD.csv`. They
+// come from the store, the in-memory history rarely reaches that far back.
+pub fn run(
+    archives: &mut [Archive; GROUPS.len()],
+    canvases: &[Canvas],
+    store: &mut Store,
+    clock: &Clock,
+) -> Duration {
+    let now = clock.local();
+
+    for (group, archive) in archives.iter_mut().enumerate() {
+        if !archive.due(now) {
+            continue;
+        }
+
+        let day = now.date_naive().pred_opt().unwrap_or(now.date_naive());
+
+        archive.archived = Some(now.date_naive());
+        archive.error = export(&archive.dir, group, day, canvases, store).err();
+    }
+
+    archives
+        .iter()
+        .map(|archive| archive.remaining(now))
+        .min()
+        .unwrap_or(Duration::MAX)
+}
+
+// Where the store must stop compacting raw samples so they are still there to archive.
+pub fn keep(
+    archives: &[Archive; GROUPS.len()],
+    clock: &Clock,
+) -> [Option<DateTime<Utc>>; GROUPS.len()] {
+    archives
+        .each_ref()
+        .map(|archive| archive.pending(clock.local()).map(midnight))
+}
+
+pub fn editor(ui: &mut Ui, archive: &mut Archive, store: &Store) {
+    ui.horizontal(|ui| {
+        ui.checkbox(&mut archive.enabled, "Archivar cada día a las");
+
+        let mut hour = archive.at.hour();
+        let mut minute = archive.at.minute();
+
+        let changed = ui
+            .add(DragValue::new(&mut hour).clamp_range(0..=23))
+            .changed()
+            | ui.add(DragValue::new(&mut minute).clamp_range(0..=59).prefix(":"))
+                .changed();
+
+        if changed {
+            archive.at = NaiveTime::from_hms_opt(hour, minute, 0).unwrap_or(archive.at);
+        }
+    });
+
+    let dir_input = TextEdit::singleline(&mut archive.dir)
+        .hint_text(WidgetText::italics("Carpeta".into()))
+        .font(TextStyle::Monospace);
+
+    ui.add_enabled(archive.enabled, dir_input);
+
+    if archive.enabled && !store.enabled() {
+        ui.weak("Necesita el historial en disco");
+    }
+
+    if let Some(error) = &archive.error {
+        ui.colored_label(FAIL, error);
+    }
+}
+
+fn export(
+    dir: &str,
+    group: usize,
+    day: NaiveDate,
+    canvases: &[Canvas],
+    store: &mut Store,
+) -> Result<(), String> {
+    let [from, to] = [day, day.succ_opt().unwrap_or(day)].map(midnight);
+
+    let windows = canvases
+        .iter()
+        .flat_map(|canvas| &canvas.windows)
+        .filter(|win| win.group == group);
+
+    // Everything is read before the file is created, a failing store leaves no empty archive.
+    let mut days = vec![];
+
+    for win in windows {
+        let key = store::key(&win.address, &win.check);
+        days.push((win, store.range(&key, from, to)?));
+    }
+
+    write(dir, group, day, &days).map_err(|err| err.to_string())
+}
+
+fn write(
+    dir: &str,
+    group: usize,
+    day: NaiveDate,
+    days: &[(&PingWindow, Vec<Sample>)],
+) -> io::Result<()> {
+    let dir = Path::new(dir.trim());
+    fs::create_dir_all(dir)?;
+
+    let name = format!("pinga-grupo{}-{}.csv", group + 1, day.format("%Y-%m-%d"));
+    let mut file = BufWriter::new(File::create(dir.join(name))?);
+
+    csv::header(&mut file)?;
+
+    for (win, samples) in days {
+        csv::rows(&mut file, &win.hostname, &win.address, samples)?;
+    }
+
+    file.flush()
+}
+
+fn midnight(day: NaiveDate) -> DateTime<Utc> {
+    Local
+        .from_local_datetime(&day.and_time(NaiveTime::MIN))
+        .earliest()
+        .map_or(DateTime::<Utc>::MIN_UTC, |time| time.with_timezone(&Utc))
+}
+
+fn default_at() -> NaiveTime {
+    NaiveTime::from_hms_opt(0, 5, 0).unwrap_or(NaiveTime::MIN)
+}
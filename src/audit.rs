@@ -0,0 +1,165 @@
+use std::{env, time::Instant};
+
+use chrono::{DateTime, Local, Utc};
+use egui::{Context, Grid, ScrollArea, Window};
+
+use crate::{clock::Clock, policy, Canvas, PingWindow, Policy, GROUPS};
+
+const COALESCE: i64 = 3;
+
+struct Entry {
+    time: DateTime<Utc>,
+    user: String,
+    action: String,
+    change: Option<(String, String, String)>,
+}
+
+#[derive(Default)]
+pub struct Audit {
+    pub open: bool,
+    entries: Vec<Entry>,
+}
+
+pub struct Snapshot {
+    windows: Vec<(Instant, String, bool, Policy)>,
+    groups: [Policy; GROUPS.len()],
+}
+
+impl Snapshot {
+    pub fn take(canvases: &[Canvas], groups: &[Policy; GROUPS.len()]) -> Self {
+        let windows = canvases
+            .iter()
+            .flat_map(|canvas| &canvas.windows)
+            .map(|win| (win.ctime, name(win), win.scanning, win.policy))
+            .collect();
+
+        Self {
+            windows,
+            groups: *groups,
+        }
+    }
+}
+
+impl Audit {
+    pub fn record(&mut self, action: impl Into<String>, now: DateTime<Utc>) {
+        let user = env::var("USER")
+            .or_else(|_| env::var("USERNAME"))
+            .unwrap_or_default();
+
+        self.entries.push(Entry {
+            time: now,
+            user,
+            action: action.into(),
+            change: None,
+        });
+    }
+
+    // Dragging a value changes it every frame, so keep folding into the last entry while it lasts.
+    fn record_change(
+        &mut self,
+        subject: String,
+        (label, old, new): (&str, String, String),
+        now: DateTime<Utc>,
+    ) {
+        let key = format!("{}: {}", subject, label);
+
+        if let Some(last) = self.entries.last_mut() {
+            let recent = (now - last.time).num_seconds() < COALESCE;
+
+            if let (true, Some((last_key, first, _))) = (recent, &last.change) {
+                if *last_key == key {
+                    let first = first.clone();
+                    last.time = now;
+                    last.action = format!("{} {} → {}", key, first, new);
+                    last.change = Some((key, first, new));
+                    return;
+                }
+            }
+        }
+
+        self.record(format!("{} {} → {}", key, old, new), now);
+
+        if let Some(last) = self.entries.last_mut() {
+            last.change = Some((key, old, new));
+        }
+    }
+
+    pub fn compare(
+        &mut self,
+        before: Snapshot,
+        canvases: &[Canvas],
+        groups: &[Policy; GROUPS.len()],
+        clock: &Clock,
+    ) {
+        let now = clock.utc();
+        let windows = canvases.iter().flat_map(|canvas| &canvas.windows);
+
+        for win in windows.clone() {
+            let Some((_, _, scanning, policy)) = before
+                .windows
+                .iter()
+                .find(|(ctime, ..)| *ctime == win.ctime)
+            else {
+                self.record(format!("Añadido {}", name(win)), now);
+                continue;
+            };
+
+            if *scanning != win.scanning {
+                let what = ["Desactivado", "Activado"][win.scanning as usize];
+                self.record(format!("{} el sondeo de {}", what, name(win)), now);
+            }
+
+            for change in policy::changes(policy, &win.policy) {
+                self.record_change(name(win), change, now);
+            }
+        }
+
+        for (ctime, name, ..) in &before.windows {
+            if !windows.clone().any(|win| win.ctime == *ctime) {
+                self.record(format!("Eliminado {}", name), now);
+            }
+        }
+
+        for (idx, (old, new)) in before.groups.iter().zip(groups).enumerate() {
+            for change in policy::changes(old, new) {
+                self.record_change(format!("Grupo {}", idx + 1), change, now);
+            }
+        }
+    }
+}
+
+pub fn show(ctx: &Context, audit: &mut Audit) {
+    Window::new("Registro de acciones")
+        .open(&mut audit.open)
+        .default_width(420.)
+        .show(ctx, |ui| {
+            if audit.entries.is_empty() {
+                ui.weak("Sin acciones registradas");
+            }
+
+            ScrollArea::vertical().stick_to_bottom(true).show(ui, |ui| {
+                Grid::new("audit").striped(true).show(ui, |ui| {
+                    for entry in &audit.entries {
+                        ui.monospace(
+                            entry
+                                .time
+                                .with_timezone(&Local)
+                                .format("%F %H:%M:%S")
+                                .to_string(),
+                        );
+                        ui.monospace(&entry.user);
+                        ui.label(&entry.action);
+                        ui.end_row();
+                    }
+                });
+            });
+        });
+}
+
+fn name(win: &PingWindow) -> String {
+    match (win.hostname.is_empty(), win.address.is_empty()) {
+        (false, _) => win.hostname.clone(),
+        (true, false) => win.address.clone(),
+        (true, true) => "Sin título".into(),
+    }
+}
@@ -0,0 +1,151 @@
+use std::{
+    net::IpAddr,
+    process::Stdio,
+    time::{Duration, Instant},
+};
+
+use egui::{ComboBox, Ui};
+use serde::{Deserialize, Serialize};
+use socket2::{Domain, Protocol, Socket, Type};
+use tokio::process::Command;
+
+use crate::Pong;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Backend {
+    #[default]
+    Auto,
+    Raw,
+    Dgram,
+    System,
+}
+
+impl Backend {
+    pub const ALL: [Backend; 4] = [Backend::Auto, Backend::Raw, Backend::Dgram, Backend::System];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Backend::Auto => "Automático",
+            Backend::Raw => "ICMP crudo",
+            Backend::Dgram => "ICMP DGRAM",
+            Backend::System => "Comando ping",
+        }
+    }
+
+    pub fn resolve(self, ip: IpAddr) -> Backend {
+        match self {
+            Backend::Auto => detect(ip),
+            backend => backend,
+        }
+    }
+
+    pub fn socket_type(&self) -> Type {
+        [Type::DGRAM, Type::RAW][(*self == Backend::Raw) as usize]
+    }
+}
+
+pub fn detect(ip: IpAddr) -> Backend {
+    let (domain, protocol) = match ip {
+        IpAddr::V4(_) => (Domain::IPV4, Protocol::ICMPV4),
+        IpAddr::V6(_) => (Domain::IPV6, Protocol::ICMPV6),
+    };
+
+    // Unprivileged ICMP sockets only behave on Linux, elsewhere they need raw access anyway.
+    let dgram = cfg!(any(target_os = "linux", target_os = "android"))
+        && Socket::new(domain, Type::DGRAM, Some(protocol)).is_ok();
+
+    if dgram {
+        return Backend::Dgram;
+    }
+
+    match Socket::new(domain, Type::RAW, Some(protocol)) {
+        Ok(_) => Backend::Raw,
+        Err(_) => Backend::System,
+    }
+}
+
+pub async fn system_ping(ip: IpAddr, timeout: Duration) -> (Pong, Option<u8>) {
+    let mut command = Command::new("ping");
+    let millis = timeout.as_millis().max(1);
+
+    if cfg!(windows) {
+        command.args(["-n", "1", "-w", &millis.to_string()]);
+    } else if cfg!(target_os = "macos") {
+        command.args(["-c", "1", "-W", &millis.to_string()]);
+    } else {
+        let secs = timeout.as_secs().max(1);
+        command.args(["-c", "1", "-W", &secs.to_string()]);
+    }
+
+    if ip.is_ipv6() && !cfg!(windows) {
+        command.arg("-6");
+    }
+
+    let start = Instant::now();
+
+    let output = command
+        .arg(ip.to_string())
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .output();
+
+    let Ok(Ok(output)) = tokio::time::timeout(timeout * 2, output).await else {
+        return (Pong::Failure, None);
+    };
+
+    if !output.status.success() {
+        return (Pong::Failure, None);
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let rtt = parse_rtt(&stdout).unwrap_or_else(|| start.elapsed());
+
+    (Pong::Success(rtt), parse_ttl(&stdout))
+}
+
+// Output is localized on some systems, so only rely on the "=12.3 ms" / "<1ms" shape.
+fn parse_rtt(output: &str) -> Option<Duration> {
+    output.split(['=', '<']).skip(1).find_map(|field| {
+        let number = field
+            .trim_start()
+            .split(|c: char| !c.is_ascii_digit() && c != '.')
+            .next()?;
+
+        let rest = field.trim_start()[number.len()..].trim_start();
+
+        match rest.starts_with("ms") {
+            true => number
+                .parse()
+                .ok()
+                .map(|ms: f64| Duration::from_secs_f64(ms / 1000.)),
+            false => None,
+        }
+    })
+}
+
+// Every ping flavour spells it "ttl=" or "TTL=", hop limit included.
+fn parse_ttl(output: &str) -> Option<u8> {
+    let start = output.to_ascii_lowercase().find("ttl=")? + 4;
+
+    output[start..]
+        .split(|c: char| !c.is_ascii_digit())
+        .next()?
+        .parse()
+        .ok()
+}
+
+pub fn editor(ui: &mut Ui, backend: &mut Backend) {
+    ComboBox::from_id_source("backend")
+        .selected_text(backend.label())
+        .show_ui(ui, |ui| {
+            for choice in Backend::ALL {
+                ui.selectable_value(backend, choice, choice.label());
+            }
+        });
+
+    if *backend == Backend::Auto {
+        let detected = detect(IpAddr::from([127, 0, 0, 1]));
+        ui.weak(format!("Detectado: {}", detected.label()));
+    }
+}
@@ -0,0 +1,108 @@
+use std::{
+    fs::File,
+    io::{Read, Write},
+};
+
+use egui::{TextEdit, TextStyle, Ui};
+use zip::{result::ZipError, write::SimpleFileOptions, ZipArchive, ZipWriter};
+
+use crate::{csv, FAIL, PASS};
+
+const CONFIG: &str = "config.ron";
+const HISTORY: &str = "history.sqlite3";
+
+pub enum Action {
+    Backup,
+    Restore,
+}
+
+#[derive(Default)]
+pub struct Backup {
+    pub path: String,
+    pub result: Option<Result<String, String>>,
+}
+
+pub fn menu(ui: &mut Ui, backup: &mut Backup) -> Option<Action> {
+    let mut action = None;
+
+    if backup.path.is_empty() {
+        backup.path = csv::path("copia", "zip").display().to_string();
+    }
+
+    let path_input = TextEdit::singleline(&mut backup.path)
+        .font(TextStyle::Monospace)
+        .desired_width(320.);
+
+    ui.add(path_input);
+
+    ui.horizontal(|ui| {
+        if ui.button("Guardar copia").clicked() {
+            action = Some(Action::Backup);
+        }
+
+        if ui
+            .button("Restaurar")
+            .on_hover_text("Sustituye la configuración y el historial actuales")
+            .clicked()
+        {
+            action = Some(Action::Restore);
+        }
+    });
+
+    if let Some(result) = &backup.result {
+        match result {
+            Ok(done) => ui.colored_label(PASS, done),
+            Err(error) => ui.colored_label(FAIL, error),
+        };
+    }
+
+    action
+}
+
+pub fn write(path: &str, config: &str, history: Option<&[u8]>) -> Result<(), String> {
+    let file = File::create(path.trim()).map_err(|err| err.to_string())?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default();
+
+    let entries = [(CONFIG, Some(config.as_bytes())), (HISTORY, history)];
+
+    for (name, bytes) in entries {
+        let Some(bytes) = bytes else {
+            continue;
+        };
+
+        zip.start_file(name, options)
+            .map_err(|err| err.to_string())?;
+        zip.write_all(bytes).map_err(|err| err.to_string())?;
+    }
+
+    zip.finish().map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+// The history is optional, a backup taken with the store disabled only carries the config.
+pub fn read(path: &str) -> Result<(String, Option<Vec<u8>>), String> {
+    let file = File::open(path.trim()).map_err(|err| err.to_string())?;
+    let mut zip = ZipArchive::new(file).map_err(|err| err.to_string())?;
+
+    let mut config = String::new();
+
+    zip.by_name(CONFIG)
+        .map_err(|err| err.to_string())?
+        .read_to_string(&mut config)
+        .map_err(|err| err.to_string())?;
+
+    let history = match zip.by_name(HISTORY) {
+        Ok(mut entry) => {
+            let mut bytes = vec![];
+            entry
+                .read_to_end(&mut bytes)
+                .map_err(|err| err.to_string())?;
+            Some(bytes)
+        }
+        Err(ZipError::FileNotFound) => None,
+        Err(err) => return Err(err.to_string()),
+    };
+
+    Ok((config, history))
+}
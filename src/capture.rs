@@ -0,0 +1,109 @@
+use std::{borrow::Cow, time::Instant};
+
+use arboard::{Clipboard, ImageData};
+use egui::{Context, Event, Id, LayerId, Order, ViewportCommand};
+
+use crate::PingWindow;
+
+const MAX_FRAMES: usize = 10;
+
+// A capture takes three frames: the menu that asked for it closes in the first one, the second
+// is the one read back, and the pixels arrive with the events of the third.
+#[derive(Default)]
+pub struct Capture {
+    host: Option<Instant>,
+    frames: usize,
+    pub result: Option<(Instant, Result<String, String>)>,
+
+    // On X11 the image is served by whoever owns the clipboard, so it has to outlive the copy.
+    clipboard: Option<Clipboard>,
+}
+
+impl Capture {
+    pub fn request(&mut self, ctx: &Context, host: Instant) {
+        self.host = Some(host);
+        self.frames = 0;
+        self.result = None;
+
+        ctx.move_to_top(LayerId::new(Order::Middle, Id::new(host)));
+    }
+
+    pub fn run(&mut self, ctx: &Context, windows: &[PingWindow]) {
+        let Some(host) = self.host else {
+            return;
+        };
+
+        self.frames += 1;
+
+        match self.frames {
+            1 => {
+                ctx.request_repaint();
+                return;
+            }
+            2 => {
+                ctx.send_viewport_cmd(ViewportCommand::Screenshot);
+                return;
+            }
+            _ => {}
+        }
+
+        let screenshot = ctx.input(|input| {
+            input.events.iter().find_map(|event| match event {
+                Event::Screenshot { image, .. } => Some(image.clone()),
+                _ => None,
+            })
+        });
+
+        let Some(screenshot) = screenshot else {
+            if self.frames > MAX_FRAMES {
+                self.host = None;
+                self.result = Some((host, Err("No se pudo capturar la pantalla".into())));
+            }
+
+            ctx.request_repaint();
+            return;
+        };
+
+        self.host = None;
+
+        let Some(rect) = windows
+            .iter()
+            .find(|win| win.ctime == host)
+            .and_then(|win| win.rect)
+        else {
+            return;
+        };
+
+        let rect = rect.intersect(ctx.screen_rect());
+
+        if !rect.is_positive() {
+            return;
+        }
+
+        let image = screenshot.region(&rect, Some(ctx.pixels_per_point()));
+
+        let bytes = image
+            .pixels
+            .iter()
+            .flat_map(|pixel| pixel.to_array())
+            .collect::<Vec<_>>();
+
+        let data = ImageData {
+            width: image.size[0],
+            height: image.size[1],
+            bytes: Cow::Owned(bytes),
+        };
+
+        let copied = match &mut self.clipboard {
+            Some(clipboard) => clipboard.set_image(data),
+            None => Clipboard::new()
+                .and_then(|clipboard| self.clipboard.insert(clipboard).set_image(data)),
+        };
+
+        let result = copied
+            .map(|_| format!("Imagen de {}×{} copiada", image.size[0], image.size[1]))
+            .map_err(|err| err.to_string());
+
+        self.result = Some((host, result));
+    }
+}
@@ -0,0 +1,489 @@
+use std::{fmt, future::Future, hash::Hash, net::IpAddr, time::Duration};
+
+use egui::{ComboBox, Id, Ui};
+use itertools::Itertools;
+use serde::{
+    de::{
+        self, value::EnumAccessDeserializer, DeserializeSeed, EnumAccess, IntoDeserializer,
+        VariantAccess, Visitor,
+    },
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use tokio::time;
+
+use crate::{
+    a2s::A2s,
+    backend::Backend,
+    clock::Clock,
+    dns,
+    grpc::Grpc,
+    heartbeat::{Heartbeat, Heartbeats},
+    http::Http,
+    mail::Mail,
+    minecraft::Minecraft,
+    mock::Mock,
+    mysql::Mysql,
+    ntp::Ntp,
+    postgres::Postgres,
+    redis::Redis,
+    resolver::{Dns, Record},
+    snmp::Snmp,
+    tcp::Tcp,
+    udp::Udp,
+    websocket::WebSocket,
+    worker, Reply,
+};
+
+// Everything a window settles on before a round, handed to whichever type runs it.
+pub struct Target<'a> {
+    pub address: &'a str,
+    pub interface: &'a str,
+    pub probes: usize,
+    pub timeout: Duration,
+    pub backend: Backend,
+    pub beats: &'a Heartbeats,
+    pub clock: &'a Clock,
+}
+
+// A new type is a module implementing this plus a variant below, the window code never has to
+// change.
+pub trait Kind {
+    fn label(&self) -> &'static str;
+
+    fn ping(&self, target: &Target) -> Vec<Reply>;
+
+    // Host and port the type talks TLS to, where its certificate can be watched.
+    fn tls(&self, _address: &str) -> Option<(String, u16)> {
+        None
+    }
+
+    fn editor(&mut self, _ui: &mut Ui, _id: Id, _address: &str, _bind: Option<&str>) {}
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Icmp;
+
+pub fn resolve(address: &str) -> Option<IpAddr> {
+    dns::lookup(address).ok()?.first().copied()
+}
+
+// Runs one probe per sequence number at once, each under the timeout, and numbers the replies.
+// A probe that answers `None` or not in time is lost.
+pub fn fan_out<P, F>(ip: IpAddr, probes: usize, timeout: Duration, mut probe: P) -> Vec<Reply>
+where
+    P: FnMut(usize) -> F,
+    F: Future<Output = Option<Reply>> + Send + 'static,
+{
+    let failures = Reply::failures(Some(ip), probes);
+
+    worker::block_on(async move {
+        let probes = (0..probes)
+            .map(|seq| tokio::spawn(time::timeout(timeout, probe(seq))))
+            .collect_vec();
+
+        let mut replies = vec![];
+
+        for (failure, probe) in failures.into_iter().zip(probes) {
+            let reply = match probe.await {
+                Ok(Ok(Some(reply))) => Reply {
+                    seq: failure.seq,
+                    target: failure.target,
+                    ..reply
+                },
+                _ => failure,
+            };
+
+            replies.push(reply);
+        }
+
+        replies
+    })
+}
+
+// Saved as itself, except for the types that predate it. Those keep the flat shape older configs
+// have them in and go through `Stored` (see the `Deserialize` impl).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(remote = "Self")]
+pub enum Check {
+    #[serde(skip)]
+    Icmp(Icmp),
+    #[serde(skip)]
+    Heartbeat(Heartbeat),
+    #[serde(skip)]
+    Udp(Udp),
+    #[serde(skip)]
+    Tcp(Tcp),
+    #[serde(skip)]
+    Dns(Dns),
+    Ntp(Ntp),
+    Grpc(Grpc),
+    WebSocket(WebSocket),
+    Http(Http),
+    Redis(Redis),
+    Postgres(Postgres),
+    Mysql(Mysql),
+    A2s(A2s),
+    Minecraft(Minecraft),
+    Snmp(Snmp),
+    Mock(Mock),
+    Mail(Mail),
+}
+
+// How the first types were saved, before each got its own struct. Nothing new goes here.
+#[derive(Serialize, Deserialize)]
+enum Stored {
+    Icmp,
+    Heartbeat {
+        period: Duration,
+    },
+    Udp {
+        port: u16,
+        payload: String,
+        expect: Option<String>,
+    },
+    Tcp {
+        port: u16,
+    },
+    Dns {
+        server: String,
+        record: Record,
+    },
+}
+
+const STORED: [&str; 5] = ["Icmp", "Heartbeat", "Udp", "Tcp", "Dns"];
+
+impl Default for Check {
+    fn default() -> Self {
+        Check::Icmp(Icmp)
+    }
+}
+
+impl Check {
+    pub const KINDS: [Check; 17] = [
+        Check::Icmp(Icmp),
+        Check::Heartbeat(Heartbeat::DEFAULT),
+        Check::Udp(Udp::DEFAULT),
+        Check::Tcp(Tcp::DEFAULT),
+        Check::Dns(Dns::DEFAULT),
+        Check::Ntp(Ntp),
+        Check::Grpc(Grpc::DEFAULT),
+        Check::WebSocket(WebSocket::DEFAULT),
+        Check::Http(Http::DEFAULT),
+        Check::Redis(Redis::DEFAULT),
+        Check::Postgres(Postgres::DEFAULT),
+        Check::Mysql(Mysql::DEFAULT),
+        Check::A2s(A2s::DEFAULT),
+        Check::Minecraft(Minecraft::DEFAULT),
+        Check::Snmp(Snmp::DEFAULT),
+        Check::Mock(Mock::DEFAULT),
+        Check::Mail(Mail::DEFAULT),
+    ];
+
+    pub fn kind(&self) -> &dyn Kind {
+        match self {
+            Check::Icmp(kind) => kind,
+            Check::Heartbeat(kind) => kind,
+            Check::Udp(kind) => kind,
+            Check::Tcp(kind) => kind,
+            Check::Dns(kind) => kind,
+            Check::Ntp(kind) => kind,
+            Check::Grpc(kind) => kind,
+            Check::WebSocket(kind) => kind,
+            Check::Http(kind) => kind,
+            Check::Redis(kind) => kind,
+            Check::Postgres(kind) => kind,
+            Check::Mysql(kind) => kind,
+            Check::A2s(kind) => kind,
+            Check::Minecraft(kind) => kind,
+            Check::Snmp(kind) => kind,
+            Check::Mock(kind) => kind,
+            Check::Mail(kind) => kind,
+        }
+    }
+
+    fn kind_mut(&mut self) -> &mut dyn Kind {
+        match self {
+            Check::Icmp(kind) => kind,
+            Check::Heartbeat(kind) => kind,
+            Check::Udp(kind) => kind,
+            Check::Tcp(kind) => kind,
+            Check::Dns(kind) => kind,
+            Check::Ntp(kind) => kind,
+            Check::Grpc(kind) => kind,
+            Check::WebSocket(kind) => kind,
+            Check::Http(kind) => kind,
+            Check::Redis(kind) => kind,
+            Check::Postgres(kind) => kind,
+            Check::Mysql(kind) => kind,
+            Check::A2s(kind) => kind,
+            Check::Minecraft(kind) => kind,
+            Check::Snmp(kind) => kind,
+            Check::Mock(kind) => kind,
+            Check::Mail(kind) => kind,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        self.kind().label()
+    }
+
+    pub fn tls(&self, address: &str) -> Option<(String, u16)> {
+        self.kind().tls(address)
+    }
+
+    fn same_kind(&self, other: &Check) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+}
+
+impl Kind for Icmp {
+    fn label(&self) -> &'static str {
+        "ICMP"
+    }
+
+    fn ping(&self, target: &Target) -> Vec<Reply> {
+        crate::do_ping(
+            target.address,
+            target.probes,
+            target.timeout,
+            target.interface,
+            target.backend,
+        )
+    }
+}
+
+impl Serialize for Check {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let stored = match self.clone() {
+            Check::Icmp(Icmp) => Stored::Icmp,
+            Check::Heartbeat(Heartbeat { period }) => Stored::Heartbeat { period },
+            Check::Udp(Udp {
+                port,
+                payload,
+                expect,
+            }) => Stored::Udp {
+                port,
+                payload,
+                expect,
+            },
+            Check::Tcp(Tcp { port }) => Stored::Tcp { port },
+            Check::Dns(Dns { server, record }) => Stored::Dns { server, record },
+            _ => return Check::serialize(self, serializer),
+        };
+
+        stored.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Check {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_enum("Check", &STORED, Dispatch)
+    }
+}
+
+// Reads the variant name once and hands the rest to whichever shape it belongs to.
+struct Dispatch;
+
+struct Named<V> {
+    name: String,
+    variant: V,
+}
+
+struct Name(String);
+
+impl<'de> Visitor<'de> for Dispatch {
+    type Value = Check;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("un tipo de sondeo")
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Check, A::Error> {
+        let (Name(name), variant) = data.variant()?;
+        let stored = STORED.contains(&name.as_str());
+        let named = EnumAccessDeserializer::new(Named { name, variant });
+
+        if !stored {
+            return Check::deserialize(named);
+        }
+
+        let check = match Stored::deserialize(named)? {
+            Stored::Icmp => Check::Icmp(Icmp),
+            Stored::Heartbeat { period } => Check::Heartbeat(Heartbeat { period }),
+            Stored::Udp {
+                port,
+                payload,
+                expect,
+            } => Check::Udp(Udp {
+                port,
+                payload,
+                expect,
+            }),
+            Stored::Tcp { port } => Check::Tcp(Tcp { port }),
+            Stored::Dns { server, record } => Check::Dns(Dns { server, record }),
+        };
+
+        Ok(check)
+    }
+}
+
+impl<'de> Deserialize<'de> for Name {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_identifier(Name(String::new()))
+    }
+}
+
+impl Visitor<'_> for Name {
+    type Value = Name;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("el nombre de un tipo de sondeo")
+    }
+
+    fn visit_str<E: de::Error>(self, name: &str) -> Result<Name, E> {
+        Ok(Name(name.into()))
+    }
+}
+
+impl<'de, V: VariantAccess<'de>> EnumAccess<'de> for Named<V> {
+    type Error = V::Error;
+    type Variant = V;
+
+    fn variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<(T::Value, V), V::Error> {
+        let name = seed.deserialize(self.name.into_deserializer())?;
+        Ok((name, self.variant))
+    }
+}
+
+pub fn editor(
+    ui: &mut Ui,
+    id_source: impl Hash,
+    check: &mut Check,
+    address: &str,
+    bind: Option<&str>,
+) {
+    let id = Id::new(id_source);
+
+    ui.horizontal(|ui| {
+        ui.label("Tipo");
+
+        ComboBox::from_id_source(id)
+            .selected_text(check.label())
+            .show_ui(ui, |ui| {
+                for kind in Check::KINDS {
+                    if ui
+                        .selectable_label(check.same_kind(&kind), kind.label())
+                        .clicked()
+                        && !check.same_kind(&kind)
+                    {
+                        *check = kind;
+                    }
+                }
+            });
+    });
+
+    check.kind_mut().editor(ui, id, address, bind);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{net::Ipv4Addr, time::Duration};
+
+    use super::{fan_out, Check, Icmp};
+    use crate::{
+        grpc::Grpc, heartbeat::Heartbeat, http::Http, tcp::Tcp, websocket::WebSocket, Pong, Reply,
+    };
+
+    #[test]
+    fn every_kind_survives_a_round_trip() {
+        for kind in Check::KINDS {
+            let saved = ron::to_string(&kind).unwrap();
+            assert_eq!(ron::from_str::<Check>(&saved).unwrap(), kind, "{}", saved);
+        }
+    }
+
+    #[test]
+    fn older_configs_still_load() {
+        let cases = [
+            ("Icmp", Check::Icmp(Icmp)),
+            ("Tcp(port: 22)", Check::Tcp(Tcp { port: 22 })),
+            (
+                "Heartbeat(period: (secs: 30, nanos: 0))",
+                Check::Heartbeat(Heartbeat {
+                    period: std::time::Duration::from_secs(30),
+                }),
+            ),
+        ];
+
+        for (saved, check) in cases {
+            assert_eq!(ron::from_str::<Check>(saved).unwrap(), check);
+            assert_eq!(ron::to_string(&check).unwrap(), saved.replace(' ', ""));
+        }
+    }
+
+    #[test]
+    fn certificates_are_watched_where_tls_is_spoken() {
+        let cases = [
+            (Check::Icmp(Icmp), None),
+            (Check::Tcp(Tcp { port: 443 }), None),
+            (
+                Check::Grpc(Grpc {
+                    tls: true,
+                    ..Grpc::DEFAULT
+                }),
+                Some(("example.org".into(), 50051)),
+            ),
+            (Check::Grpc(Grpc::DEFAULT), None),
+            (
+                Check::WebSocket(WebSocket {
+                    url: "wss://[::1]:8443/feed".into(),
+                    ping: false,
+                }),
+                Some(("::1".into(), 8443)),
+            ),
+            (
+                Check::Http(Http {
+                    url: "https://status.example.org/".into(),
+                    reuse: false,
+                }),
+                Some(("status.example.org".into(), 443)),
+            ),
+            (Check::Http(Http::DEFAULT), None),
+        ];
+
+        for (check, endpoint) in cases {
+            assert_eq!(check.tls("example.org"), endpoint, "{}", check.label());
+        }
+    }
+
+    #[test]
+    fn fanned_out_probes_are_numbered_and_late_ones_lost() {
+        let ip = Ipv4Addr::LOCALHOST.into();
+
+        let replies = fan_out(ip, 3, Duration::from_millis(50), |seq| async move {
+            let rtt = Duration::from_millis([1, 200, 1][seq]);
+            tokio::time::sleep(rtt).await;
+            (seq != 2).then(|| Reply::new(Pong::Success(rtt)))
+        });
+
+        let outcome = replies
+            .iter()
+            .map(|reply| {
+                (
+                    reply.seq,
+                    reply.target,
+                    matches!(reply.pong, Pong::Success(_)),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            outcome,
+            [
+                (0, Some(ip), true),
+                (1, Some(ip), false),
+                (2, Some(ip), false)
+            ]
+        );
+    }
+}
@@ -0,0 +1,81 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use egui::Vec2;
+
+use crate::PingWindow;
+
+// Anything bigger would bury the canvas under windows.
+const MAX_HOSTS: u128 = 256;
+
+// `None` when the text is not a network at all. The network and broadcast addresses of an IPv4
+// subnet are left out, /31 and /32 have neither.
+pub fn hosts(text: &str) -> Option<Result<Vec<IpAddr>, String>> {
+    let (base, prefix) = text.trim().split_once('/')?;
+    let base = base.trim().parse::<IpAddr>().ok()?;
+    let prefix = prefix.trim().parse::<u32>().ok()?;
+
+    let width = [32, 128][base.is_ipv6() as usize];
+
+    if prefix > width {
+        return Some(Err(format!("El prefijo debe ir de 0 a {}", width)));
+    }
+
+    let bits = width - prefix;
+    let skip = (base.is_ipv4() && bits >= 2) as u128;
+
+    let count = match 1u128.checked_shl(bits) {
+        Some(count) if count - 2 * skip <= MAX_HOSTS => count,
+        _ => {
+            return Some(Err(format!(
+                "La red tiene más de {} direcciones",
+                MAX_HOSTS
+            )))
+        }
+    };
+
+    let network = match base {
+        IpAddr::V4(base) => u32::from(base) as u128,
+        IpAddr::V6(base) => u128::from(base),
+    } & !(count - 1);
+
+    let hosts = (network + skip..network + count - skip)
+        .map(|host| match base {
+            IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::from(host as u32)),
+            IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::from(host)),
+        })
+        .collect();
+
+    Some(Ok(hosts))
+}
+
+// One window per host with the same setup as the one the network was typed into, cascaded from
+// where it was.
+pub fn split(win: &PingWindow, hosts: &[IpAddr]) -> Vec<PingWindow> {
+    let origin = win.rect.map(|rect| rect.min);
+
+    hosts
+        .iter()
+        .enumerate()
+        .map(|(idx, host)| {
+            let name = match win.hostname.is_empty() {
+                true => host.to_string(),
+                false => format!("{} {}", win.hostname, host),
+            };
+
+            let offset = Vec2::splat((idx % 16) as f32 * 24.);
+            let mut split = PingWindow::new(name, host.to_string(), origin.map(|pos| pos + offset));
+
+            split.label = win.label.clone();
+            split.group = win.group;
+            split.color = win.color;
+            split.probes = win.probes;
+            split.policy = win.policy;
+            split.check = win.check.clone();
+            split.interface = win.interface.clone();
+            split.probe_schedule = win.probe_schedule.clone();
+            split.alert_schedule = win.alert_schedule.clone();
+            split.scanning = win.scanning;
+            split
+        })
+        .collect()
+}
@@ -0,0 +1,87 @@
+#[cfg(test)]
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Local, Utc};
+
+// Scheduling, hysteresis and aggregation ask this for the time instead of the system, so the
+// same logic can run on a clock that goes faster than the wall, or one that only moves when a
+// test says so.
+#[derive(Clone, Debug, Default)]
+pub enum Clock {
+    #[default]
+    System,
+    Virtual {
+        start: Instant,
+        epoch: DateTime<Utc>,
+        rate: f64,
+    },
+    #[cfg(test)]
+    Manual(Arc<Mutex<(Instant, DateTime<Utc>)>>),
+}
+
+impl Clock {
+    pub fn scaled(rate: f64) -> Self {
+        Clock::Virtual {
+            start: Instant::now(),
+            epoch: Utc::now(),
+            rate,
+        }
+    }
+
+    // Stands still at the moment it was made, clones share the same time.
+    #[cfg(test)]
+    pub fn manual() -> Self {
+        Clock::Manual(Arc::new(Mutex::new((Instant::now(), Utc::now()))))
+    }
+
+    #[cfg(test)]
+    pub fn advance(&self, by: Duration) {
+        if let Clock::Manual(time) = self {
+            let mut time = time.lock().unwrap();
+            time.0 += by;
+            time.1 += chrono::Duration::from_std(by).unwrap_or_default();
+        }
+    }
+
+    pub fn now(&self) -> Instant {
+        match self {
+            Clock::System => Instant::now(),
+            Clock::Virtual { start, rate, .. } => *start + start.elapsed().mul_f64(*rate),
+            #[cfg(test)]
+            Clock::Manual(time) => time.lock().unwrap().0,
+        }
+    }
+
+    pub fn utc(&self) -> DateTime<Utc> {
+        match self {
+            Clock::System => Utc::now(),
+            Clock::Virtual { start, epoch, rate } => {
+                let elapsed = start.elapsed().mul_f64(*rate);
+                *epoch + chrono::Duration::from_std(elapsed).unwrap_or_default()
+            }
+            #[cfg(test)]
+            Clock::Manual(time) => time.lock().unwrap().1,
+        }
+    }
+
+    pub fn local(&self) -> DateTime<Local> {
+        self.utc().with_timezone(&Local)
+    }
+
+    pub fn since(&self, earlier: Instant) -> Duration {
+        self.now().saturating_duration_since(earlier)
+    }
+
+    // How long to actually sleep for a wait measured on this clock.
+    pub fn real(&self, wait: Duration) -> Duration {
+        match self {
+            Clock::System => wait,
+            Clock::Virtual { rate, .. } => {
+                Duration::try_from_secs_f64(wait.as_secs_f64() / rate).unwrap_or(Duration::MAX)
+            }
+            #[cfg(test)]
+            Clock::Manual(_) => wait,
+        }
+    }
+}
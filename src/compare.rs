@@ -0,0 +1,193 @@
+use std::time::Instant;
+
+use chrono::{DateTime, Local, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use egui::{ComboBox, Context, Grid, TextEdit, Ui, Window};
+
+use crate::{
+    clock::Clock,
+    stats::{Summary, PERCENTILES},
+    units::Units,
+    Canvas, PingWindow, Sample, FAIL, PASS,
+};
+
+#[derive(Default)]
+pub struct Compare {
+    pub open: bool,
+    host: Option<Instant>,
+    ranges: [[String; 2]; 2],
+}
+
+pub fn show(
+    ctx: &Context,
+    compare: &mut Compare,
+    canvases: &[Canvas],
+    units: Units,
+    clock: &Clock,
+) {
+    let windows = canvases.iter().flat_map(|canvas| &canvas.windows);
+    let title =
+        |win: &PingWindow| [&win.hostname, &win.address][win.hostname.is_empty() as usize].clone();
+
+    Window::new("Comparar rangos")
+        .open(&mut compare.open)
+        .default_width(360.)
+        .show(ctx, |ui| {
+            let selected = windows.clone().find(|win| Some(win.ctime) == compare.host);
+
+            ComboBox::from_id_source("compare_host")
+                .selected_text(selected.map_or("Elige un host".into(), title))
+                .show_ui(ui, |ui| {
+                    for win in windows.clone() {
+                        ui.selectable_value(&mut compare.host, Some(win.ctime), title(win));
+                    }
+                });
+
+            Grid::new("compare_ranges").num_columns(3).show(ui, |ui| {
+                for (label, [from, until]) in ["Antes", "Después"].iter().zip(&mut compare.ranges)
+                {
+                    ui.label(*label);
+                    ui.add(time_edit(from));
+                    ui.add(time_edit(until));
+                    ui.end_row();
+                }
+            });
+
+            let Some(win) = selected else {
+                return;
+            };
+
+            let spans = compare.ranges.each_ref().map(|[from, until]| {
+                let from = instant(from, clock)?;
+                let until = match until.trim() {
+                    "" => clock.utc(),
+                    until => instant(until, clock)?,
+                };
+
+                Some(slice(&win.history, from, until))
+            });
+
+            let [Some(before), Some(after)] = spans else {
+                ui.weak("Indica el inicio de ambos rangos (AAAA-MM-DD hh:mm o hh:mm)");
+                return;
+            };
+
+            let summaries = [before, after].map(|span| Summary::of(span, span));
+
+            ui.separator();
+
+            Grid::new("compare_summary")
+                .num_columns(4)
+                .striped(true)
+                .show(ui, |ui| {
+                    for header in ["", "Antes", "Después", "Δ"] {
+                        ui.strong(header);
+                    }
+
+                    ui.end_row();
+
+                    ui.label("Enviados");
+
+                    for summary in &summaries {
+                        ui.monospace(summary.sent().to_string());
+                    }
+
+                    ui.end_row();
+
+                    let [loss_before, loss_after] = summaries.each_ref().map(Summary::loss);
+
+                    ui.label("Perdidos");
+                    ui.monospace(format!("{:.1} %", loss_before));
+                    ui.monospace(format!("{:.1} %", loss_after));
+                    delta(
+                        ui,
+                        loss_after - loss_before,
+                        format!("{:+.1} %", loss_after - loss_before),
+                    );
+                    ui.end_row();
+
+                    let mut rows = vec![(
+                        "Media".to_owned(),
+                        summaries
+                            .each_ref()
+                            .map(|summary| summary.spread().map(|[_, avg, _, _]| avg)),
+                    )];
+
+                    for (idx, percentile) in PERCENTILES.into_iter().enumerate() {
+                        let values = summaries.each_ref().map(|summary| {
+                            summary.percentiles().map(|percentiles| percentiles[idx])
+                        });
+
+                        rows.push((format!("p{}", percentile), values));
+                    }
+
+                    for (label, [before, after]) in rows {
+                        let format = |value: Option<f64>| {
+                            value.map_or("—".into(), |value| units.format_secs(value))
+                        };
+
+                        ui.label(label);
+                        ui.monospace(format(before));
+                        ui.monospace(format(after));
+
+                        match (before, after) {
+                            (Some(before), Some(after)) => {
+                                let sign = ["+", "−"][(after < before) as usize];
+                                let text = format!(
+                                    "{}{}",
+                                    sign,
+                                    units.format_secs((after - before).abs())
+                                );
+                                delta(ui, after - before, text);
+                            }
+                            _ => {
+                                ui.monospace("—");
+                            }
+                        }
+
+                        ui.end_row();
+                    }
+                });
+        });
+}
+
+// Lower is better for everything shown, so growth is painted as a regression.
+fn delta(ui: &mut Ui, change: f64, text: String) {
+    match change {
+        change if change > 0. => ui.colored_label(FAIL, text),
+        change if change < 0. => ui.colored_label(PASS, text),
+        _ => ui.monospace(text),
+    };
+}
+
+fn slice(history: &[Sample], from: DateTime<Utc>, until: DateTime<Utc>) -> &[Sample] {
+    let start = history.partition_point(|sample| sample.time < from);
+    let end = history.partition_point(|sample| sample.time <= until);
+    &history[start..end.max(start)]
+}
+
+// A bare time of day refers to today.
+fn instant(text: &str, clock: &Clock) -> Option<DateTime<Utc>> {
+    let text = text.trim();
+
+    let naive = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M"]
+        .into_iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+        .or_else(|| {
+            let time = NaiveTime::parse_from_str(text, "%H:%M:%S")
+                .or_else(|_| NaiveTime::parse_from_str(text, "%H:%M"))
+                .ok()?;
+
+            Some(clock.local().date_naive().and_time(time))
+        })?;
+
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+fn time_edit(text: &mut String) -> TextEdit<'_> {
+    TextEdit::singleline(text)
+        .desired_width(120.)
+        .hint_text("AAAA-MM-DD hh:mm")
+}
@@ -0,0 +1,194 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Local, Utc};
+use egui::{Context, DragValue, ScrollArea, Window};
+use itertools::Itertools;
+
+use crate::{plot, policy, Canvas, Policy, GROUPS};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Outage,
+    Spike,
+}
+
+pub struct Cluster {
+    kind: Kind,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    hosts: Vec<String>,
+}
+
+pub struct Report {
+    pub open: bool,
+    window: Duration,
+    min_hosts: usize,
+    clusters: Vec<Cluster>,
+}
+
+impl Default for Report {
+    fn default() -> Self {
+        Self {
+            open: false,
+            window: Duration::from_secs(10),
+            min_hosts: 2,
+            clusters: vec![],
+        }
+    }
+}
+
+const SPIKE_FACTOR: u32 = 3;
+
+pub fn show(
+    ctx: &Context,
+    report: &mut Report,
+    canvases: &[Canvas],
+    policies: &[Policy; GROUPS.len()],
+) {
+    Window::new("Correlación de eventos")
+        .open(&mut report.open)
+        .default_width(320.)
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                let mut secs = report.window.as_secs();
+
+                ui.label("Ventana");
+
+                if ui
+                    .add(DragValue::new(&mut secs).clamp_range(1..=3600).suffix(" s"))
+                    .changed()
+                {
+                    report.window = Duration::from_secs(secs);
+                }
+
+                ui.label("Mínimo");
+                ui.add(
+                    DragValue::new(&mut report.min_hosts)
+                        .clamp_range(2..=100)
+                        .suffix(" hosts"),
+                );
+            });
+
+            if ui.button("Analizar").clicked() {
+                let events = events(canvases, policies);
+                report.clusters = correlate(events, report.window, report.min_hosts);
+            }
+
+            ui.separator();
+
+            if report.clusters.is_empty() {
+                ui.weak("Sin eventos correlacionados");
+            }
+
+            ScrollArea::vertical().show(ui, |ui| {
+                for cluster in report.clusters.iter().rev() {
+                    let what = match cluster.kind {
+                        Kind::Outage => "cayeron",
+                        Kind::Spike => "sufrieron picos",
+                    };
+
+                    let start = cluster.start.with_timezone(&Local).format("%H:%M:%S");
+                    let span = (cluster.end - cluster.start).to_std().unwrap_or_default();
+                    let header = format!(
+                        "{} — {} hosts {} en {:?}",
+                        start,
+                        cluster.hosts.len(),
+                        what,
+                        span
+                    );
+
+                    ui.collapsing(header, |ui| {
+                        for host in &cluster.hosts {
+                            ui.monospace(host);
+                        }
+                    });
+                }
+            });
+        });
+}
+
+fn events(
+    canvases: &[Canvas],
+    policies: &[Policy; GROUPS.len()],
+) -> Vec<(DateTime<Utc>, Kind, String)> {
+    let mut events = vec![];
+
+    for win in canvases.iter().flat_map(|canvas| &canvas.windows) {
+        let settings = win.policy.over(policies[win.group].over(policy::DEFAULTS));
+        let name = [&win.hostname, &win.address][win.hostname.is_empty() as usize];
+        let rounds = plot::rounds(&win.history);
+
+        for (since, _) in plot::outages(&rounds) {
+            events.push((since, Kind::Outage, name.clone()));
+        }
+
+        let mut medians = rounds
+            .iter()
+            .filter_map(|(_, round)| round.rtt)
+            .map(|[_, median, _]| median)
+            .collect_vec();
+        medians.sort();
+
+        let threshold = match (settings.threshold.is_zero(), medians.get(medians.len() / 2)) {
+            (false, _) => settings.threshold,
+            (true, Some(&median)) => median * SPIKE_FACTOR,
+            (true, None) => continue,
+        };
+
+        let mut spiking = false;
+
+        for (time, round) in &rounds {
+            let spike = round.rtt.is_some_and(|[_, median, _]| median > threshold);
+
+            if spike && !spiking {
+                events.push((*time, Kind::Spike, name.clone()));
+            }
+
+            spiking = spike;
+        }
+    }
+
+    events.sort_by_key(|(time, ..)| *time);
+    events
+}
+
+fn correlate(
+    events: Vec<(DateTime<Utc>, Kind, String)>,
+    window: Duration,
+    min_hosts: usize,
+) -> Vec<Cluster> {
+    let window = chrono::Duration::from_std(window).unwrap_or(chrono::Duration::zero());
+    let mut clusters = vec![];
+
+    for kind in [Kind::Outage, Kind::Spike] {
+        let mut events = events
+            .iter()
+            .filter(|(_, other, _)| *other == kind)
+            .peekable();
+
+        while let Some((start, _, host)) = events.next() {
+            let mut cluster = Cluster {
+                kind,
+                start: *start,
+                end: *start,
+                hosts: vec![host.clone()],
+            };
+
+            while let Some((time, _, host)) = events.next_if(|(time, ..)| *time - *start <= window)
+            {
+                cluster.end = *time;
+
+                if !cluster.hosts.contains(host) {
+                    cluster.hosts.push(host.clone());
+                }
+            }
+
+            if cluster.hosts.len() >= min_hosts {
+                clusters.push(cluster);
+            }
+        }
+    }
+
+    clusters.sort_by_key(|cluster| cluster.start);
+    clusters
+}
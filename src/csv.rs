@@ -0,0 +1,230 @@
+use std::{
+    env,
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::PathBuf,
+};
+
+use chrono::Local;
+use egui::{Context, TextEdit, TextStyle, Ui, WidgetText, Window};
+
+use crate::{PingWindow, Pong, Sample, FAIL, GROUPS, PASS};
+
+// Same field names as the NDJSON stream, so both can be fed to the same tooling.
+const HEADER: &str = "time,host,address,target,check,seq,success,rtt_ms";
+
+#[derive(Default)]
+pub struct Export {
+    path: String,
+    result: Option<Result<String, String>>,
+}
+
+#[derive(Default)]
+pub struct Import {
+    pub open: bool,
+    path: String,
+    text: String,
+    error: Option<String>,
+}
+
+pub fn menu(ui: &mut Ui, export: &mut Export, host: &str, address: &str, history: &[Sample]) {
+    let title = [host, address][host.is_empty() as usize];
+
+    if export.path.is_empty() {
+        export.path = path(title, "csv").display().to_string();
+    }
+
+    let path_input = TextEdit::singleline(&mut export.path)
+        .font(TextStyle::Monospace)
+        .desired_width(320.);
+
+    ui.add(path_input);
+
+    if ui.button("Exportar CSV").clicked() {
+        let result = write(&export.path, host, address, history)
+            .map(|rows| format!("{} filas guardadas", rows))
+            .map_err(|err| err.to_string());
+
+        export.result = Some(result);
+    }
+
+    if let Some(result) = &export.result {
+        match result {
+            Ok(done) => ui.colored_label(PASS, done),
+            Err(error) => ui.colored_label(FAIL, error),
+        };
+    }
+}
+
+pub fn import(ctx: &Context, import: &mut Import, windows: &mut Vec<PingWindow>) {
+    let mut open = import.open;
+
+    Window::new("Importar hosts")
+        .open(&mut open)
+        .default_width(320.)
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                let path_input = TextEdit::singleline(&mut import.path)
+                    .hint_text(WidgetText::italics("Fichero".into()))
+                    .font(TextStyle::Monospace);
+
+                ui.add(path_input);
+
+                if ui.button("Leer").clicked() {
+                    match fs::read_to_string(import.path.trim()) {
+                        Ok(text) => import.text = text,
+                        Err(err) => import.error = Some(err.to_string()),
+                    }
+                }
+            });
+
+            let input = TextEdit::multiline(&mut import.text)
+                .hint_text(WidgetText::italics("nombre,dirección,grupo".into()))
+                .font(TextStyle::Monospace)
+                .desired_width(f32::INFINITY);
+
+            ui.add(input);
+
+            if let Some(error) = &import.error {
+                ui.colored_label(FAIL, error);
+            }
+
+            if ui.button("Importar").clicked() {
+                match parse(&import.text) {
+                    Ok(parsed) => {
+                        windows.extend(parsed);
+                        import.text.clear();
+                        import.error = None;
+                        import.open = false;
+                    }
+                    Err(err) => import.error = Some(err),
+                }
+            }
+        });
+
+    import.open &= open;
+}
+
+// One `hostname,address,group` row per window, the group (1 to 5) can be left out.
+fn parse(text: &str) -> Result<Vec<PingWindow>, String> {
+    let mut windows = vec![];
+
+    for (idx, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let fields = split(line);
+        let field = |idx: usize| fields.get(idx).map_or("", |field| field.trim());
+
+        if idx == 0 && ["address", "dirección", "direccion"].contains(&&*field(1).to_lowercase()) {
+            continue;
+        }
+
+        if field(1).is_empty() {
+            return Err(format!("Línea {}: falta la dirección", idx + 1));
+        }
+
+        let group = match field(2) {
+            "" => 1,
+            group => group
+                .parse::<usize>()
+                .ok()
+                .filter(|group| (1..=GROUPS.len()).contains(group))
+                .ok_or(format!(
+                    "Línea {}: el grupo debe ir de 1 a {}",
+                    idx + 1,
+                    GROUPS.len()
+                ))?,
+        };
+
+        let mut win = PingWindow::new(field(0), field(1), None);
+        win.group = group - 1;
+        windows.push(win);
+    }
+
+    Ok(windows)
+}
+
+fn split(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, quoted) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                chars.next();
+                fields.last_mut().unwrap().push('"');
+            }
+            ('"', _) => quoted = !quoted,
+            (',', false) => fields.push(String::new()),
+            (c, _) => fields.last_mut().unwrap().push(c),
+        }
+    }
+
+    fields
+}
+
+fn write(path: &str, host: &str, address: &str, history: &[Sample]) -> io::Result<usize> {
+    let mut file = BufWriter::new(File::create(path)?);
+
+    header(&mut file)?;
+    rows(&mut file, host, address, history)?;
+
+    file.flush()?;
+    Ok(history.len())
+}
+
+pub fn header(out: &mut impl Write) -> io::Result<()> {
+    writeln!(out, "{}", HEADER)
+}
+
+pub fn rows(out: &mut impl Write, host: &str, address: &str, history: &[Sample]) -> io::Result<()> {
+    for sample in history {
+        let rtt = match sample.reply.pong {
+            Pong::Success(rtt) => format!("{:.3}", rtt.as_secs_f64() * 1000.),
+            Pong::Failure => String::new(),
+        };
+
+        let target = sample.reply.target.map(|ip| ip.to_string());
+
+        let fields = [
+            sample.time.to_rfc3339(),
+            field(host),
+            field(address),
+            target.unwrap_or_default(),
+            sample.check.into(),
+            sample.reply.seq.to_string(),
+            matches!(sample.reply.pong, Pong::Success(_)).to_string(),
+            rtt,
+        ];
+
+        writeln!(out, "{}", fields.join(","))?;
+    }
+
+    Ok(())
+}
+
+// RFC 4180: quote anything holding a separator, a quote or a line break.
+fn field(text: &str) -> String {
+    match text.contains([',', '"', '\n', '\r']) {
+        true => format!("\"{}\"", text.replace('"', "\"\"")),
+        false => text.into(),
+    }
+}
+
+// A timestamped file in the home folder, named after whatever is being exported.
+pub fn path(title: &str, extension: &str) -> PathBuf {
+    let home = env::var_os("HOME").or_else(|| env::var_os("USERPROFILE"));
+    let dir = home.map(PathBuf::from).unwrap_or_default();
+
+    let name = title
+        .chars()
+        .map(|c| [c, '_'][!c.is_alphanumeric() as usize])
+        .collect::<String>();
+
+    let stamp = Local::now().format("%Y%m%d-%H%M%S");
+
+    dir.join(format!("pinga-{}-{}.{}", name, stamp, extension))
+}
@@ -0,0 +1,68 @@
+use std::{future::Future, net::SocketAddr, time::Duration};
+
+use egui::{TextEdit, TextStyle, Ui, WidgetText};
+use serde::{Deserialize, Serialize};
+
+use crate::{check, secrets, Pong, Reply};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Login {
+    pub user: String,
+    pub password: String,
+    pub database: String,
+}
+
+impl Login {
+    pub const EMPTY: Login = Login {
+        user: String::new(),
+        password: String::new(),
+        database: String::new(),
+    };
+
+    // The password may name an environment variable, so it does not have to sit in the config.
+    pub fn password(&self) -> Option<String> {
+        secrets::interpolate(&self.password).ok()
+    }
+}
+
+pub fn editor(ui: &mut Ui, login: &mut Login, user: &str, database: &str) {
+    let user_input = TextEdit::singleline(&mut login.user)
+        .hint_text(WidgetText::italics(format!("Usuario ({})", user).into()))
+        .font(TextStyle::Monospace);
+
+    ui.add(user_input);
+
+    let password_input = TextEdit::singleline(&mut login.password)
+        .hint_text(WidgetText::italics(
+            "Contraseña (admite ${VARIABLE})".into(),
+        ))
+        .font(TextStyle::Monospace)
+        .password(true);
+
+    ui.add(password_input);
+
+    let database_input = TextEdit::singleline(&mut login.database)
+        .hint_text(WidgetText::italics(database.into()))
+        .font(TextStyle::Monospace);
+
+    ui.add(database_input);
+}
+
+// Each probe opens its own connection. The round trip is the trivial command alone, logging in
+// is only a precondition for it.
+pub fn ping<F, T>(addr: &str, port: u16, probes: usize, timeout: Duration, probe: F) -> Vec<Reply>
+where
+    F: Fn(SocketAddr) -> T,
+    T: Future<Output = Option<Duration>> + Send + 'static,
+{
+    let Some(ip) = check::resolve(addr) else {
+        return Reply::failures(None, probes);
+    };
+
+    let target = SocketAddr::new(ip, port);
+
+    check::fan_out(ip, probes, timeout, |_| {
+        let probe = probe(target);
+        async move { Some(Reply::new(Pong::Success(probe.await?))) }
+    })
+}
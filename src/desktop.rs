@@ -0,0 +1,396 @@
+use std::time::Duration;
+
+use eframe::Frame;
+use egui::{Context, Ui, UserAttentionType, ViewportCommand};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    notify::{Notifier, Outlet},
+    units::Units,
+};
+
+#[derive(Default)]
+pub struct Desktop {
+    down: Option<usize>,
+    tray: bool,
+    status: Option<String>,
+
+    #[cfg(target_os = "macos")]
+    item: Option<cocoa::base::id>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct Toasts;
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct Sound;
+
+impl Desktop {
+    pub fn toast(&mut self, frame: &Frame, title: &str, body: &str, success: bool) {
+        #[cfg(windows)]
+        if let Some(hwnd) = win32::hwnd(frame) {
+            self.tray = win32::toast(hwnd, self.tray, title, body, success);
+        }
+
+        #[cfg(not(windows))]
+        let _ = (frame, title, body, success, self.tray);
+    }
+
+    // The title is what shows up in the taskbar and the alt-tab list, on every platform.
+    pub fn badge(&mut self, ctx: &Context, frame: &Frame, down: usize) {
+        if self.down == Some(down) {
+            return;
+        }
+
+        let title = match down {
+            0 => "PingA".to_owned(),
+            1 => "PingA — 1 caído".to_owned(),
+            down => format!("PingA — {} caídos", down),
+        };
+
+        ctx.send_viewport_cmd(ViewportCommand::Title(title));
+
+        if down > self.down.unwrap_or_default() {
+            ctx.send_viewport_cmd(ViewportCommand::RequestUserAttention(
+                UserAttentionType::Informational,
+            ));
+        }
+
+        self.down = Some(down);
+
+        #[cfg(windows)]
+        if let Some(hwnd) = win32::hwnd(frame) {
+            win32::overlay(hwnd, down);
+        }
+
+        #[cfg(not(windows))]
+        let _ = frame;
+    }
+
+    pub fn status(&mut self, up: usize, down: &[(String, Option<Duration>)], units: Units) {
+        let title = match down.len() {
+            0 => format!("🟢 {}", up),
+            down => format!("🔴 {}", down),
+        };
+
+        let mut lines = vec![match down.len() {
+            0 => "Todos los hosts responden".to_owned(),
+            1 => "1 host caído".to_owned(),
+            down => format!("{} hosts caídos", down),
+        }];
+
+        for (host, rtt) in down {
+            lines.push(match rtt {
+                Some(rtt) => format!("{} — última respuesta en {}", host, units.format(*rtt)),
+                None => format!("{} — sin respuesta", host),
+            });
+        }
+
+        let status = format!("{}\n{}", title, lines.join("\n"));
+
+        if self.status.as_ref() == Some(&status) {
+            return;
+        }
+
+        self.status = Some(status);
+
+        #[cfg(target_os = "macos")]
+        macos::status(&mut self.item, &title, &lines);
+    }
+}
+
+impl Notifier for Toasts {
+    fn key(&self) -> &'static str {
+        "desktop"
+    }
+
+    fn label(&self) -> &'static str {
+        "Escritorio"
+    }
+
+    fn send(&mut self, outlet: &mut Outlet, title: &str, body: &str, success: bool) {
+        let attention = match success {
+            false => UserAttentionType::Critical,
+            true => UserAttentionType::Informational,
+        };
+
+        outlet.desktop.toast(outlet.frame, title, body, success);
+        outlet
+            .ctx
+            .send_viewport_cmd(ViewportCommand::RequestUserAttention(attention));
+    }
+
+    fn settings(&mut self, ui: &mut Ui, outlet: &mut Outlet) {
+        if ui.button("Probar escritorio").clicked() {
+            self.send(outlet, "PingA", "Notificación de prueba", true);
+        }
+    }
+}
+
+impl Notifier for Sound {
+    fn key(&self) -> &'static str {
+        "sound"
+    }
+
+    fn label(&self) -> &'static str {
+        "Sonido"
+    }
+
+    fn send(&mut self, _outlet: &mut Outlet, _title: &str, _body: &str, _success: bool) {
+        beep();
+    }
+
+    fn settings(&mut self, ui: &mut Ui, _outlet: &mut Outlet) {
+        if ui.button("Probar sonido").clicked() {
+            beep();
+        }
+    }
+}
+
+pub fn beep() {
+    #[cfg(windows)]
+    unsafe {
+        winapi::um::winuser::MessageBeep(winapi::um::winuser::MB_ICONEXCLAMATION);
+    }
+
+    #[cfg(target_os = "macos")]
+    unsafe {
+        macos::NSBeep();
+    }
+
+    #[cfg(not(any(windows, target_os = "macos")))]
+    {
+        use std::io::Write;
+
+        let _ = std::io::stderr().write_all(b"\x07");
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use std::ptr;
+
+    use cocoa::{
+        appkit::{
+            NSButton, NSMenu, NSMenuItem, NSStatusBar, NSStatusItem, NSVariableStatusItemLength,
+        },
+        base::{id, nil},
+        foundation::{NSAutoreleasePool, NSString},
+    };
+    use objc::runtime::{objc_retain, Sel};
+
+    #[link(name = "AppKit", kind = "framework")]
+    extern "C" {
+        pub fn NSBeep();
+    }
+
+    pub fn status(item: &mut Option<id>, title: &str, lines: &[String]) {
+        unsafe {
+            let pool = NSAutoreleasePool::new(nil);
+
+            let item = *item.get_or_insert_with(|| {
+                let bar = NSStatusBar::systemStatusBar(nil);
+                let item: id = bar.statusItemWithLength_(NSVariableStatusItemLength);
+                objc_retain(item)
+            });
+
+            item.button().setTitle_(string(title));
+
+            let menu = NSMenu::new(nil).autorelease();
+
+            for (idx, line) in lines.iter().enumerate() {
+                if idx == 1 {
+                    menu.addItem_(NSMenuItem::separatorItem(nil));
+                }
+
+                let entry = NSMenuItem::alloc(nil)
+                    .initWithTitle_action_keyEquivalent_(
+                        string(line),
+                        Sel::from_ptr(ptr::null()),
+                        string(""),
+                    )
+                    .autorelease();
+
+                menu.addItem_(entry);
+            }
+
+            item.setMenu_(menu);
+            pool.drain();
+        }
+    }
+
+    unsafe fn string(text: &str) -> id {
+        NSString::alloc(nil).init_str(text).autorelease()
+    }
+}
+
+#[cfg(windows)]
+mod win32 {
+    use std::{mem, ptr};
+
+    use eframe::Frame;
+    use raw_window_handle::{HasRawWindowHandle, RawWindowHandle};
+    use winapi::{
+        shared::{
+            windef::{HICON, HWND},
+            winerror::SUCCEEDED,
+            wtypesbase::CLSCTX_INPROC_SERVER,
+        },
+        um::{
+            combaseapi::{CoCreateInstance, CoInitializeEx},
+            objbase::COINIT_APARTMENTTHREADED,
+            shellapi::{
+                Shell_NotifyIconW, NIF_ICON, NIF_INFO, NIF_TIP, NIIF_INFO, NIIF_WARNING, NIM_ADD,
+                NIM_MODIFY, NOTIFYICONDATAW,
+            },
+            shobjidl_core::{CLSID_TaskbarList, ITaskbarList3},
+            winuser::{CreateIcon, DestroyIcon, LoadIconW, IDI_APPLICATION},
+        },
+        Interface,
+    };
+
+    const SIZE: usize = 16;
+    const BADGE: [u8; 4] = [0x2F, 0x2F, 0xF4, 0xFF];
+    const INK: [u8; 4] = [0xFF, 0xFF, 0xFF, 0xFF];
+
+    // 3x5 glyphs, one row per nibble, most significant bit on the left.
+    const DIGITS: [[u8; 5]; 10] = [
+        [0b111, 0b101, 0b101, 0b101, 0b111],
+        [0b010, 0b110, 0b010, 0b010, 0b111],
+        [0b111, 0b001, 0b111, 0b100, 0b111],
+        [0b111, 0b001, 0b111, 0b001, 0b111],
+        [0b101, 0b101, 0b111, 0b001, 0b001],
+        [0b111, 0b100, 0b111, 0b001, 0b111],
+        [0b111, 0b100, 0b111, 0b101, 0b111],
+        [0b111, 0b001, 0b010, 0b010, 0b010],
+        [0b111, 0b101, 0b111, 0b101, 0b111],
+        [0b111, 0b101, 0b111, 0b001, 0b111],
+    ];
+
+    pub fn hwnd(frame: &Frame) -> Option<HWND> {
+        match frame.raw_window_handle() {
+            RawWindowHandle::Win32(handle) => Some(handle.hwnd as HWND),
+            _ => None,
+        }
+    }
+
+    pub fn toast(hwnd: HWND, added: bool, title: &str, body: &str, success: bool) -> bool {
+        let mut data: NOTIFYICONDATAW = unsafe { mem::zeroed() };
+
+        data.cbSize = mem::size_of::<NOTIFYICONDATAW>() as u32;
+        data.hWnd = hwnd;
+        data.uID = 1;
+        data.uFlags = NIF_ICON | NIF_TIP | NIF_INFO;
+        data.hIcon = unsafe { LoadIconW(ptr::null_mut(), IDI_APPLICATION) };
+        data.dwInfoFlags = [NIIF_WARNING, NIIF_INFO][success as usize];
+
+        copy_wide(&mut data.szTip, "PingA");
+        copy_wide(&mut data.szInfoTitle, title);
+        copy_wide(&mut data.szInfo, body);
+
+        let message = [NIM_ADD, NIM_MODIFY][added as usize];
+        unsafe { Shell_NotifyIconW(message, &mut data) != 0 || added }
+    }
+
+    pub fn overlay(hwnd: HWND, down: usize) {
+        unsafe {
+            CoInitializeEx(ptr::null_mut(), COINIT_APARTMENTTHREADED);
+
+            let mut taskbar: *mut ITaskbarList3 = ptr::null_mut();
+
+            let created = CoCreateInstance(
+                &CLSID_TaskbarList,
+                ptr::null_mut(),
+                CLSCTX_INPROC_SERVER,
+                &ITaskbarList3::uuidof(),
+                &mut taskbar as *mut _ as *mut _,
+            );
+
+            if !SUCCEEDED(created) || taskbar.is_null() {
+                return;
+            }
+
+            let taskbar = &*taskbar;
+
+            if SUCCEEDED(taskbar.HrInit()) {
+                let icon = match down {
+                    0 => ptr::null_mut(),
+                    down => badge(down),
+                };
+
+                let description = wide(&format!("{} hosts caídos", down));
+                taskbar.SetOverlayIcon(hwnd, icon, description.as_ptr());
+
+                if !icon.is_null() {
+                    DestroyIcon(icon);
+                }
+            }
+
+            taskbar.Release();
+        }
+    }
+
+    fn badge(count: usize) -> HICON {
+        let mut pixels = [[0u8; 4]; SIZE * SIZE];
+        let center = (SIZE as f32 - 1.) / 2.;
+
+        for (idx, pixel) in pixels.iter_mut().enumerate() {
+            let [x, y] = [(idx % SIZE) as f32 - center, (idx / SIZE) as f32 - center];
+
+            if x * x + y * y <= center * center + 1. {
+                *pixel = BADGE;
+            }
+        }
+
+        let text = count.min(99).to_string();
+        let scale = [1, 2][(text.len() == 1) as usize];
+        let width = text.len() * 4 * scale - scale;
+        let [left, top] = [(SIZE - width) / 2, (SIZE - 5 * scale) / 2];
+
+        for (pos, digit) in text.bytes().enumerate() {
+            let glyph = DIGITS[(digit - b'0') as usize];
+
+            for (row, bits) in glyph.iter().enumerate() {
+                for col in 0..3 {
+                    if bits & (0b100 >> col) == 0 {
+                        continue;
+                    }
+
+                    for dy in 0..scale {
+                        for dx in 0..scale {
+                            let x = left + (pos * 4 + col) * scale + dx;
+                            let y = top + row * scale + dy;
+                            pixels[y * SIZE + x] = INK;
+                        }
+                    }
+                }
+            }
+        }
+
+        let mask = [0u8; SIZE * SIZE / 8];
+
+        unsafe {
+            CreateIcon(
+                ptr::null_mut(),
+                SIZE as i32,
+                SIZE as i32,
+                1,
+                32,
+                mask.as_ptr(),
+                pixels.as_ptr() as *const u8,
+            )
+        }
+    }
+
+    fn wide(text: &str) -> Vec<u16> {
+        text.encode_utf16().chain([0]).collect()
+    }
+
+    fn copy_wide(buf: &mut [u16], text: &str) {
+        let text = wide(text);
+        let len = text.len().min(buf.len() - 1);
+
+        buf[..len].copy_from_slice(&text[..len]);
+        buf[len] = 0;
+    }
+}
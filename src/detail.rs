@@ -0,0 +1,123 @@
+use chrono::{DateTime, Local, Utc};
+use egui::{Context, DragValue, Id, ScrollArea, TextEdit, TextStyle, WidgetText, Window};
+use egui_plot::Plot;
+use itertools::Itertools;
+
+use crate::{
+    plot::{self, Series},
+    policy::{self, Settings},
+    range::TimeRange,
+    table,
+    units::Units,
+    PingWindow, PlotTheme, MAX_PROBES,
+};
+
+pub fn show(
+    ctx: &Context,
+    win: &mut PingWindow,
+    group: Settings,
+    theme: PlotTheme,
+    range: TimeRange,
+    units: Units,
+    now: DateTime<Utc>,
+) -> bool {
+    let mut open = true;
+    let title = [&win.hostname, "Sin título"][win.hostname.is_empty() as usize];
+    let title = format!("{} — {}", title, win.address);
+
+    Window::new(title)
+        .id(Id::new(win.ctime).with("detail"))
+        .fixed_rect(ctx.available_rect().shrink(24.))
+        .collapsible(false)
+        .open(&mut open)
+        .show(ctx, |ui| {
+            let history = range.slice(&win.history, now);
+            let rounds = plot::rounds(history);
+
+            let points = rounds
+                .iter()
+                .map(|&(time, round)| (plot::timestamp(time), round))
+                .collect_vec();
+
+            let response = Plot::new("detail")
+                .height(ui.available_height() / 2.)
+                .auto_bounds_y()
+                .link_cursor(plot::CURSORS, true, false)
+                .x_axis_formatter(|x, _, _| clock(x))
+                .y_axis_formatter(move |y, _, _| units.format_secs(y))
+                .label_formatter(plot::rtt_label(units))
+                .show(ui, |ui| Series::new(&points, theme).show(ui));
+
+            let bounds = response.transform.bounds();
+            let [start, end] = [bounds.min()[0], bounds.max()[0]];
+            let lo = history.partition_point(|sample| plot::timestamp(sample.time) < start);
+            let hi = history.partition_point(|sample| plot::timestamp(sample.time) <= end);
+
+            ui.columns(3, |columns| {
+                columns[0].strong("Historial");
+                table::history(
+                    &mut columns[0],
+                    &history[lo..hi],
+                    &mut win.table,
+                    &mut win.columns,
+                    None,
+                    f32::INFINITY,
+                    units,
+                );
+
+                columns[1].strong("Caídas");
+
+                ScrollArea::vertical()
+                    .id_source("outages")
+                    .show(&mut columns[1], |ui| {
+                        for (since, until) in plot::outages(&rounds).into_iter().rev() {
+                            let since_text = since.with_timezone(&Local).format("%H:%M:%S");
+
+                            let text = match until {
+                                Some(until) => {
+                                    let until_text = until.with_timezone(&Local).format("%H:%M:%S");
+                                    let length = (until - since).to_std().unwrap_or_default();
+                                    format!("{} – {} ({:?})", since_text, until_text, length)
+                                }
+                                None => format!("{} – en curso", since_text),
+                            };
+
+                            ui.monospace(text);
+                        }
+                    });
+
+                columns[2].strong("Notas");
+
+                let scratch_input = TextEdit::multiline(&mut win.scratchpad)
+                    .font(TextStyle::Monospace)
+                    .desired_width(f32::INFINITY)
+                    .hint_text(WidgetText::italics("Anotaciones".into()));
+
+                columns[2].add(scratch_input);
+                columns[2].strong("Ajustes");
+
+                columns[2].horizontal(|ui| {
+                    ui.label("Sondas");
+                    ui.add(DragValue::new(&mut win.probes).clamp_range(1..=MAX_PROBES));
+                });
+
+                policy::editor(
+                    &mut columns[2],
+                    ("detail", win.ctime),
+                    &mut win.policy,
+                    group,
+                );
+            });
+        });
+
+    open
+}
+
+pub fn clock(timestamp: f64) -> String {
+    let millis = (timestamp * 1000.) as i64;
+
+    match DateTime::from_timestamp_millis(millis) {
+        Some(time) => time.with_timezone(&Local).format("%H:%M:%S").to_string(),
+        None => String::new(),
+    }
+}
@@ -0,0 +1,232 @@
+use std::fs;
+
+use chrono::{DateTime, Utc};
+use egui::{Color32, Pos2, Rect, TextEdit, TextStyle, Ui, Vec2};
+
+use crate::{csv, Canvas, PingWindow, FAIL, GROUPS, PASS};
+
+const SIZE: Vec2 = Vec2::new(200., 80.);
+const PADDING: f32 = 24.;
+const HEADING: f32 = 32.;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Dot,
+    Svg,
+}
+
+impl Format {
+    fn label(self) -> &'static str {
+        match self {
+            Format::Dot => "Graphviz (DOT)",
+            Format::Svg => "Imagen (SVG)",
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Format::Dot => "dot",
+            Format::Svg => "svg",
+        }
+    }
+}
+
+pub struct Export {
+    path: String,
+    format: Format,
+    result: Option<Result<String, String>>,
+}
+
+impl Default for Export {
+    fn default() -> Self {
+        Self {
+            path: String::new(),
+            format: Format::Svg,
+            result: None,
+        }
+    }
+}
+
+pub fn menu(ui: &mut Ui, export: &mut Export, canvases: &[Canvas], now: DateTime<Utc>) {
+    let previous = export.format;
+
+    ui.horizontal(|ui| {
+        for format in [Format::Svg, Format::Dot] {
+            ui.selectable_value(&mut export.format, format, format.label());
+        }
+    });
+
+    if export.path.is_empty() || export.format != previous {
+        export.path = csv::path("disposicion", export.format.extension())
+            .display()
+            .to_string();
+    }
+
+    let path_input = TextEdit::singleline(&mut export.path)
+        .font(TextStyle::Monospace)
+        .desired_width(320.);
+
+    ui.add(path_input);
+
+    if ui.button("Exportar disposición").clicked() {
+        let text = match export.format {
+            Format::Dot => dot(canvases, now),
+            Format::Svg => svg(canvases, now),
+        };
+
+        let result = fs::write(&export.path, text)
+            .map(|_| "Disposición exportada".into())
+            .map_err(|err| err.to_string());
+
+        export.result = Some(result);
+    }
+
+    if let Some(result) = &export.result {
+        match result {
+            Ok(done) => ui.colored_label(PASS, done),
+            Err(error) => ui.colored_label(FAIL, error),
+        };
+    }
+}
+
+// One cluster per canvas and, inside it, one per group. Positions are pinned so `neato -n`
+// reproduces the arrangement on screen.
+fn dot(canvases: &[Canvas], now: DateTime<Utc>) -> String {
+    let mut dot = String::from("graph pinga {\n");
+    dot += "    node [shape=box, style=\"rounded,filled\", fontname=monospace];\n";
+
+    for (idx, canvas) in canvases.iter().enumerate() {
+        dot += &format!("    subgraph cluster_{} {{\n", idx);
+        dot += &format!("        label=\"{}\";\n", quote(&canvas.name));
+
+        for (group, &color) in GROUPS.iter().enumerate() {
+            let members = canvas
+                .windows
+                .iter()
+                .enumerate()
+                .filter(|(_, win)| win.group == group)
+                .collect::<Vec<_>>();
+
+            if members.is_empty() {
+                continue;
+            }
+
+            dot += &format!("        subgraph cluster_{}_{} {{\n", idx, group);
+            dot += &format!(
+                "            label=\"Grupo {}\"; style=filled; fillcolor=\"{}\"; fontcolor=white;\n",
+                group + 1,
+                hex(color),
+            );
+
+            for (win_idx, win) in members {
+                let rect = rect(win);
+
+                dot += &format!(
+                    "            w{}_{} [label=\"{}\\n{}\", fillcolor=\"{}\", color=\"{}\", pos=\"{},{}!\"];\n",
+                    idx,
+                    win_idx,
+                    quote(title(win)),
+                    quote(&win.address),
+                    hex(fill(win)),
+                    hex(win.status(now)),
+                    rect.center().x,
+                    -rect.center().y,
+                );
+            }
+
+            dot += "        }\n";
+        }
+
+        dot += "    }\n";
+    }
+
+    dot += "}\n";
+    dot
+}
+
+// Canvases are stacked top to bottom, each cropped to the windows it holds.
+fn svg(canvases: &[Canvas], now: DateTime<Utc>) -> String {
+    let mut body = String::new();
+    let mut top = PADDING;
+    let mut width = 0f32;
+
+    for canvas in canvases {
+        let bounds = canvas
+            .windows
+            .iter()
+            .map(rect)
+            .reduce(|bounds, rect| bounds.union(rect))
+            .unwrap_or(Rect::from_min_size(Pos2::ZERO, SIZE));
+
+        let shift = Vec2::new(PADDING, top + HEADING) - bounds.min.to_vec2();
+
+        body += &format!(
+            "  <text x=\"{}\" y=\"{}\" font-size=\"18\" font-weight=\"bold\">{}</text>\n",
+            PADDING,
+            top + HEADING * 0.6,
+            escape(&canvas.name),
+        );
+
+        for win in &canvas.windows {
+            let rect = rect(win).translate(shift);
+
+            body += &format!(
+                "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"6\" fill=\"{}\" \
+                 stroke=\"{}\" stroke-width=\"3\"/>\n",
+                rect.min.x,
+                rect.min.y,
+                rect.width(),
+                rect.height(),
+                hex(fill(win)),
+                hex(win.status(now)),
+            );
+
+            for (line, text) in [title(win), &win.address].into_iter().enumerate() {
+                body += &format!(
+                    "  <text x=\"{}\" y=\"{}\" fill=\"white\">{}</text>\n",
+                    rect.min.x + 10.,
+                    rect.min.y + 22. + 18. * line as f32,
+                    escape(text),
+                );
+            }
+        }
+
+        width = width.max(bounds.width() + 2. * PADDING);
+        top += HEADING + bounds.height() + PADDING;
+    }
+
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" \
+         font-family=\"monospace\" font-size=\"13\">\n{}</svg>\n",
+        width, top, body,
+    )
+}
+
+// Windows not drawn since startup only know where they were created.
+fn rect(win: &PingWindow) -> Rect {
+    win.rect
+        .unwrap_or_else(|| Rect::from_min_size(win.origin.unwrap_or_default(), SIZE))
+}
+
+fn title(win: &PingWindow) -> &str {
+    [&win.hostname, &win.address][win.hostname.is_empty() as usize]
+}
+
+fn fill(win: &PingWindow) -> Color32 {
+    win.color.unwrap_or(GROUPS[win.group])
+}
+
+fn hex(color: Color32) -> String {
+    format!("#{:02x}{:02x}{:02x}", color.r(), color.g(), color.b())
+}
+
+fn quote(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
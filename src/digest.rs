@@ -0,0 +1,112 @@
+use std::time::{Duration, Instant};
+
+use itertools::Itertools;
+
+use crate::routing::Severity;
+
+const MAX_LISTED: usize = 5;
+
+struct Pending {
+    host: String,
+    group: Option<usize>,
+    severity: Severity,
+    success: bool,
+    body: &'static str,
+}
+
+pub struct Batch {
+    pub title: String,
+    pub body: String,
+    pub severity: Severity,
+    pub success: bool,
+}
+
+#[derive(Default)]
+pub struct Digest {
+    pending: Vec<Pending>,
+    since: Option<Instant>,
+}
+
+impl Digest {
+    pub fn push(
+        &mut self,
+        host: &str,
+        group: Option<usize>,
+        severity: Severity,
+        success: bool,
+        body: &'static str,
+        now: Instant,
+    ) {
+        self.since.get_or_insert(now);
+
+        self.pending.push(Pending {
+            host: host.to_owned(),
+            group,
+            severity,
+            success,
+            body,
+        });
+    }
+
+    pub fn remaining(&self, window: Duration, now: Instant) -> Duration {
+        match self.since {
+            Some(since) => window.saturating_sub(now.saturating_duration_since(since)),
+            None => Duration::MAX,
+        }
+    }
+
+    pub fn flush(&mut self, window: Duration, now: Instant) -> Vec<Batch> {
+        if self.since.is_none() || !self.remaining(window, now).is_zero() {
+            return vec![];
+        }
+
+        self.since = None;
+
+        self.pending
+            .drain(..)
+            .into_group_map_by(|pending| {
+                (
+                    pending.group,
+                    pending.severity,
+                    pending.success,
+                    pending.body,
+                )
+            })
+            .into_iter()
+            .sorted_by_key(|&(key, _)| key)
+            .map(
+                |((group, severity, success, body), pending)| match &pending[..] {
+                    [single] => Batch {
+                        title: single.host.clone(),
+                        body: body.to_owned(),
+                        severity,
+                        success,
+                    },
+                    _ => {
+                        let mut hosts = pending
+                            .iter()
+                            .map(|pending| &pending.host)
+                            .take(MAX_LISTED)
+                            .join(", ");
+
+                        if pending.len() > MAX_LISTED {
+                            hosts += &format!(" y {} más", pending.len() - MAX_LISTED);
+                        }
+
+                        Batch {
+                            title: match group {
+                                Some(group) => {
+                                    format!("{} hosts del grupo {}", pending.len(), group + 1)
+                                }
+                                None => format!("{} métricas", pending.len()),
+                            },
+                            body: format!("{}: {}", body, hosts),
+                            severity,
+                            success,
+                        }
+                    }
+                },
+            )
+            .collect()
+    }
+}
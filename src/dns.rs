@@ -0,0 +1,33 @@
+use std::{io, net::IpAddr};
+
+use percent_encoding::percent_decode_str;
+
+pub fn lookup(addr: &str) -> io::Result<Vec<IpAddr>> {
+    dns_lookup::lookup_host(&ascii(addr))
+}
+
+// Turns whatever was typed or pasted (`M%C3%BCnchen.de.`, `bücher.example`) into the
+// punycode form the resolver understands. IP literals and anything that is not a valid
+// domain go through untouched and get to fail on their own.
+pub fn ascii(addr: &str) -> String {
+    let addr = addr.trim();
+
+    // Colons only show up in IPv6 literals, scoped ones (`fe80::1%eth0`) included.
+    if addr.contains(':') || addr.parse::<IpAddr>().is_ok() {
+        return addr.into();
+    }
+
+    let decoded = percent_decode_str(addr).decode_utf8_lossy();
+    let name = decoded.trim_end_matches('.');
+
+    idna::domain_to_ascii(name).unwrap_or_else(|_| name.into())
+}
+
+pub fn unicode(addr: &str) -> String {
+    let ascii = ascii(addr);
+
+    match idna::domain_to_unicode(&ascii) {
+        (name, Ok(())) => name,
+        (_, Err(_)) => ascii,
+    }
+}
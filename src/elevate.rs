@@ -0,0 +1,126 @@
+use std::{
+    env,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    process::Command,
+    sync::{Arc, Mutex},
+    thread,
+};
+
+use egui::Ui;
+
+use crate::{
+    backend::{self, Backend},
+    FAIL, PASS,
+};
+
+#[derive(Default)]
+pub struct Helper {
+    detected: Option<[Backend; 2]>,
+    result: Arc<Mutex<Option<Result<&'static str, String>>>>,
+}
+
+enum Fix {
+    // Takes effect right away but is lost on reboot.
+    Sysctl,
+    // Survives reboots but only applies to freshly started processes.
+    Setcap,
+}
+
+impl Fix {
+    fn args(&self) -> Result<Vec<String>, String> {
+        match self {
+            Fix::Sysctl => Ok(vec![
+                "sysctl".into(),
+                "-w".into(),
+                "net.ipv4.ping_group_range=0 2147483647".into(),
+            ]),
+            Fix::Setcap => {
+                let exe = env::current_exe().map_err(|err| err.to_string())?;
+                Ok(vec![
+                    "setcap".into(),
+                    "cap_net_raw+ep".into(),
+                    exe.display().to_string(),
+                ])
+            }
+        }
+    }
+
+    fn done(&self) -> &'static str {
+        match self {
+            Fix::Sysctl => "Aplicado, ya se pueden usar sockets ICMP",
+            Fix::Setcap => "Aplicado, reinicia PingA para usar sockets ICMP",
+        }
+    }
+}
+
+impl Helper {
+    fn apply(&self, fix: Fix) {
+        let result = self.result.clone();
+
+        thread::spawn(move || {
+            let outcome = fix.args().and_then(|args| {
+                let output = Command::new("pkexec")
+                    .args(args)
+                    .output()
+                    .map_err(|err| format!("No se pudo lanzar pkexec: {}", err))?;
+
+                match output.status.success() {
+                    true => Ok(fix.done()),
+                    false => Err(String::from_utf8_lossy(&output.stderr).trim().to_owned()),
+                }
+            });
+
+            *result.lock().unwrap() = Some(outcome);
+        });
+    }
+}
+
+pub fn show(ui: &mut Ui, helper: &mut Helper) {
+    let detected = *helper.detected.get_or_insert_with(|| {
+        [
+            backend::detect(IpAddr::V4(Ipv4Addr::LOCALHOST)),
+            backend::detect(IpAddr::V6(Ipv6Addr::LOCALHOST)),
+        ]
+    });
+
+    for (family, backend) in ["IPv4", "IPv6"].into_iter().zip(detected) {
+        let (color, text) = match backend {
+            Backend::System => (FAIL, "sin sockets ICMP, se usará el ping del sistema"),
+            _ => (PASS, backend.label()),
+        };
+
+        ui.colored_label(color, format!("{}: {}", family, text));
+    }
+
+    if !detected.contains(&Backend::System) {
+        return;
+    }
+
+    if cfg!(target_os = "linux") {
+        ui.horizontal(|ui| {
+            if ui.button("Permitir a todos los usuarios").clicked() {
+                helper.apply(Fix::Sysctl);
+            }
+
+            if ui.button("Dar permiso a PingA").clicked() {
+                helper.apply(Fix::Setcap);
+            }
+        });
+    } else if cfg!(windows) {
+        ui.weak("Ejecuta PingA como administrador para usar sockets ICMP propios");
+    } else {
+        ui.weak("Ejecuta PingA con sudo para usar sockets ICMP propios");
+    }
+
+    if let Some(result) = helper.result.lock().unwrap().as_ref() {
+        match result {
+            Ok(done) => ui.colored_label(PASS, *done),
+            Err(error) => ui.colored_label(FAIL, error),
+        };
+    }
+
+    if ui.button("Volver a comprobar").clicked() {
+        helper.detected = None;
+        *helper.result.lock().unwrap() = None;
+    }
+}
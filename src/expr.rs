@@ -0,0 +1,252 @@
+use crate::{clock::Clock, plot, range::TimeRange, Canvas};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Field {
+    Rtt,
+    Loss,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Func {
+    Min,
+    Max,
+    Avg,
+    P95,
+    Last,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expr {
+    Number(f64),
+    Series(Field, String),
+    Neg(Box<Expr>),
+    Binary(char, Box<Expr>, Box<Expr>),
+    Call(Func, Box<Expr>),
+}
+
+enum Value {
+    Scalar(f64),
+    Series(Vec<f64>),
+}
+
+struct Parser<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl Value {
+    fn scalar(self) -> Option<f64> {
+        match self {
+            Value::Scalar(value) => Some(value),
+            Value::Series(values) => values.last().copied(),
+        }
+    }
+}
+
+impl Func {
+    fn apply(self, mut values: Vec<f64>) -> Option<f64> {
+        if self == Func::Last || values.is_empty() {
+            return values.last().copied();
+        }
+
+        values.sort_by(f64::total_cmp);
+
+        match self {
+            Func::Min => values.first().copied(),
+            Func::Max => values.last().copied(),
+            Func::Avg => Some(values.iter().sum::<f64>() / values.len() as f64),
+            Func::P95 => Some(values[(values.len() - 1) * 95 / 100]),
+            Func::Last => values.last().copied(),
+        }
+    }
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.src[self.pos..]
+    }
+
+    fn skip_spaces(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn eat(&mut self, token: char) -> bool {
+        self.skip_spaces();
+
+        match self.rest().starts_with(token) {
+            true => {
+                self.pos += token.len_utf8();
+                true
+            }
+            false => false,
+        }
+    }
+
+    fn expect(&mut self, token: char) -> Result<(), String> {
+        match self.eat(token) {
+            true => Ok(()),
+            false => Err(format!(
+                "Se esperaba «{}» en la posición {}",
+                token,
+                self.pos + 1
+            )),
+        }
+    }
+
+    fn take_while(&mut self, accept: impl Fn(char) -> bool) -> &'a str {
+        let rest = self.rest();
+        let len = rest.find(|c: char| !accept(c)).unwrap_or(rest.len());
+        self.pos += len;
+        &rest[..len]
+    }
+
+    fn expr(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.term()?;
+
+        loop {
+            let op = match () {
+                _ if self.eat('+') => '+',
+                _ if self.eat('-') => '-',
+                _ => return Ok(lhs),
+            };
+
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(self.term()?));
+        }
+    }
+
+    fn term(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.unary()?;
+
+        loop {
+            let op = match () {
+                _ if self.eat('*') => '*',
+                _ if self.eat('/') => '/',
+                _ => return Ok(lhs),
+            };
+
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(self.unary()?));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        match self.eat('-') {
+            true => Ok(Expr::Neg(Box::new(self.unary()?))),
+            false => self.atom(),
+        }
+    }
+
+    fn atom(&mut self) -> Result<Expr, String> {
+        if self.eat('(') {
+            let expr = self.expr()?;
+            self.expect(')')?;
+            return Ok(expr);
+        }
+
+        self.skip_spaces();
+        let start = self.pos;
+
+        let number = self.take_while(|c| c.is_ascii_digit() || c == '.');
+
+        if !number.is_empty() {
+            return number
+                .parse()
+                .map(Expr::Number)
+                .map_err(|_| format!("Número no válido en la posición {}", start + 1));
+        }
+
+        let name = self.take_while(|c| c.is_alphanumeric() || c == '_');
+
+        let field = match name {
+            "rtt" => Some(Field::Rtt),
+            "loss" => Some(Field::Loss),
+            _ => None,
+        };
+
+        let func = match name {
+            "min" => Some(Func::Min),
+            "max" => Some(Func::Max),
+            "avg" => Some(Func::Avg),
+            "p95" => Some(Func::P95),
+            "last" => Some(Func::Last),
+            _ => None,
+        };
+
+        match (field, func) {
+            (Some(field), _) => {
+                self.expect('[')?;
+                let target = self.take_while(|c| c != ']').trim().to_owned();
+                self.expect(']')?;
+                Ok(Expr::Series(field, target))
+            }
+            (_, Some(func)) => {
+                self.expect('(')?;
+                let arg = self.expr()?;
+                self.expect(')')?;
+                Ok(Expr::Call(func, Box::new(arg)))
+            }
+            _ if name.is_empty() => {
+                Err(format!("Expresión incompleta en la posición {}", start + 1))
+            }
+            _ => Err(format!("«{}» no es una serie ni una función", name)),
+        }
+    }
+}
+
+pub fn parse(src: &str) -> Result<Expr, String> {
+    let mut parser = Parser { src, pos: 0 };
+    let expr = parser.expr()?;
+
+    parser.skip_spaces();
+
+    match parser.rest().is_empty() {
+        true => Ok(expr),
+        false => Err(format!("Sobra texto en la posición {}", parser.pos + 1)),
+    }
+}
+
+pub fn eval(expr: &Expr, canvases: &[Canvas], range: TimeRange, clock: &Clock) -> Option<f64> {
+    value(expr, canvases, range, clock)?.scalar()
+}
+
+fn value(expr: &Expr, canvases: &[Canvas], range: TimeRange, clock: &Clock) -> Option<Value> {
+    let value = match expr {
+        Expr::Number(number) => Value::Scalar(*number),
+        Expr::Series(field, target) => {
+            let win = canvases
+                .iter()
+                .flat_map(|canvas| &canvas.windows)
+                .find(|win| win.hostname == *target || win.address == *target)?;
+
+            let history = win.range.unwrap_or(range).slice(&win.history, clock.utc());
+
+            let values = plot::rounds(history)
+                .into_iter()
+                .filter_map(|(_, round)| match field {
+                    Field::Rtt => round.rtt.map(|[_, median, _]| median.as_secs_f64() * 1000.),
+                    Field::Loss => Some(round.loss as f64 * 100.),
+                })
+                .collect();
+
+            Value::Series(values)
+        }
+        Expr::Neg(inner) => Value::Scalar(-value(inner, canvases, range, clock)?.scalar()?),
+        Expr::Binary(op, lhs, rhs) => {
+            let lhs = value(lhs, canvases, range, clock)?.scalar()?;
+            let rhs = value(rhs, canvases, range, clock)?.scalar()?;
+
+            Value::Scalar(match op {
+                '+' => lhs + rhs,
+                '-' => lhs - rhs,
+                '*' => lhs * rhs,
+                _ => lhs / rhs,
+            })
+        }
+        Expr::Call(func, inner) => match value(inner, canvases, range, clock)? {
+            Value::Scalar(value) => Value::Scalar(value),
+            Value::Series(values) => Value::Scalar(func.apply(values)?),
+        },
+    };
+
+    Some(value)
+}
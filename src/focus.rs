@@ -0,0 +1,115 @@
+use std::time::Instant;
+
+use chrono::{DateTime, Utc};
+use egui::{Area, Context, Frame, Id, Key, Modifiers, Order, RichText, ViewportCommand};
+use egui_plot::Plot;
+use itertools::Itertools;
+
+use crate::{
+    detail,
+    plot::{self, Series},
+    range::TimeRange,
+    stats,
+    units::Units,
+    PingWindow, PlotTheme, GROUPS,
+};
+
+#[derive(Default)]
+pub struct Focus {
+    host: Option<Instant>,
+}
+
+impl Focus {
+    pub fn enter(&mut self, ctx: &Context, host: Instant) {
+        self.host = Some(host);
+        ctx.send_viewport_cmd(ViewportCommand::Fullscreen(true));
+    }
+
+    fn leave(&mut self, ctx: &Context) {
+        self.host = None;
+        ctx.send_viewport_cmd(ViewportCommand::Fullscreen(false));
+    }
+}
+
+// Covers everything else while it lasts, polling goes on underneath as usual.
+pub fn show(
+    ctx: &Context,
+    focus: &mut Focus,
+    windows: &mut [PingWindow],
+    themes: &[PlotTheme; GROUPS.len()],
+    range: TimeRange,
+    units: Units,
+    now: DateTime<Utc>,
+) {
+    let Some(host) = focus.host else {
+        return;
+    };
+
+    let escaped = ctx.input_mut(|input| input.consume_key(Modifiers::NONE, Key::Escape));
+
+    let Some(win) = windows
+        .iter_mut()
+        .find(|win| win.ctime == host)
+        .filter(|_| !escaped)
+    else {
+        focus.leave(ctx);
+        return;
+    };
+
+    let screen = ctx.screen_rect();
+    let mut leave = false;
+
+    Area::new(Id::new(host).with("focus"))
+        .order(Order::Foreground)
+        .fixed_pos(screen.min)
+        .show(ctx, |ui| {
+            Frame::canvas(ui.style()).show(ui, |ui| {
+                ui.set_min_size(screen.size() - ui.spacing().window_margin.sum());
+                ui.set_max_size(screen.size() - ui.spacing().window_margin.sum());
+
+                let title = [&win.hostname, &win.address][win.hostname.is_empty() as usize];
+
+                ui.horizontal(|ui| {
+                    ui.label(RichText::new("████").heading().color(win.status(now)));
+                    ui.heading(title);
+                    ui.monospace(&win.address);
+
+                    if ui.button("✖ Salir (Esc)").clicked() {
+                        leave = true;
+                    }
+                });
+
+                let range = win.range.unwrap_or(range);
+                let theme = win.theme.unwrap_or(themes[win.group]);
+                let history = range.slice(&win.history, now);
+
+                let points = plot::rounds(history)
+                    .into_iter()
+                    .map(|(time, round)| (plot::timestamp(time), round))
+                    .collect_vec();
+
+                Plot::new(("focus", host))
+                    .height(ui.available_height() * 0.7)
+                    .auto_bounds_y()
+                    .x_axis_formatter(|x, _, _| detail::clock(x))
+                    .y_axis_formatter(move |y, _, _| units.format_secs(y))
+                    .label_formatter(plot::rtt_label(units))
+                    .show(ui, |ui| Series::new(&points, theme).show(ui));
+
+                let span = win.percentiles.slice(&win.history, history, now);
+                let summary = stats::Summary::of(history, span);
+
+                stats::show(
+                    ui,
+                    ("focus_stats", host),
+                    &summary,
+                    &mut win.percentiles,
+                    units,
+                );
+            });
+        });
+
+    if leave {
+        focus.leave(ctx);
+    }
+}
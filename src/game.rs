@@ -0,0 +1,49 @@
+use std::{future::Future, net::SocketAddr, time::Duration};
+
+use egui::Ui;
+
+use crate::{check, Pong, Reply, Sample};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Players {
+    pub online: u32,
+    pub max: u32,
+}
+
+pub fn format(players: Players) -> String {
+    format!("{}/{}", players.online, players.max)
+}
+
+pub fn show(ui: &mut Ui, last: Option<&Sample>) {
+    let Some(players) = last.and_then(|sample| sample.reply.players) else {
+        return;
+    };
+
+    ui.label(format!("👥 {} jugadores", format(players)));
+}
+
+// Like the database checks, each probe asks on its own connection or socket.
+pub fn ping<F, T>(addr: &str, port: u16, probes: usize, timeout: Duration, probe: F) -> Vec<Reply>
+where
+    F: Fn(SocketAddr) -> T,
+    T: Future<Output = Option<(Duration, Players)>> + Send + 'static,
+{
+    let Some(ip) = check::resolve(addr) else {
+        return Reply::failures(None, probes);
+    };
+
+    let target = SocketAddr::new(ip, port);
+
+    check::fan_out(ip, probes, timeout, |_| {
+        let probe = probe(target);
+
+        async move {
+            let (duration, players) = probe.await?;
+
+            Some(Reply {
+                players: Some(players),
+                ..Reply::new(Pong::Success(duration))
+            })
+        }
+    })
+}
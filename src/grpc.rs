@@ -0,0 +1,242 @@
+use std::{
+    net::{Ipv6Addr, SocketAddr},
+    time::{Duration, Instant},
+};
+
+use bytes::Bytes;
+use egui::{Checkbox, DragValue, Id, TextEdit, TextStyle, Ui, WidgetText};
+use rustls::pki_types::ServerName;
+use serde::{Deserialize, Serialize};
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    net::TcpStream,
+};
+
+use crate::{
+    check::{self, Kind, Target},
+    dns, tls, Pong, Reply,
+};
+
+const PATH: &str = "/grpc.health.v1.Health/Check";
+const SERVING: u64 = 1;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Grpc {
+    pub port: u16,
+    pub service: String,
+    pub tls: bool,
+}
+
+impl Grpc {
+    pub const DEFAULT: Grpc = Grpc {
+        port: 50051,
+        service: String::new(),
+        tls: false,
+    };
+}
+
+impl Kind for Grpc {
+    fn label(&self) -> &'static str {
+        "gRPC"
+    }
+
+    fn ping(&self, target: &Target) -> Vec<Reply> {
+        ping(
+            target.address,
+            self.port,
+            &self.service,
+            self.tls,
+            target.probes,
+            target.timeout,
+        )
+    }
+
+    fn tls(&self, address: &str) -> Option<(String, u16)> {
+        self.tls.then(|| (address.to_owned(), self.port))
+    }
+
+    fn editor(&mut self, ui: &mut Ui, _id: Id, _address: &str, _bind: Option<&str>) {
+        ui.horizontal(|ui| {
+            ui.label("Puerto");
+            ui.add(DragValue::new(&mut self.port).clamp_range(1..=u16::MAX));
+            ui.add(Checkbox::new(&mut self.tls, "TLS"));
+        });
+
+        let service_input = TextEdit::singleline(&mut self.service)
+            .hint_text(WidgetText::italics("Servicio (el servidor entero)".into()))
+            .font(TextStyle::Monospace);
+
+        ui.add(service_input);
+        ui.weak(format!("POST {}", PATH));
+    }
+}
+
+// Only a SERVING answer counts, the round trip covers connecting as well as the call itself.
+pub fn ping(
+    addr: &str,
+    port: u16,
+    service: &str,
+    tls: bool,
+    probes: usize,
+    timeout: Duration,
+) -> Vec<Reply> {
+    let Some(ip) = check::resolve(addr) else {
+        return Reply::failures(None, probes);
+    };
+
+    let target = SocketAddr::new(ip, port);
+
+    let host = match addr.parse::<Ipv6Addr>() {
+        Ok(_) => format!("[{}]", addr),
+        Err(_) => addr.to_owned(),
+    };
+
+    let uri = format!(
+        "{}://{}:{}{}",
+        ["http", "https"][tls as usize],
+        host,
+        port,
+        PATH
+    );
+    let name = tls
+        .then(|| ServerName::try_from(dns::ascii(addr)).ok())
+        .flatten();
+    let message = message(service);
+
+    check::fan_out(ip, probes, timeout, |_| {
+        let uri = uri.clone();
+        let name = name.clone();
+        let message = message.clone();
+
+        async move {
+            let duration = probe(target, name, uri, message).await?;
+            Some(Reply::new(Pong::Success(duration)))
+        }
+    })
+}
+
+async fn probe(
+    target: SocketAddr,
+    name: Option<ServerName<'static>>,
+    uri: String,
+    message: Bytes,
+) -> Option<Duration> {
+    let start = Instant::now();
+    let stream = TcpStream::connect(target).await.ok()?;
+
+    let serving = match name {
+        None => call(stream, &uri, message).await?,
+        Some(name) => {
+            let stream = tls::connector(&[b"h2"])?.connect(name, stream).await.ok()?;
+            call(stream, &uri, message).await?
+        }
+    };
+
+    serving.then(|| start.elapsed())
+}
+
+async fn call<T>(io: T, uri: &str, message: Bytes) -> Option<bool>
+where
+    T: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let (client, connection) = h2::client::handshake(io).await.ok()?;
+    tokio::spawn(connection);
+
+    let request = http::Request::post(uri)
+        .header("content-type", "application/grpc")
+        .header("te", "trailers")
+        .body(())
+        .ok()?;
+
+    let mut client = client.ready().await.ok()?;
+    let (response, mut stream) = client.send_request(request, false).ok()?;
+    stream.send_data(message, true).ok()?;
+
+    let (head, mut body) = response.await.ok()?.into_parts();
+
+    if head.status != http::StatusCode::OK {
+        return None;
+    }
+
+    let mut data = vec![];
+
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk.ok()?;
+        let _ = body.flow_control().release_capacity(chunk.len());
+        data.extend_from_slice(&chunk);
+    }
+
+    // A call that fails straight away carries its status in the headers, with no trailers.
+    let trailers = body.trailers().await.ok()?;
+    let status = trailers
+        .as_ref()
+        .and_then(|trailers| trailers.get("grpc-status"))
+        .or_else(|| head.headers.get("grpc-status"))?;
+
+    Some(status == "0" && status_field(data.get(5..)?) == Some(SERVING))
+}
+
+// `HealthCheckRequest { service = 1 }` inside an uncompressed gRPC frame.
+fn message(service: &str) -> Bytes {
+    let mut message = vec![];
+
+    if !service.is_empty() {
+        message.push(0x0A);
+        write_varint(&mut message, service.len() as u64);
+        message.extend(service.as_bytes());
+    }
+
+    let mut frame = vec![0];
+    frame.extend((message.len() as u32).to_be_bytes());
+    frame.extend(message);
+    frame.into()
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+
+    out.push(value as u8);
+}
+
+// `HealthCheckResponse { status = 1 }`, anything else in the message is skipped.
+fn status_field(mut message: &[u8]) -> Option<u64> {
+    // An empty message is a status left at its default, UNKNOWN.
+    let mut status = Some(0);
+
+    while !message.is_empty() {
+        let key = read_varint(&mut message)?;
+
+        match (key >> 3, key & 0x07) {
+            (1, 0) => status = Some(read_varint(&mut message)?),
+            (_, 0) => {
+                read_varint(&mut message)?;
+            }
+            (_, 2) => {
+                let len = read_varint(&mut message)? as usize;
+                message = message.get(len..)?;
+            }
+            _ => return None,
+        }
+    }
+
+    status
+}
+
+fn read_varint(message: &mut &[u8]) -> Option<u64> {
+    let mut value = 0;
+
+    for shift in (0..64).step_by(7) {
+        let (&byte, rest) = message.split_first()?;
+        *message = rest;
+        value |= ((byte & 0x7F) as u64) << shift;
+
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+
+    None
+}
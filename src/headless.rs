@@ -0,0 +1,675 @@
+use std::{
+    collections::HashMap,
+    fs,
+    io::{self, Write},
+    iter::Peekable,
+    str::Chars,
+    thread,
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use egui::Context;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::{
+    backend::Backend,
+    check::Check,
+    clock::Clock,
+    default_probes,
+    heartbeat::Heartbeats,
+    otlp,
+    policy::{self, Policy, MAX_RETRIES},
+    secrets,
+    store::{self, Store},
+    stream::{self, Streamer},
+    systemd, Canvas, PingWindow, Sample, GROUPS, MAX_PROBES,
+};
+
+const TICK: Duration = Duration::from_millis(100);
+const KEEP: usize = 3600;
+const MIN_INTERVAL: Duration = Duration::from_millis(100);
+const MIN_TIMEOUT: Duration = Duration::from_millis(100);
+const DRAIN: Duration = Duration::from_secs(1);
+
+const USAGE: &str = "\
+Uso: pinga --headless [--config FICHERO] [--check-config] [--output text|ndjson] [--speed FACTOR] [NOMBRE=]DIRECCIÓN...";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum Output {
+    #[default]
+    Text,
+    Ndjson,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Config {
+    #[serde(default)]
+    policies: [Policy; GROUPS.len()],
+
+    #[serde(default)]
+    backend: Backend,
+
+    #[serde(default)]
+    hosts: Vec<Host>,
+
+    // History on disk and the exporters take the same settings as in the app.
+    #[serde(default)]
+    store: Store,
+
+    #[serde(default)]
+    otlp: otlp::Exporter,
+
+    #[serde(default)]
+    stream: Option<String>,
+
+    #[serde(default)]
+    stream_format: stream::Format,
+
+    // Where each field was written, for `validate` to point at (see `lines`).
+    #[serde(skip)]
+    lines: HashMap<String, usize>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Host {
+    #[serde(default)]
+    name: String,
+    address: String,
+
+    #[serde(default)]
+    group: usize,
+
+    #[serde(default)]
+    check: Check,
+
+    #[serde(default = "default_probes")]
+    probes: usize,
+
+    #[serde(default)]
+    policy: Policy,
+}
+
+impl Config {
+    // Each message starts with the line, to go after the file name like a parse error does.
+    fn validate(&self) -> Vec<String> {
+        let mut errors = vec![];
+
+        let mut report = |path: String, shown: String, error: String| {
+            errors.push(format!("{}: {}: {}", self.line(&path), shown, error));
+        };
+
+        for (idx, policy) in self.policies.iter().enumerate() {
+            for (field, error) in check_policy(policy) {
+                let path = format!("policies[{}].{}", idx, field);
+                report(path.clone(), path, error);
+            }
+        }
+
+        for (idx, host) in self.hosts.iter().enumerate() {
+            let path = format!("hosts[{}]", idx);
+            let at = match host.name.is_empty() {
+                true => path.clone(),
+                false => format!("{} («{}»)", path, host.name),
+            };
+
+            let mut report = |field: &str, error: String| {
+                report(
+                    format!("{}.{}", path, field),
+                    format!("{}.{}", at, field),
+                    error,
+                )
+            };
+
+            if host.address.trim().is_empty() {
+                report("address", "no puede estar vacío".into());
+            }
+
+            if host.group >= GROUPS.len() {
+                report(
+                    "group",
+                    format!(
+                        "se esperaba un valor entre 0 y {}, hay {}",
+                        GROUPS.len() - 1,
+                        host.group
+                    ),
+                );
+            }
+
+            if !(1..=MAX_PROBES).contains(&host.probes) {
+                report(
+                    "probes",
+                    format!(
+                        "se esperaba un valor entre 1 y {}, hay {}",
+                        MAX_PROBES, host.probes
+                    ),
+                );
+            }
+
+            for (field, error) in check_policy(&host.policy) {
+                report(&format!("policy.{}", field), error);
+            }
+
+            for (field, error) in check_secrets(&host.check) {
+                report(&format!("check.{}", field), error);
+            }
+        }
+
+        errors
+    }
+
+    // A field left out points at whatever holds it, down to the start of the file.
+    fn line(&self, mut path: &str) -> usize {
+        loop {
+            if let Some(&line) = self.lines.get(path) {
+                return line;
+            }
+
+            match path.rfind(['.', '[']) {
+                Some(end) => path = &path[..end],
+                None => return 1,
+            }
+        }
+    }
+}
+
+impl Output {
+    fn result(self, win: &PingWindow, sample: &Sample) -> String {
+        let frame = stream::json(&win.hostname, &win.address, win.group, sample);
+
+        match self {
+            Output::Ndjson => with_event("result", frame),
+            Output::Text => {
+                let status = match frame["rtt_ms"].as_f64() {
+                    Some(rtt) => format!("{:.2} ms", rtt),
+                    None => "sin respuesta".into(),
+                };
+
+                format!("{} {} {}", sample.time.to_rfc3339(), name(win), status)
+            }
+        }
+    }
+
+    fn change(self, win: &PingWindow, success: bool, now: DateTime<Utc>) -> String {
+        match self {
+            Output::Ndjson => with_event(
+                "state",
+                json!({
+                    "host": win.hostname,
+                    "address": win.address,
+                    "group": win.group + 1,
+                    "time": now.to_rfc3339(),
+                    "success": success,
+                }),
+            ),
+            Output::Text => {
+                let body = ["Ha dejado de responder", "Vuelve a responder"][success as usize];
+                format!("{} {}: {}", now.to_rfc3339(), name(win), body)
+            }
+        }
+    }
+}
+
+pub fn run(args: &[String]) -> i32 {
+    let mut output = Output::default();
+    let mut config = Config::default();
+    let mut check_only = false;
+    let mut clock = Clock::System;
+    let mut errors = vec![];
+    let mut args = args.iter().filter(|arg| *arg != "--headless");
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--output" => match args.next().map(String::as_str) {
+                Some("text") => output = Output::Text,
+                Some("ndjson") => output = Output::Ndjson,
+                _ => return usage("--output admite «text» o «ndjson»"),
+            },
+            "--config" => {
+                let Some(path) = args.next() else {
+                    return usage("--config necesita un fichero");
+                };
+
+                match load(path) {
+                    Ok(loaded) => {
+                        errors.extend(
+                            loaded
+                                .validate()
+                                .into_iter()
+                                .map(|error| format!("{}:{}", path, error)),
+                        );
+
+                        config.hosts.extend(loaded.hosts);
+                        config.policies = loaded.policies;
+                        config.backend = loaded.backend;
+                        config.store = loaded.store;
+                        config.otlp = loaded.otlp;
+                        config.stream = loaded.stream;
+                        config.stream_format = loaded.stream_format;
+                    }
+                    Err(error) => errors.push(error),
+                }
+            }
+            "--check-config" => check_only = true,
+            // Only the probe schedule runs faster, the probes themselves still take real time.
+            "--speed" => match args.next().and_then(|rate| rate.parse::<f64>().ok()) {
+                Some(rate) if rate.is_finite() && rate > 0. => clock = Clock::scaled(rate),
+                _ => return usage("--speed necesita un factor mayor que 0"),
+            },
+            flag if flag.starts_with("--") => {
+                return usage(&format!("Opción desconocida {}", flag))
+            }
+            target => {
+                let (name, address) = target.split_once('=').unwrap_or(("", target));
+
+                if address.trim().is_empty() {
+                    return usage(&format!("«{}» no tiene dirección", target));
+                }
+
+                config.hosts.push(Host {
+                    name: name.into(),
+                    address: address.into(),
+                    group: 0,
+                    check: Check::default(),
+                    probes: default_probes(),
+                    policy: Policy::default(),
+                });
+            }
+        }
+    }
+
+    for error in &errors {
+        eprintln!("{}", error);
+    }
+
+    if !errors.is_empty() {
+        return 1;
+    }
+
+    if check_only {
+        println!("Configuración válida: {} hosts", config.hosts.len());
+        return 0;
+    }
+
+    if config.hosts.is_empty() {
+        return usage("No hay hosts que sondear");
+    }
+
+    let mut canvas = Canvas::new("");
+
+    canvas.windows = config
+        .hosts
+        .into_iter()
+        .map(|host| {
+            let mut win = PingWindow::new(host.name, host.address, None);
+            win.group = host.group;
+            win.check = host.check;
+            win.probes = host.probes;
+            win.policy = host.policy;
+            win.scanning = true;
+
+            // Nothing is plotted here, so old samples are only dead weight.
+            win.history.set_capacity(KEEP);
+            win
+        })
+        .collect::<Vec<_>>();
+
+    let mut canvases = [canvas];
+    let mut store = config.store;
+    let mut otlp = config.otlp;
+    let mut streamer = Streamer::default();
+
+    store.restore(&mut canvases, KEEP);
+
+    if let Some(bind) = &config.stream {
+        streamer.start(bind);
+    }
+
+    let ctx = Context::default();
+    let beats = Heartbeats::new(&clock);
+    let mut stdout = io::stdout().lock();
+    let mut service = systemd::Service::new();
+
+    service.ready();
+
+    loop {
+        // Tell systemd first, flushing may take a while.
+        if service.stopping() {
+            service.stop();
+            store.flush();
+            otlp.flush(&canvases, &clock);
+            streamer.flush(DRAIN);
+            let _ = stdout.flush();
+            return 0;
+        }
+
+        let mut next = Duration::MAX;
+
+        for win in &mut canvases[0].windows {
+            let group = config.policies[win.group].over(policy::DEFAULTS);
+            let settings = win.policy.over(group);
+
+            let last = win.success;
+            let polled = win.history.last().map(|sample| sample.time);
+            next = next.min(win.poll(&ctx, settings, &beats, config.backend, &clock));
+
+            let fresh = win
+                .history
+                .iter()
+                .rev()
+                .take_while(|sample| Some(sample.time) != polled)
+                .count();
+
+            let fresh = &win.history[win.history.len() - fresh..];
+            store.save(store::key(&win.address, &win.check), fresh);
+
+            for sample in fresh {
+                let _ = writeln!(stdout, "{}", output.result(win, sample));
+
+                streamer.publish(
+                    config.stream_format,
+                    &win.hostname,
+                    &win.address,
+                    win.group,
+                    sample,
+                );
+            }
+
+            if let (Some(last), Some(success)) = (last, win.success) {
+                if last != success {
+                    let _ = writeln!(stdout, "{}", output.change(win, success, clock.utc()));
+                }
+            }
+        }
+
+        store.run(&ctx, &canvases, Default::default(), &clock);
+        otlp.export(&canvases, &clock);
+        next = next.min(otlp.remaining(&clock));
+
+        // Broken pipe means whoever was reading is gone, so there is no point in going on.
+        if stdout.flush().is_err() {
+            store.flush();
+            return 0;
+        }
+
+        thread::sleep(clock.real(next).min(TICK).min(service.pet()));
+    }
+}
+
+fn load(path: &str) -> Result<Config, String> {
+    let text = fs::read_to_string(path).map_err(|err| format!("{}: {}", path, err))?;
+    parse(path, &text)
+}
+
+fn parse(path: &str, text: &str) -> Result<Config, String> {
+    // The position comes first so editors can jump straight to it.
+    let mut config = ron::from_str::<Config>(text).map_err(|err| {
+        format!(
+            "{}:{}:{}: {}",
+            path, err.position.line, err.position.col, err.code
+        )
+    })?;
+
+    config.lines = lines(text);
+    Ok(config)
+}
+
+struct Level {
+    path: String,
+    list: bool,
+    item: usize,
+    started: bool,
+    field: Option<String>,
+}
+
+// The line every field and list item starts on, keyed by its path as `validate` spells it
+// (`hosts[0].policy.timeout`). Only ron's punctuation is followed, values are skipped over. It
+// runs on text ron already accepted, so it need not care about malformed input.
+fn lines(text: &str) -> HashMap<String, usize> {
+    let mut lines = HashMap::new();
+    let mut levels = Vec::<Level>::new();
+    let mut chars = text.chars().peekable();
+    let mut line = 1;
+    let mut ident = None::<String>;
+
+    let join = |path: &str, field: &str| match path {
+        "" => field.to_owned(),
+        path => format!("{}.{}", path, field),
+    };
+
+    while let Some(char) = chars.next() {
+        if char == '\n' {
+            line += 1;
+        }
+
+        if char.is_whitespace() {
+            continue;
+        }
+
+        // A value starts here, so it is the next item if inside a list.
+        if !matches!(char, ')' | ']' | '}' | ',' | ':' | '/') {
+            if let Some(level) = levels
+                .last_mut()
+                .filter(|level| level.list && !level.started)
+            {
+                level.started = true;
+                lines.insert(format!("{}[{}]", level.path, level.item), line);
+            }
+        }
+
+        let name = ident.take();
+
+        match char {
+            '/' if chars.peek() == Some(&'/') => {
+                while chars.next_if(|&next| next != '\n').is_some() {}
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                let mut last = ' ';
+
+                for next in chars.by_ref() {
+                    line += (next == '\n') as usize;
+
+                    if last == '*' && next == '/' {
+                        break;
+                    }
+
+                    last = next;
+                }
+            }
+            '"' => skip_string(&mut chars, &mut line, 0),
+            '#' => while chars.next_if(|&next| next != ']').is_some() {},
+            ':' => {
+                if let (Some(name), Some(level)) = (name, levels.last_mut()) {
+                    lines.insert(join(&level.path, &name), line);
+                    level.field = Some(name);
+                }
+            }
+            ',' => match levels.last_mut() {
+                Some(level) if level.list => {
+                    level.item += 1;
+                    level.started = false;
+                }
+                Some(level) => level.field = None,
+                None => {}
+            },
+            '(' | '[' | '{' => {
+                let path = match levels.last() {
+                    None => String::new(),
+                    Some(level) if level.list => format!("{}[{}]", level.path, level.item),
+                    Some(level) => match &level.field {
+                        Some(field) => join(&level.path, field),
+                        None => level.path.clone(),
+                    },
+                };
+
+                levels.push(Level {
+                    path,
+                    list: char == '[',
+                    item: 0,
+                    started: false,
+                    field: None,
+                });
+            }
+            ')' | ']' | '}' => {
+                levels.pop();
+            }
+            // Raw strings, `r"…"` or `r#"…"#`.
+            'r' if matches!(chars.peek(), Some('"' | '#')) => {
+                let mut hashes = 0;
+
+                while chars.next_if_eq(&'#').is_some() {
+                    hashes += 1;
+                }
+
+                chars.next();
+                skip_string(&mut chars, &mut line, hashes);
+            }
+            char if char.is_alphanumeric() || char == '_' => {
+                let mut name = String::from(char);
+
+                while let Some(next) = chars.next_if(|&next| next.is_alphanumeric() || next == '_')
+                {
+                    name.push(next);
+                }
+
+                ident = Some(name);
+            }
+            _ => {}
+        }
+    }
+
+    lines
+}
+
+// Past the closing quote, and as many `#` as the raw string opened with. Plain strings are the
+// ones with escapes.
+fn skip_string(chars: &mut Peekable<Chars>, line: &mut usize, hashes: usize) {
+    while let Some(char) = chars.next() {
+        *line += (char == '\n') as usize;
+
+        match char {
+            '\\' if hashes == 0 => {
+                chars.next();
+            }
+            '"' if (0..hashes).all(|_| chars.next_if_eq(&'#').is_some()) => return,
+            _ => {}
+        }
+    }
+}
+
+// `${…}` is only expanded where each value is used, so a secret may hold any character without
+// touching the config syntax. References are resolved here just to fail before probing.
+fn check_secrets(check: &Check) -> Vec<(&'static str, String)> {
+    let fields = match check {
+        Check::Snmp(snmp) => vec![
+            ("community", &snmp.community),
+            ("auth_password", &snmp.auth_password),
+            ("privacy_password", &snmp.privacy_password),
+        ],
+        Check::Redis(redis) => vec![("login.password", &redis.login.password)],
+        Check::Postgres(postgres) => vec![("login.password", &postgres.login.password)],
+        Check::Mysql(mysql) => vec![("login.password", &mysql.login.password)],
+        _ => vec![],
+    };
+
+    fields
+        .into_iter()
+        .filter_map(|(field, value)| {
+            let (_, error) = secrets::interpolate(value).err()?;
+            Some((field, error))
+        })
+        .collect()
+}
+
+fn check_policy(policy: &Policy) -> Vec<(&'static str, String)> {
+    let limits = [
+        ("interval", policy.interval, MIN_INTERVAL),
+        ("timeout", policy.timeout, MIN_TIMEOUT),
+    ];
+
+    let retries = policy
+        .retries
+        .filter(|&retries| retries > MAX_RETRIES)
+        .map(|retries| {
+            let error = format!(
+                "se esperaba un valor entre 0 y {}, hay {}",
+                MAX_RETRIES, retries
+            );
+
+            ("retries", error)
+        });
+
+    limits
+        .into_iter()
+        .filter_map(|(field, value, min)| match value {
+            Some(value) if value < min => Some((
+                field,
+                format!(
+                    "se esperaba al menos {} ms, hay {} ms",
+                    min.as_millis(),
+                    value.as_millis()
+                ),
+            )),
+            _ => None,
+        })
+        .chain(retries)
+        .collect()
+}
+
+fn usage(error: &str) -> i32 {
+    eprintln!("{}\n{}", error, USAGE);
+    2
+}
+
+fn with_event(event: &str, mut frame: serde_json::Value) -> String {
+    frame["event"] = event.into();
+    frame.to_string()
+}
+
+fn name(win: &PingWindow) -> &str {
+    [&win.hostname, &win.address][win.hostname.is_empty() as usize]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+
+    #[test]
+    fn every_error_points_at_its_line() {
+        let text = r#"// Hosts de prueba
+(
+    hosts: [
+        (address: "10.0.0.1", probes: 0),
+        (
+            name: "web",
+            address: "",
+            group: 9,
+            policy: (
+                timeout: Some((secs: 0, nanos: 0)),
+            ),
+        ),
+        (address: "10.0.0.3", policy: (retries: Some(99))),
+    ],
+)
+"#;
+
+        let config = parse("pinga.ron", text).unwrap();
+
+        assert_eq!(
+            config.validate(),
+            [
+                "4: hosts[0].probes: se esperaba un valor entre 1 y 20, hay 0",
+                "7: hosts[1] («web»).address: no puede estar vacío",
+                "8: hosts[1] («web»).group: se esperaba un valor entre 0 y 4, hay 9",
+                "10: hosts[1] («web»).policy.timeout: se esperaba al menos 100 ms, hay 0 ms",
+                "13: hosts[2].policy.retries: se esperaba un valor entre 0 y 10, hay 99",
+            ]
+        );
+
+        let broken = parse("pinga.ron", "(\n    hosts: [(address: 1)],\n)").err();
+        assert!(broken.is_some_and(|error| error.starts_with("pinga.ron:2:")));
+    }
+}
@@ -0,0 +1,206 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use egui::{Context, DragValue, Id, Ui};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    sync::oneshot,
+};
+
+use crate::{
+    check::{Kind, Target},
+    clock::Clock,
+    worker, Pong, Reply,
+};
+
+const MAX_REQUEST: usize = 8192;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Heartbeat {
+    pub period: Duration,
+}
+
+// Beats arrive on the receiver's own thread, so the clock they are stamped with travels along.
+#[derive(Clone, Default)]
+pub struct Heartbeats {
+    beats: Arc<Mutex<HashMap<String, DateTime<Utc>>>>,
+    clock: Clock,
+}
+
+impl Heartbeats {
+    pub fn new(clock: &Clock) -> Self {
+        Self {
+            beats: Default::default(),
+            clock: clock.clone(),
+        }
+    }
+
+    pub fn beat(&self, token: &str) {
+        let mut beats = self.beats.lock().unwrap();
+        beats.insert(token.into(), self.clock.utc());
+    }
+
+    pub fn pong(&self, token: &str, period: Duration) -> Pong {
+        let beats = self.beats.lock().unwrap();
+
+        let Some(age) = beats
+            .get(token)
+            .and_then(|last| (self.clock.utc() - *last).to_std().ok())
+        else {
+            return Pong::Failure;
+        };
+
+        match age <= period {
+            true => Pong::Success(age),
+            false => Pong::Failure,
+        }
+    }
+}
+
+impl Heartbeat {
+    pub const DEFAULT: Heartbeat = Heartbeat {
+        period: Duration::from_secs(60),
+    };
+}
+
+impl Kind for Heartbeat {
+    fn label(&self) -> &'static str {
+        "Latido"
+    }
+
+    fn ping(&self, target: &Target) -> Vec<Reply> {
+        vec![Reply::new(target.beats.pong(target.address, self.period))]
+    }
+
+    fn editor(&mut self, ui: &mut Ui, _id: Id, address: &str, bind: Option<&str>) {
+        ui.horizontal(|ui| {
+            let mut secs = self.period.as_secs();
+
+            ui.label("Periodo");
+
+            if ui
+                .add(
+                    DragValue::new(&mut secs)
+                        .clamp_range(1..=7 * 24 * 3600)
+                        .suffix(" s"),
+                )
+                .changed()
+            {
+                self.period = Duration::from_secs(secs);
+            }
+        });
+
+        match bind {
+            Some(bind) => ui.monospace(format!("POST http://{}/{}", bind, address)),
+            None => ui.weak("El receptor de latidos está desactivado"),
+        };
+    }
+}
+
+#[derive(Default)]
+pub struct Receiver {
+    bind: Option<String>,
+    error: Arc<Mutex<Option<String>>>,
+    stop: Option<oneshot::Sender<()>>,
+}
+
+impl Receiver {
+    pub fn bind(&self) -> Option<&str> {
+        self.bind.as_deref()
+    }
+
+    pub fn error(&self) -> Option<String> {
+        self.error.lock().unwrap().clone()
+    }
+
+    pub fn start(&mut self, ctx: &Context, bind: &str, beats: &Heartbeats) {
+        let (stop, stopped) = oneshot::channel();
+        let error = Arc::new(Mutex::new(None));
+
+        self.bind = Some(bind.into());
+        self.error = error.clone();
+        self.stop = Some(stop);
+
+        let bind = bind.to_owned();
+        let beats = beats.clone();
+        let ctx = ctx.clone();
+
+        worker::spawn_task(async move {
+            if let Err(err) = serve(&bind, beats, ctx, stopped).await {
+                *error.lock().unwrap() = Some(err.to_string());
+            }
+        });
+    }
+
+    pub fn stop(&mut self) {
+        self.bind = None;
+        self.stop = None;
+        *self.error.lock().unwrap() = None;
+    }
+}
+
+async fn serve(
+    bind: &str,
+    beats: Heartbeats,
+    ctx: Context,
+    mut stopped: oneshot::Receiver<()>,
+) -> std::io::Result<()> {
+    let listener = TcpListener::bind(bind).await?;
+
+    loop {
+        tokio::select! {
+            _ = &mut stopped => return Ok(()),
+            accepted = listener.accept() => {
+                let Ok((stream, _)) = accepted else {
+                    continue;
+                };
+
+                let beats = beats.clone();
+                let ctx = ctx.clone();
+
+                tokio::spawn(async move {
+                    if handle(stream, &beats).await.is_ok() {
+                        ctx.request_repaint();
+                    }
+                });
+            }
+        }
+    }
+}
+
+async fn handle(mut stream: TcpStream, beats: &Heartbeats) -> std::io::Result<()> {
+    let mut request = vec![];
+    let mut buf = [0; 1024];
+
+    while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+        let read = stream.read(&mut buf).await?;
+
+        if read == 0 || request.len() > MAX_REQUEST {
+            break;
+        }
+
+        request.extend_from_slice(&buf[..read]);
+    }
+
+    let request = String::from_utf8_lossy(&request);
+    let mut line = request.lines().next().unwrap_or_default().split(' ');
+
+    let status = match (line.next(), line.next().map(|path| path.trim_matches('/'))) {
+        (Some("GET" | "POST" | "HEAD"), Some(token)) if !token.is_empty() => {
+            beats.beat(token);
+            "200 OK"
+        }
+        _ => "400 Bad Request",
+    };
+
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        status
+    );
+    stream.write_all(response.as_bytes()).await
+}
@@ -0,0 +1,184 @@
+use std::{env, fs, net::IpAddr, path::PathBuf};
+
+use egui::{Context, ScrollArea, Window};
+
+use crate::{PingWindow, FAIL};
+
+#[derive(Clone, Copy)]
+pub enum Source {
+    Hosts,
+    Ssh,
+}
+
+struct Entry {
+    add: bool,
+    name: String,
+    address: String,
+}
+
+#[derive(Default)]
+pub struct Picker {
+    pub open: Option<Source>,
+    entries: Option<Result<Vec<Entry>, String>>,
+}
+
+impl Source {
+    fn title(self) -> &'static str {
+        match self {
+            Source::Hosts => "Importar del fichero hosts",
+            Source::Ssh => "Importar de la configuración SSH",
+        }
+    }
+
+    fn path(self) -> PathBuf {
+        let home = env::var_os("HOME").or_else(|| env::var_os("USERPROFILE"));
+
+        match (self, env::var_os("SystemRoot")) {
+            (Source::Hosts, Some(root)) if cfg!(windows) => {
+                PathBuf::from(root).join(r"System32\drivers\etc\hosts")
+            }
+            (Source::Hosts, _) => PathBuf::from("/etc/hosts"),
+            (Source::Ssh, _) => home
+                .map(PathBuf::from)
+                .unwrap_or_default()
+                .join(".ssh/config"),
+        }
+    }
+
+    fn parse(self, text: &str) -> Vec<Entry> {
+        match self {
+            Source::Hosts => hosts(text),
+            Source::Ssh => ssh(text),
+        }
+    }
+}
+
+pub fn show(ctx: &Context, picker: &mut Picker, windows: &mut Vec<PingWindow>) {
+    let Some(source) = picker.open else {
+        picker.entries = None;
+        return;
+    };
+
+    let mut open = true;
+
+    Window::new(source.title())
+        .open(&mut open)
+        .default_width(320.)
+        .show(ctx, |ui| {
+            let path = source.path();
+            let entries = picker.entries.get_or_insert_with(|| {
+                fs::read_to_string(&path)
+                    .map(|text| source.parse(&text))
+                    .map_err(|err| format!("{}: {}", path.display(), err))
+            });
+
+            let entries = match entries {
+                Ok(entries) => entries,
+                Err(error) => {
+                    ui.colored_label(FAIL, &*error);
+                    return;
+                }
+            };
+
+            if entries.is_empty() {
+                ui.weak("No hay entradas");
+                return;
+            }
+
+            ui.horizontal(|ui| {
+                for (label, add) in [("Todas", true), ("Ninguna", false)] {
+                    if ui.button(label).clicked() {
+                        entries.iter_mut().for_each(|entry| entry.add = add);
+                    }
+                }
+            });
+
+            ScrollArea::vertical().max_height(320.).show(ui, |ui| {
+                for entry in entries.iter_mut() {
+                    let text = format!("{} ({})", entry.name, entry.address);
+                    ui.checkbox(&mut entry.add, text);
+                }
+            });
+
+            ui.separator();
+
+            if ui.button("Añadir").clicked() {
+                let chosen = entries.iter().filter(|entry| entry.add);
+                windows.extend(
+                    chosen.map(|entry| PingWindow::new(&*entry.name, &*entry.address, None)),
+                );
+
+                picker.open = None;
+            }
+        });
+
+    if !open {
+        picker.open = None;
+    }
+}
+
+// Only the canonical name of each line is kept, aliases would just duplicate windows.
+fn hosts(text: &str) -> Vec<Entry> {
+    text.lines()
+        .filter_map(|line| {
+            let line = line.split('#').next().unwrap_or_default();
+            let mut fields = line.split_whitespace();
+            let address = fields.next()?.parse::<IpAddr>().ok()?;
+            let name = fields.next()?;
+
+            Some(Entry {
+                add: !address.is_loopback() && !address.is_multicast(),
+                name: name.into(),
+                address: address.to_string(),
+            })
+        })
+        .collect()
+}
+
+// Every concrete `Host` alias becomes a window, pointed at its `HostName` or at the alias itself.
+// Wildcard patterns only carry defaults, and `Match` blocks are too dynamic to follow.
+fn ssh(text: &str) -> Vec<Entry> {
+    let mut entries = vec![];
+    let mut block = 0..0;
+
+    for line in text.lines() {
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (keyword, value) = line
+            .split_once(|c: char| c.is_whitespace() || c == '=')
+            .unwrap_or((line, ""));
+        let value = value.trim_start_matches(|c: char| c.is_whitespace() || c == '=');
+
+        match &*keyword.to_lowercase() {
+            "host" => {
+                let start = entries.len();
+
+                entries.extend(
+                    value
+                        .split_whitespace()
+                        .filter(|alias| !alias.contains(['*', '?', '!']))
+                        .map(|alias| Entry {
+                            add: true,
+                            name: alias.into(),
+                            address: alias.into(),
+                        }),
+                );
+
+                block = start..entries.len();
+            }
+            "match" => block = entries.len()..entries.len(),
+            "hostname" => {
+                for entry in &mut entries[block.clone()] {
+                    entry.address = value.trim().into();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    entries
+}
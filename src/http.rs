@@ -0,0 +1,373 @@
+use std::{
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
+
+use egui::{Checkbox, Id, TextEdit, TextStyle, Ui, WidgetText};
+use itertools::Itertools;
+use rustls::pki_types::ServerName;
+use serde::{Deserialize, Serialize};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader},
+    net::TcpStream,
+    time,
+};
+
+use crate::{
+    check::{self, Kind, Target},
+    dns, tls, worker, Pong, Reply,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Http {
+    pub url: String,
+    pub reuse: bool,
+}
+
+struct Timing {
+    setup: Option<Duration>,
+    request: Duration,
+    size: usize,
+}
+
+impl Http {
+    pub const DEFAULT: Http = Http {
+        url: String::new(),
+        reuse: false,
+    };
+}
+
+impl Kind for Http {
+    fn label(&self) -> &'static str {
+        "HTTP"
+    }
+
+    fn ping(&self, target: &Target) -> Vec<Reply> {
+        let url = match self.url.trim() {
+            "" => format!("http://{}/", target.address),
+            url => url.to_owned(),
+        };
+
+        ping(&url, self.reuse, target.probes, target.timeout)
+    }
+
+    fn tls(&self, _address: &str) -> Option<(String, u16)> {
+        tls::endpoint(self.url.trim(), "https")
+    }
+
+    fn editor(&mut self, ui: &mut Ui, _id: Id, address: &str, _bind: Option<&str>) {
+        let hint = format!("URL (http://{}/)", address);
+        let url_input = TextEdit::singleline(&mut self.url)
+            .hint_text(WidgetText::italics(hint.into()))
+            .font(TextStyle::Monospace);
+
+        ui.add(url_input);
+        ui.add(Checkbox::new(
+            &mut self.reuse,
+            "Alternar con conexiones reutilizadas",
+        ))
+        .on_hover_text("Cada segunda sonda de la ronda repite sobre la conexión anterior");
+    }
+}
+
+// The round trip is the request alone, from sending it to the end of the body. Setting up the
+// connection (DNS, TCP and TLS) is kept apart, and left out on a reused one. Any 2xx or 3xx
+// counts as an answer.
+pub fn ping(url: &str, reuse: bool, probes: usize, timeout: Duration) -> Vec<Reply> {
+    let Ok(uri) = url.parse::<http::Uri>() else {
+        return Reply::failures(None, probes);
+    };
+
+    let secure = match uri.scheme_str() {
+        Some("http") => false,
+        Some("https") => true,
+        _ => return Reply::failures(None, probes),
+    };
+
+    let Some(host) = uri
+        .host()
+        .map(|host| host.trim_matches(['[', ']']).to_owned())
+    else {
+        return Reply::failures(None, probes);
+    };
+
+    let resolving = Instant::now();
+
+    let Some(ip) = check::resolve(&host) else {
+        return Reply::failures(None, probes);
+    };
+
+    let resolved = resolving.elapsed();
+    let failures = Reply::failures(Some(ip), probes);
+    let port = uri.port_u16().unwrap_or([80, 443][secure as usize]);
+    let target = SocketAddr::new(ip, port);
+
+    let name = secure
+        .then(|| ServerName::try_from(dns::ascii(&host)).ok())
+        .flatten();
+
+    let authority = uri
+        .authority()
+        .map_or(host, |authority| authority.to_string());
+    let path = uri.path_and_query().map_or("/", |path| path.as_str());
+    let request = format!(
+        "GET {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: pinga\r\nAccept: */*\r\n\r\n",
+        path, authority
+    );
+
+    worker::block_on(async move {
+        // With `reuse` probes go in pairs over one connection, fresh first and kept alive second.
+        let pair = [1, 2][reuse as usize];
+        let sessions = (0..probes)
+            .step_by(pair)
+            .map(|first| {
+                let count = pair.min(probes - first);
+                let name = name.clone();
+                let request = request.clone();
+
+                let session = tokio::spawn(async move {
+                    let session = session(target, name, &request, count, resolved);
+                    time::timeout(timeout * count as u32, session).await
+                });
+
+                (count, session)
+            })
+            .collect_vec();
+
+        let mut timings = vec![];
+
+        for (count, session) in sessions {
+            let mut session = match session.await {
+                Ok(Ok(session)) => session,
+                _ => vec![],
+            };
+
+            session.resize_with(count, || None);
+            timings.extend(session);
+        }
+
+        failures
+            .into_iter()
+            .zip(timings)
+            .map(|(reply, timing)| match timing {
+                Some(timing) => Reply {
+                    pong: Pong::Success(timing.request),
+                    size: timing.size,
+                    setup: timing.setup,
+                    ..reply
+                },
+                None => reply,
+            })
+            .collect()
+    })
+}
+
+// A server that will not keep the connection open gets a fresh one for the rest. Whatever is
+// left after a failure counts as lost.
+async fn session(
+    target: SocketAddr,
+    name: Option<ServerName<'static>>,
+    request: &str,
+    count: usize,
+    resolved: Duration,
+) -> Vec<Option<Timing>> {
+    let mut timings = vec![];
+
+    while timings.len() < count {
+        let left = count - timings.len();
+        let start = Instant::now();
+
+        let Ok(stream) = TcpStream::connect(target).await else {
+            break;
+        };
+
+        let done = match &name {
+            None => requests(stream, request, left, resolved + start.elapsed()).await,
+            Some(name) => {
+                let Some(connector) = tls::connector(&[b"http/1.1"]) else {
+                    break;
+                };
+
+                let Ok(stream) = connector.connect(name.clone(), stream).await else {
+                    break;
+                };
+
+                requests(stream, request, left, resolved + start.elapsed()).await
+            }
+        };
+
+        let failed = done.last().is_none_or(Option::is_none);
+        timings.extend(done);
+
+        if failed {
+            break;
+        }
+    }
+
+    timings
+}
+
+// Ends with `None` if a request failed, or early without it if the server closed the connection.
+async fn requests<T>(stream: T, request: &str, count: usize, setup: Duration) -> Vec<Option<Timing>>
+where
+    T: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut stream = BufReader::new(stream);
+    let mut timings = vec![];
+
+    for sent in 0..count {
+        let start = Instant::now();
+
+        let Some((size, open)) = exchange(&mut stream, request).await else {
+            timings.push(None);
+            break;
+        };
+
+        timings.push(Some(Timing {
+            setup: (sent == 0).then_some(setup),
+            request: start.elapsed(),
+            size,
+        }));
+
+        if !open {
+            break;
+        }
+    }
+
+    timings
+}
+
+// The body is read to the end so the connection can take another request. Returns its size and
+// whether the server is willing to keep the connection open.
+async fn exchange<T>(stream: &mut BufReader<T>, request: &str) -> Option<(usize, bool)>
+where
+    T: AsyncRead + AsyncWrite + Unpin,
+{
+    stream.get_mut().write_all(request.as_bytes()).await.ok()?;
+
+    let mut line = String::new();
+    stream.read_line(&mut line).await.ok()?;
+
+    let status = line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|status| status.parse::<u16>().ok())?;
+
+    let mut length = None;
+    let mut chunked = false;
+    let mut open = line.starts_with("HTTP/1.1");
+
+    loop {
+        line.clear();
+        stream.read_line(&mut line).await.ok()?;
+
+        let header = line.trim_end();
+
+        if header.is_empty() {
+            break;
+        }
+
+        let (name, value) = header.split_once(':')?;
+        let value = value.trim().to_ascii_lowercase();
+
+        match name.trim().to_ascii_lowercase().as_str() {
+            "content-length" => length = Some(value.parse::<usize>().ok()?),
+            "transfer-encoding" => chunked = value.ends_with("chunked"),
+            "connection" => open = value != "close",
+            _ => {}
+        }
+    }
+
+    // These never carry a body, whatever the headers say.
+    let bodiless = status < 200 || status == 204 || status == 304;
+
+    let size = match (chunked, length) {
+        _ if bodiless => 0,
+        (true, _) => chunks(stream).await?,
+        (false, Some(length)) => {
+            let mut body = vec![0; length];
+            stream.read_exact(&mut body).await.ok()?;
+            length
+        }
+        // Without a length the body runs until the server hangs up.
+        (false, None) => {
+            open = false;
+            stream.read_to_end(&mut vec![]).await.ok()?
+        }
+    };
+
+    (200..400).contains(&status).then_some((size, open))
+}
+
+async fn chunks<T: AsyncRead + Unpin>(stream: &mut BufReader<T>) -> Option<usize> {
+    let mut size = 0;
+    let mut line = String::new();
+
+    loop {
+        line.clear();
+        stream.read_line(&mut line).await.ok()?;
+
+        let length = line.trim_end().split(';').next()?;
+        let length = usize::from_str_radix(length.trim(), 16).ok()?;
+
+        if length == 0 {
+            break;
+        }
+
+        let mut chunk = vec![0; length + 2];
+        stream.read_exact(&mut chunk).await.ok()?;
+        size += length;
+    }
+
+    // Trailers, if any, up to the closing blank line.
+    loop {
+        line.clear();
+
+        if stream.read_line(&mut line).await.ok()? == 0 || line.trim_end().is_empty() {
+            return Some(size);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        thread,
+        time::Duration,
+    };
+
+    use super::ping;
+    use crate::Pong;
+
+    #[test]
+    fn no_content_keeps_the_connection() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/healthz", listener.local_addr().unwrap());
+
+        // Answers every request on the first connection, then hangs up on any other.
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut stream = stream;
+            let mut line = String::new();
+
+            while reader.read_line(&mut line).is_ok_and(|read| read > 0) {
+                if line == "\r\n" {
+                    let _ = stream.write_all(b"HTTP/1.1 204 No Content\r\n\r\n");
+                }
+
+                line.clear();
+            }
+        });
+
+        let replies = ping(&url, true, 2, Duration::from_secs(2));
+
+        assert!(replies
+            .iter()
+            .all(|reply| matches!(reply.pong, Pong::Success(_)) && reply.size == 0));
+        assert!(replies[0].setup.is_some());
+        assert_eq!(replies[1].setup, None);
+    }
+}
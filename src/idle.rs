@@ -0,0 +1,134 @@
+use std::time::{Duration, Instant};
+
+use egui::{Align2, Context, Window};
+
+use crate::{clock::Clock, Canvas};
+
+pub struct Idle {
+    last_input: Instant,
+    paused: Vec<Instant>,
+}
+
+impl Default for Idle {
+    fn default() -> Self {
+        Self::new(&Clock::System)
+    }
+}
+
+impl Idle {
+    pub fn new(clock: &Clock) -> Self {
+        Self {
+            last_input: clock.now(),
+            paused: vec![],
+        }
+    }
+
+    pub fn check(
+        &mut self,
+        ctx: &Context,
+        limit: Option<Duration>,
+        canvases: &mut [Canvas],
+        clock: &Clock,
+    ) -> bool {
+        if ctx.input(|input| !input.events.is_empty()) {
+            self.last_input = clock.now();
+        }
+
+        let Some(limit) = limit else {
+            return false;
+        };
+
+        if !self.paused.is_empty() || clock.since(self.last_input) < limit {
+            return false;
+        }
+
+        for win in canvases.iter_mut().flat_map(|canvas| &mut canvas.windows) {
+            if win.scanning {
+                win.scanning = false;
+                win.success = None;
+                self.paused.push(win.ctime);
+            }
+        }
+
+        !self.paused.is_empty()
+    }
+}
+
+pub fn show(ctx: &Context, idle: &mut Idle, canvases: &mut [Canvas], clock: &Clock) {
+    if idle.paused.is_empty() {
+        return;
+    }
+
+    Window::new("Sondeo pausado")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(Align2::CENTER_TOP, [0., 48.])
+        .show(ctx, |ui| {
+            ui.label(format!(
+                "Se ha pausado el sondeo de {} hosts por inactividad",
+                idle.paused.len()
+            ));
+
+            if ui.button("Reanudar").clicked() {
+                for win in canvases.iter_mut().flat_map(|canvas| &mut canvas.windows) {
+                    win.scanning |= idle.paused.contains(&win.ctime);
+                }
+
+                idle.paused.clear();
+                idle.last_input = clock.now();
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use egui::Context;
+
+    use super::Idle;
+    use crate::{clock::Clock, Canvas, PingWindow};
+
+    const LIMIT: Duration = Duration::from_secs(600);
+
+    fn canvases() -> [Canvas; 1] {
+        let mut canvas = Canvas::new("Prueba");
+        let mut scanning = PingWindow::new("scanning", "127.0.0.1", None);
+        scanning.scanning = true;
+
+        canvas.windows.push(scanning);
+        canvas
+            .windows
+            .push(PingWindow::new("stopped", "127.0.0.2", None));
+        [canvas]
+    }
+
+    #[test]
+    fn stops_scanning_windows_after_the_limit() {
+        let clock = Clock::manual();
+        let ctx = Context::default();
+        let mut canvases = canvases();
+        let mut idle = Idle::new(&clock);
+
+        clock.advance(LIMIT - Duration::from_secs(1));
+        assert!(!idle.check(&ctx, Some(LIMIT), &mut canvases, &clock));
+        assert!(canvases[0].windows[0].scanning);
+
+        clock.advance(Duration::from_secs(1));
+        assert!(idle.check(&ctx, Some(LIMIT), &mut canvases, &clock));
+        assert!(!canvases[0].windows[0].scanning);
+        assert_eq!(idle.paused, [canvases[0].windows[0].ctime]);
+    }
+
+    #[test]
+    fn never_stops_without_a_limit() {
+        let clock = Clock::manual();
+        let ctx = Context::default();
+        let mut canvases = canvases();
+        let mut idle = Idle::new(&clock);
+
+        clock.advance(LIMIT * 10);
+        assert!(!idle.check(&ctx, None, &mut canvases, &clock));
+        assert!(canvases[0].windows[0].scanning);
+    }
+}
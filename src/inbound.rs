@@ -0,0 +1,99 @@
+use std::{
+    collections::HashMap,
+    io,
+    net::{IpAddr, UdpSocket},
+    sync::{Arc, Mutex},
+    thread,
+};
+
+use chrono::{DateTime, Utc};
+use egui::Context;
+use socket2::{Domain, Protocol, Socket, Type};
+
+const ECHO_REQUEST_V4: u8 = 8;
+const ECHO_REQUEST_V6: u8 = 128;
+
+#[derive(Clone, Copy)]
+pub struct Peer {
+    pub count: u64,
+    pub last: DateTime<Utc>,
+}
+
+#[derive(Default)]
+pub struct Inbound {
+    peers: Arc<Mutex<HashMap<IpAddr, Peer>>>,
+    error: Arc<Mutex<Option<String>>>,
+    started: bool,
+}
+
+impl Inbound {
+    pub fn start(&mut self, ctx: &Context) {
+        if self.started {
+            return;
+        }
+
+        self.started = true;
+
+        for (domain, protocol) in [
+            (Domain::IPV4, Protocol::ICMPV4),
+            (Domain::IPV6, Protocol::ICMPV6),
+        ] {
+            let peers = self.peers.clone();
+            let error = self.error.clone();
+            let ctx = ctx.clone();
+
+            thread::spawn(move || {
+                if let Err(err) = listen(domain, protocol, &peers, &ctx) {
+                    *error.lock().unwrap() = Some(err.to_string());
+                }
+            });
+        }
+    }
+
+    pub fn peer(&self, ip: IpAddr) -> Option<Peer> {
+        self.peers.lock().unwrap().get(&ip).copied()
+    }
+
+    pub fn error(&self) -> Option<String> {
+        self.error.lock().unwrap().clone()
+    }
+}
+
+fn listen(
+    domain: Domain,
+    protocol: Protocol,
+    peers: &Mutex<HashMap<IpAddr, Peer>>,
+    ctx: &Context,
+) -> io::Result<()> {
+    let socket = UdpSocket::from(Socket::new(domain, Type::RAW, Some(protocol))?);
+    let mut buf = [0; 1500];
+
+    loop {
+        let (len, from) = socket.recv_from(&mut buf)?;
+        let packet = &buf[..len];
+
+        // Raw IPv4 sockets hand over the IP header too, IPv6 ones don't.
+        let request = match from.ip() {
+            IpAddr::V4(_) => {
+                let header = (packet.first().copied().unwrap_or_default() & 0x0F) as usize * 4;
+                packet.get(header) == Some(&ECHO_REQUEST_V4)
+            }
+            IpAddr::V6(_) => packet.first() == Some(&ECHO_REQUEST_V6),
+        };
+
+        if !request {
+            continue;
+        }
+
+        let mut peers = peers.lock().unwrap();
+
+        let peer = peers.entry(from.ip()).or_insert(Peer {
+            count: 0,
+            last: Utc::now(),
+        });
+
+        peer.count += 1;
+        peer.last = Utc::now();
+        ctx.request_repaint();
+    }
+}
@@ -0,0 +1,110 @@
+use chrono::{DateTime, Local, Utc};
+use egui::{Context, RichText, ScrollArea, SidePanel};
+
+use crate::{clock::Clock, FAIL, PASS};
+
+const MAX_NOTICES: usize = 500;
+
+struct Notice {
+    time: DateTime<Utc>,
+    host: String,
+    success: bool,
+    alerted: bool,
+    acked: bool,
+}
+
+#[derive(Default)]
+pub struct Inbox {
+    pub open: bool,
+    notices: Vec<Notice>,
+}
+
+impl Inbox {
+    pub fn push(&mut self, host: &str, success: bool, alerted: bool, clock: &Clock) {
+        self.notices.push(Notice {
+            time: clock.utc(),
+            host: host.to_owned(),
+            success,
+            alerted,
+            acked: false,
+        });
+
+        if self.notices.len() > MAX_NOTICES {
+            self.notices.remove(0);
+        }
+    }
+
+    pub fn unread(&self) -> usize {
+        self.notices.iter().filter(|notice| !notice.acked).count()
+    }
+}
+
+pub fn show(ctx: &Context, inbox: &mut Inbox) {
+    if !inbox.open {
+        return;
+    }
+
+    SidePanel::right("inbox")
+        .default_width(280.)
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.strong("Notificaciones");
+
+                if ui.small_button("✖").clicked() {
+                    inbox.open = false;
+                }
+            });
+
+            ui.horizontal(|ui| {
+                if ui.button("Marcar todo como visto").clicked() {
+                    for notice in &mut inbox.notices {
+                        notice.acked = true;
+                    }
+                }
+
+                if ui.button("Vaciar").clicked() {
+                    inbox.notices.clear();
+                }
+            });
+
+            ui.separator();
+
+            if inbox.notices.is_empty() {
+                ui.weak("Sin notificaciones");
+            }
+
+            ScrollArea::vertical().show(ui, |ui| {
+                for notice in inbox.notices.iter_mut().rev() {
+                    let time = notice.time.with_timezone(&Local).format("%F %H:%M:%S");
+                    let what =
+                        ["Ha dejado de responder", "Vuelve a responder"][notice.success as usize];
+                    let color = [FAIL, PASS][notice.success as usize];
+
+                    ui.horizontal(|ui| {
+                        ui.colored_label(color, "⏺");
+
+                        let host = match notice.acked {
+                            true => RichText::new(&notice.host),
+                            false => RichText::new(&notice.host).strong(),
+                        };
+
+                        ui.label(host);
+
+                        if notice.alerted {
+                            ui.label("🔔").on_hover_text("Se envió un aviso");
+                        }
+                    });
+
+                    ui.horizontal(|ui| {
+                        ui.weak(format!("{} · {}", time, what));
+
+                        if !notice.acked && ui.small_button("Visto").clicked() {
+                            notice.acked = true;
+                        }
+                    });
+
+                    ui.separator();
+                }
+            });
+        });
+}
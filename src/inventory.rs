@@ -0,0 +1,235 @@
+use std::{fs, path::Path};
+
+use egui::{
+    Button, Color32, Context, Grid, ScrollArea, Stroke, TextEdit, TextStyle, WidgetText, Window,
+};
+use serde_yaml::Value;
+
+use crate::{PingWindow, FAIL, GROUPS};
+
+struct Host {
+    name: String,
+    address: String,
+    group: usize,
+}
+
+// Every Ansible group is sent to a PingA group, or left out with `None`.
+#[derive(Default)]
+struct Inventory {
+    groups: Vec<(String, Option<usize>)>,
+    hosts: Vec<Host>,
+}
+
+#[derive(Default)]
+pub struct Import {
+    pub open: bool,
+    path: String,
+    inventory: Option<Result<Inventory, String>>,
+}
+
+impl Inventory {
+    // A host listed under several groups keeps the first one, later entries can only fill in
+    // its `ansible_host`.
+    fn add(&mut self, group: &str, name: &str, address: Option<&str>) {
+        let name = strip_port(name);
+        let address = address.map(|address| address.trim_matches(['"', '\'']));
+
+        if let Some(host) = self.hosts.iter_mut().find(|host| host.name == name) {
+            if let Some(address) = address {
+                host.address = address.into();
+            }
+
+            return;
+        }
+
+        let group = match self.groups.iter().position(|(known, _)| known == group) {
+            Some(idx) => idx,
+            None => {
+                let target = self.groups.len() % GROUPS.len();
+                self.groups.push((group.into(), Some(target)));
+                self.groups.len() - 1
+            }
+        };
+
+        self.hosts.push(Host {
+            name: name.into(),
+            address: address.unwrap_or(name).into(),
+            group,
+        });
+    }
+}
+
+pub fn show(ctx: &Context, import: &mut Import, windows: &mut Vec<PingWindow>) {
+    let mut open = import.open;
+
+    Window::new("Importar inventario de Ansible")
+        .open(&mut open)
+        .default_width(360.)
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                let path_input = TextEdit::singleline(&mut import.path)
+                    .hint_text(WidgetText::italics("Inventario (INI o YAML)".into()))
+                    .font(TextStyle::Monospace);
+
+                ui.add(path_input);
+
+                if ui.button("Leer").clicked() {
+                    import.inventory = Some(read(import.path.trim()));
+                }
+            });
+
+            let inventory = match &mut import.inventory {
+                None => return,
+                Some(Err(error)) => {
+                    ui.colored_label(FAIL, &*error);
+                    return;
+                }
+                Some(Ok(inventory)) => inventory,
+            };
+
+            if inventory.hosts.is_empty() {
+                ui.weak("No hay hosts");
+                return;
+            }
+
+            ScrollArea::vertical().max_height(320.).show(ui, |ui| {
+                Grid::new("inventory_groups")
+                    .num_columns(2)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (idx, (name, target)) in inventory.groups.iter_mut().enumerate() {
+                            let hosts = inventory.hosts.iter().filter(|host| host.group == idx);
+
+                            ui.label(format!("{} ({})", name, hosts.count()));
+
+                            ui.horizontal(|ui| {
+                                for (group, color) in GROUPS.into_iter().enumerate() {
+                                    let width = [0.5, 2.][(*target == Some(group)) as usize];
+                                    let stroke = Stroke::new(width, Color32::BLACK);
+                                    let button = Button::new("     ").fill(color).stroke(stroke);
+
+                                    if ui.add(button).clicked() {
+                                        *target = Some(group);
+                                    }
+                                }
+
+                                if ui.selectable_label(target.is_none(), "Omitir").clicked() {
+                                    *target = None;
+                                }
+                            });
+
+                            ui.end_row();
+                        }
+                    });
+            });
+
+            ui.separator();
+
+            if ui.button("Añadir").clicked() {
+                let groups = &inventory.groups;
+
+                windows.extend(inventory.hosts.iter().filter_map(|host| {
+                    let mut win = PingWindow::new(&*host.name, &*host.address, None);
+                    win.group = groups[host.group].1?;
+                    Some(win)
+                }));
+
+                import.inventory = None;
+                import.open = false;
+            }
+        });
+
+    import.open &= open;
+}
+
+// Inventories without a YAML extension are still tried as YAML first, INI never parses to a
+// mapping.
+fn read(path: &str) -> Result<Inventory, String> {
+    let text = fs::read_to_string(path).map_err(|err| format!("{}: {}", path, err))?;
+    let extension = Path::new(path).extension().and_then(|ext| ext.to_str());
+
+    match serde_yaml::from_str::<Value>(&text) {
+        Ok(value @ Value::Mapping(_)) => Ok(yaml(&value)),
+        Err(err) if matches!(extension, Some("yml" | "yaml")) => Err(format!("{}: {}", path, err)),
+        _ => Ok(ini(&text)),
+    }
+}
+
+// `:vars` and `:children` sections are skipped, the hosts they refer to are listed elsewhere.
+fn ini(text: &str) -> Inventory {
+    let mut inventory = Inventory::default();
+    let mut section = Some("ungrouped".to_owned());
+
+    for line in text.lines() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+
+        if line.is_empty() || line.starts_with(';') {
+            continue;
+        }
+
+        if let Some(header) = line
+            .strip_prefix('[')
+            .and_then(|line| line.strip_suffix(']'))
+        {
+            section = (!header.contains(':')).then(|| header.trim().to_owned());
+            continue;
+        }
+
+        let Some(group) = &section else {
+            continue;
+        };
+
+        let mut fields = line.split_whitespace();
+        let Some(name) = fields.next() else {
+            continue;
+        };
+
+        let address = fields.find_map(|field| field.strip_prefix("ansible_host="));
+        inventory.add(group, name, address);
+    }
+
+    inventory
+}
+
+fn yaml(value: &Value) -> Inventory {
+    let mut inventory = Inventory::default();
+
+    if let Value::Mapping(groups) = value {
+        for (name, group) in groups {
+            walk(&mut inventory, name.as_str().unwrap_or_default(), group);
+        }
+    }
+
+    inventory
+}
+
+fn walk(inventory: &mut Inventory, name: &str, group: &Value) {
+    if let Some(Value::Mapping(hosts)) = group.get("hosts") {
+        for (host, vars) in hosts {
+            let Some(host) = host.as_str() else {
+                continue;
+            };
+
+            let address = vars.get("ansible_host").and_then(Value::as_str);
+            inventory.add(name, host, address);
+        }
+    }
+
+    if let Some(Value::Mapping(children)) = group.get("children") {
+        for (child, group) in children {
+            walk(inventory, child.as_str().unwrap_or_default(), group);
+        }
+    }
+}
+
+// `host:2222` is the SSH port, but an IPv6 address is full of colons.
+fn strip_port(name: &str) -> &str {
+    match name.split_once(':') {
+        Some((host, port))
+            if !port.is_empty() && port.bytes().all(|byte| byte.is_ascii_digit()) =>
+        {
+            host
+        }
+        _ => name,
+    }
+}
@@ -0,0 +1,187 @@
+use std::{
+    ffi::CString,
+    io,
+    net::{IpAddr, Ipv6Addr},
+    time::Duration,
+};
+
+use crate::{backend::Backend, dns, do_ping, Pong, Reply};
+
+#[derive(Clone, Copy, Default)]
+pub struct Report {
+    pub v4: Option<bool>,
+    pub v6: Option<bool>,
+    pub hop_limit: Option<u8>,
+    pub flow_label: Option<u32>,
+}
+
+impl Report {
+    pub fn v6_broken(&self) -> bool {
+        self.v4 == Some(true) && self.v6 == Some(false)
+    }
+}
+
+struct Echo {
+    hop_limit: Option<u8>,
+    flow_label: Option<u32>,
+}
+
+pub fn scope_id(interface: &str) -> u32 {
+    let Ok(name) = CString::new(interface) else {
+        return 0;
+    };
+
+    #[cfg(unix)]
+    return unsafe { libc::if_nametoindex(name.as_ptr()) };
+
+    #[cfg(not(unix))]
+    return {
+        let _ = name;
+        0
+    };
+}
+
+pub fn diagnose(addr: &str, interface: &str, timeout: Duration, backend: Backend) -> Report {
+    let lookup = dns::lookup(addr).unwrap_or_default();
+    let v4 = lookup.iter().find(|ip| ip.is_ipv4());
+    let v6 = lookup.iter().find_map(|ip| match ip {
+        IpAddr::V6(ip) => Some(*ip),
+        IpAddr::V4(_) => None,
+    });
+
+    let mut report = Report {
+        v4: v4.map(|ip| reachable(&ip.to_string(), interface, timeout, backend)),
+        ..Report::default()
+    };
+
+    let Some(v6) = v6 else {
+        return report;
+    };
+
+    match echo(v6, scope_id(interface), timeout) {
+        Ok(Some(echo)) => {
+            report.v6 = Some(true);
+            report.hop_limit = echo.hop_limit;
+            report.flow_label = echo.flow_label;
+        }
+        Ok(None) => report.v6 = Some(false),
+        Err(_) => report.v6 = Some(reachable(&v6.to_string(), interface, timeout, backend)),
+    }
+
+    report
+}
+
+fn reachable(addr: &str, interface: &str, timeout: Duration, backend: Backend) -> bool {
+    matches!(
+        do_ping(addr, 1, timeout, interface, backend)[..],
+        [Reply {
+            pong: Pong::Success(_),
+            ..
+        }]
+    )
+}
+
+#[cfg(target_os = "linux")]
+fn echo(ip: Ipv6Addr, scope: u32, timeout: Duration) -> io::Result<Option<Echo>> {
+    use std::{mem, os::fd::AsRawFd, ptr, time::Instant};
+
+    use libc::{c_int, c_void, iovec, msghdr, socklen_t, IPPROTO_IPV6};
+    use socket2::{Domain, Protocol, SockAddr, Socket, Type};
+
+    const ECHO_REQUEST: u8 = 128;
+    const ECHO_REPLY: u8 = 129;
+
+    let socket = Socket::new(Domain::IPV6, Type::RAW, Some(Protocol::ICMPV6))?;
+    let fd = socket.as_raw_fd();
+
+    for option in [libc::IPV6_RECVHOPLIMIT, libc::IPV6_FLOWINFO] {
+        let on: c_int = 1;
+        let value = &on as *const c_int as *const c_void;
+        let len = mem::size_of::<c_int>() as socklen_t;
+
+        if unsafe { libc::setsockopt(fd, IPPROTO_IPV6, option, value, len) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+
+    let ident = rand::random::<u16>().to_be_bytes();
+    let request = [ECHO_REQUEST, 0, 0, 0, ident[0], ident[1], 0, 1];
+    let target = SockAddr::from(std::net::SocketAddrV6::new(ip, 0, 0, scope));
+
+    let start = Instant::now();
+    socket.send_to(&request, &target)?;
+
+    loop {
+        let Some(remaining) = timeout
+            .checked_sub(start.elapsed())
+            .filter(|remaining| !remaining.is_zero())
+        else {
+            return Ok(None);
+        };
+
+        socket.set_read_timeout(Some(remaining))?;
+
+        let mut buf = [0u8; 1500];
+        let mut control = [0u8; 128];
+
+        let mut iov = iovec {
+            iov_base: buf.as_mut_ptr() as *mut c_void,
+            iov_len: buf.len(),
+        };
+
+        let mut msg: msghdr = unsafe { mem::zeroed() };
+        msg.msg_iov = &mut iov;
+        msg.msg_iovlen = 1;
+        msg.msg_control = control.as_mut_ptr() as *mut c_void;
+        msg.msg_controllen = control.len() as _;
+
+        let len = unsafe { libc::recvmsg(fd, &mut msg, 0) };
+
+        if len < 0 {
+            return match io::Error::last_os_error() {
+                err if err.kind() == io::ErrorKind::WouldBlock => Ok(None),
+                err if err.kind() == io::ErrorKind::TimedOut => Ok(None),
+                err => Err(err),
+            };
+        }
+
+        let packet = &buf[..len as usize];
+
+        if packet.len() < 8 || packet[0] != ECHO_REPLY || packet[4..6] != ident {
+            continue;
+        }
+
+        let mut echo = Echo {
+            hop_limit: None,
+            flow_label: None,
+        };
+
+        unsafe {
+            let mut cmsg = libc::CMSG_FIRSTHDR(&msg);
+
+            while !cmsg.is_null() {
+                let data = libc::CMSG_DATA(cmsg);
+
+                match ((*cmsg).cmsg_level, (*cmsg).cmsg_type) {
+                    (IPPROTO_IPV6, libc::IPV6_HOPLIMIT) => {
+                        echo.hop_limit = Some(ptr::read_unaligned(data as *const c_int) as u8);
+                    }
+                    (IPPROTO_IPV6, libc::IPV6_FLOWINFO) => {
+                        let info = u32::from_be(ptr::read_unaligned(data as *const u32));
+                        echo.flow_label = Some(info & 0xF_FFFF);
+                    }
+                    _ => {}
+                }
+
+                cmsg = libc::CMSG_NXTHDR(&msg, cmsg);
+            }
+        }
+
+        return Ok(Some(echo));
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn echo(_: Ipv6Addr, _: u32, _: Duration) -> io::Result<Option<Echo>> {
+    Err(io::ErrorKind::Unsupported.into())
+}
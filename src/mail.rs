@@ -0,0 +1,152 @@
+use std::{
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
+
+use egui::{Checkbox, DragValue, Id, TextEdit, TextStyle, Ui, WidgetText};
+use rustls::pki_types::ServerName;
+use serde::{Deserialize, Serialize};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, BufReader},
+    net::TcpStream,
+};
+
+use crate::{
+    check::{self, Kind, Target},
+    dns, tls, Pong, Reply,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Mail {
+    pub port: u16,
+    pub tls: bool,
+    pub expect: Option<String>,
+}
+
+impl Mail {
+    pub const DEFAULT: Mail = Mail {
+        port: 25,
+        tls: false,
+        expect: None,
+    };
+}
+
+impl Kind for Mail {
+    fn label(&self) -> &'static str {
+        "Correo"
+    }
+
+    fn ping(&self, target: &Target) -> Vec<Reply> {
+        ping(
+            target.address,
+            self.port,
+            self.tls,
+            self.expect.as_deref(),
+            target.probes,
+            target.timeout,
+        )
+    }
+
+    fn tls(&self, address: &str) -> Option<(String, u16)> {
+        self.tls.then(|| (address.to_owned(), self.port))
+    }
+
+    fn editor(&mut self, ui: &mut Ui, _id: Id, _address: &str, _bind: Option<&str>) {
+        ui.horizontal(|ui| {
+            ui.label("Puerto");
+            ui.add(DragValue::new(&mut self.port).clamp_range(1..=u16::MAX));
+        });
+
+        ui.add(Checkbox::new(
+            &mut self.tls,
+            "TLS implícito (465, 993, 995)",
+        ));
+
+        let mut matching = self.expect.is_some();
+
+        if ui
+            .checkbox(&mut matching, "Exigir prefijo del saludo")
+            .changed()
+        {
+            self.expect = matching.then(String::new);
+        }
+
+        if let Some(expect) = &mut self.expect {
+            let expect_input = TextEdit::singleline(expect)
+                .hint_text(WidgetText::italics("220, * OK, +OK…".into()))
+                .font(TextStyle::Monospace);
+
+            ui.add(expect_input);
+        }
+    }
+}
+
+// The round trip is the time from connecting to the first line of the banner, so a server that
+// accepts but stalls before greeting shows up as slow.
+pub fn ping(
+    addr: &str,
+    port: u16,
+    tls: bool,
+    expect: Option<&str>,
+    probes: usize,
+    timeout: Duration,
+) -> Vec<Reply> {
+    let Some(ip) = check::resolve(addr) else {
+        return Reply::failures(None, probes);
+    };
+
+    let target = SocketAddr::new(ip, port);
+
+    let name = tls
+        .then(|| ServerName::try_from(dns::ascii(addr)).ok())
+        .flatten();
+
+    if tls && name.is_none() {
+        return Reply::failures(Some(ip), probes);
+    }
+
+    let expect = expect.map(str::to_owned);
+
+    check::fan_out(ip, probes, timeout, |_| {
+        let name = name.clone();
+        let expect = expect.clone();
+
+        async move {
+            let (duration, size) = probe(target, name, expect.as_deref()).await?;
+
+            Some(Reply {
+                size,
+                ..Reply::new(Pong::Success(duration))
+            })
+        }
+    })
+}
+
+async fn probe(
+    target: SocketAddr,
+    name: Option<ServerName<'static>>,
+    expect: Option<&str>,
+) -> Option<(Duration, usize)> {
+    let start = Instant::now();
+    let stream = TcpStream::connect(target).await.ok()?;
+
+    let banner = match name {
+        None => banner(stream).await?,
+        Some(name) => banner(tls::connector(&[])?.connect(name, stream).await.ok()?).await?,
+    };
+
+    let elapsed = start.elapsed();
+
+    if !expect.is_none_or(|expect| banner.starts_with(expect.trim())) {
+        return None;
+    }
+
+    Some((elapsed, banner.len()))
+}
+
+async fn banner<T: AsyncRead + Unpin>(stream: T) -> Option<String> {
+    let mut line = String::new();
+    let read = BufReader::new(stream).read_line(&mut line).await.ok()?;
+
+    (read > 0).then_some(line)
+}
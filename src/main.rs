@@ -1,18 +1,119 @@
 #![feature(exact_size_is_empty)]
-#![feature(slice_first_last_chunk)]
 
-use std::time::{Duration, Instant};
+mod a2s;
+mod ack;
+mod animate;
+mod archive;
+mod audit;
+mod backend;
+mod backup;
+mod capture;
+mod check;
+mod cidr;
+mod clock;
+mod compare;
+mod correlate;
+mod csv;
+mod database;
+mod desktop;
+mod detail;
+mod diagram;
+mod digest;
+mod dns;
+mod elevate;
+mod expr;
+mod focus;
+mod game;
+mod grpc;
+mod headless;
+mod heartbeat;
+mod hosts;
+mod http;
+mod idle;
+mod inbound;
+mod inbox;
+mod inventory;
+mod ipv6;
+mod mail;
+mod minecraft;
+mod minimap;
+mod mock;
+mod mysql;
+mod notify;
+mod ntp;
+mod onboarding;
+mod otlp;
+mod paste;
+mod placement;
+mod plot;
+mod policy;
+mod postgres;
+mod presentation;
+mod range;
+mod redis;
+mod resolver;
+mod resume;
+mod ring;
+mod routing;
+mod rules;
+mod schedule;
+mod secrets;
+mod session;
+mod snap;
+mod snmp;
+mod stats;
+mod status;
+mod store;
+mod stream;
+mod systemd;
+mod table;
+mod tcp;
+mod tls;
+mod trace;
+mod udp;
+mod units;
+mod update;
+mod warmup;
+mod websocket;
+mod widget;
+mod worker;
+
+use std::{
+    mem,
+    net::IpAddr,
+    time::{Duration, Instant},
+};
 
-use chrono::{DateTime, Utc};
+use ack::Ack;
+use backend::Backend;
+use check::Check;
+use chrono::{DateTime, Local, TimeDelta, Utc};
+use clock::Clock;
+use correlate::Report;
+use desktop::Desktop;
 use eframe::{App, CreationContext, NativeOptions};
 use egui::{
-    text::LayoutJob, Align, Button, CentralPanel, Color32, Context, Frame, Id, Label, Layout, Pos2,
-    Sense, Stroke, TextEdit, TextFormat, TextStyle, Vec2, Vec2b, WidgetText, Window, OpenUrl,
+    menu, text::LayoutJob, Align, Button, CentralPanel, Color32, ComboBox, Context, DragValue,
+    Frame, Id, Key, LayerId, Layout, Modifiers, OpenUrl, Order, PointerButton, Pos2, Rect, Sense,
+    Stroke, TextEdit, TextFormat, TextStyle, TopBottomPanel, Ui, UserAttentionType, Vec2, Vec2b,
+    ViewportCommand, Visuals, WidgetText, Window,
 };
-use egui_extras::{Column, TableBuilder};
-use egui_plot::{Line, Plot, PlotPoints};
+use egui_plot::{Line, LineStyle, Plot};
+use heartbeat::{Heartbeats, Receiver};
+use inbound::Inbound;
 use itertools::Itertools;
+use notify::Outlet;
+use plot::Series;
+use policy::{Policy, Settings};
+use rand::random;
+use range::TimeRange;
+use ring::Ring;
+use routing::{Routing, Severity};
 use serde::{Deserialize, Serialize};
+use snap::Guides;
+use surge_ping::{Client, Config, IcmpPacket, PingIdentifier, PingSequence, ICMP};
+use units::Units;
+use widget::Widget;
 
 #[derive(Clone, Copy, Debug)]
 pub enum Pong {
@@ -20,6 +121,111 @@ pub enum Pong {
     Failure,
 }
 
+#[derive(Clone, Copy, Debug)]
+pub struct Reply {
+    pub pong: Pong,
+    pub seq: u16,
+    pub target: Option<IpAddr>,
+    pub ttl: Option<u8>,
+    pub size: usize,
+    pub offset: Option<TimeDelta>,
+    pub players: Option<game::Players>,
+    // Connecting, when the type measures it apart from the round trip.
+    pub setup: Option<Duration>,
+}
+
+impl Reply {
+    pub fn new(pong: Pong) -> Self {
+        Self {
+            pong,
+            seq: 0,
+            target: None,
+            ttl: None,
+            size: 0,
+            offset: None,
+            players: None,
+            setup: None,
+        }
+    }
+
+    pub fn failures(target: Option<IpAddr>, probes: usize) -> Vec<Reply> {
+        (0..probes)
+            .map(|seq| Reply {
+                seq: seq as u16,
+                target,
+                ..Reply::new(Pong::Failure)
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Sample {
+    pub time: DateTime<Utc>,
+    pub check: &'static str,
+    pub reply: Reply,
+    pub jitter: Option<Duration>,
+}
+
+pub struct Probe {
+    time: DateTime<Utc>,
+    replies: Vec<Reply>,
+    diagnosis: Option<ipv6::Report>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Outage {
+    since: Instant,
+    reminded: Instant,
+    escalated: bool,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Round {
+    loss: f32,
+    rtt: Option<[Duration; 3]>,
+}
+
+impl Round {
+    pub fn new(pongs: &[Pong]) -> Self {
+        let mut rtts = pongs
+            .iter()
+            .filter_map(|pong| match pong {
+                Pong::Success(duration) => Some(*duration),
+                Pong::Failure => None,
+            })
+            .collect_vec();
+
+        rtts.sort();
+
+        let loss = 1. - rtts.len() as f32 / pongs.len() as f32;
+
+        let rtt = match (rtts.first(), rtts.last()) {
+            (Some(&min), Some(&max)) => Some([min, rtts[rtts.len() / 2], max]),
+            _ => None,
+        };
+
+        Self { loss, rtt }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PlotTheme {
+    line: Color32,
+    fill: Color32,
+    background: Color32,
+}
+
+impl Default for PlotTheme {
+    fn default() -> Self {
+        Self {
+            line: PASS,
+            fill: PASS.gamma_multiply(0.25),
+            background: Color32::TRANSPARENT,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct PingWindow {
     origin: Option<Pos2>,
@@ -28,6 +234,61 @@ pub struct PingWindow {
     group: usize,
     scratchpad: String,
 
+    #[serde(default)]
+    label: String,
+
+    #[serde(default)]
+    by_ip: bool,
+
+    // Frame color for hosts that must stand out from the rest of their group.
+    #[serde(default)]
+    color: Option<Color32>,
+
+    #[serde(default = "default_probes")]
+    probes: usize,
+
+    #[serde(default)]
+    theme: Option<PlotTheme>,
+
+    #[serde(default)]
+    policy: Policy,
+
+    #[serde(default)]
+    check: Check,
+
+    #[serde(default)]
+    listen: Option<String>,
+
+    #[serde(default)]
+    interface: String,
+
+    #[serde(default)]
+    ipv6: bool,
+
+    #[serde(default)]
+    certificate: tls::Certificate,
+
+    #[serde(default)]
+    probe_schedule: String,
+
+    #[serde(default)]
+    alert_schedule: String,
+
+    #[serde(default)]
+    range: Option<TimeRange>,
+
+    #[serde(default = "table::default_columns")]
+    columns: Vec<table::Field>,
+
+    #[serde(default)]
+    ack: Option<Ack>,
+
+    #[serde(default)]
+    plot_jitter: bool,
+
+    #[serde(default)]
+    percentiles: stats::Span,
+
     #[serde(skip)]
     #[serde(default = "default_now")]
     ctime: Instant,
@@ -36,24 +297,72 @@ pub struct PingWindow {
     #[serde(default = "default_true")]
     open: bool,
 
-    #[serde(skip)]
+    #[serde(default)]
     scanning: bool,
 
     #[serde(skip)]
     show_plot: bool,
 
+    #[serde(skip)]
+    show_stats: bool,
+
     #[serde(skip)]
     show_scratchpad: bool,
 
+    #[serde(skip)]
+    show_settings: bool,
+
     #[serde(skip)]
     success: Option<bool>,
 
     #[serde(skip)]
-    history: Vec<(DateTime<Utc>, Pong)>,
+    degraded: bool,
+
+    #[serde(skip)]
+    misses: u32,
+
+    #[serde(skip)]
+    peer: Option<Option<IpAddr>>,
+
+    #[serde(skip)]
+    diagnosis: Option<ipv6::Report>,
+
+    #[serde(skip)]
+    outage: Option<Outage>,
+
+    #[serde(skip)]
+    probing: Option<worker::Job<Probe>>,
+
+    #[serde(skip)]
+    history: Ring<Sample>,
+
+    #[serde(skip)]
+    table: table::State,
+
+    #[serde(skip)]
+    ack_draft: ack::Draft,
+
+    #[serde(skip)]
+    export: csv::Export,
 
     #[serde(skip)]
     #[serde(default = "default_now")]
     last_ping: Instant,
+
+    #[serde(skip)]
+    rect: Option<Rect>,
+
+    #[serde(skip)]
+    dragging: bool,
+
+    #[serde(skip)]
+    snap_to: Option<Pos2>,
+
+    #[serde(skip)]
+    trace: trace::Trace,
+
+    #[serde(skip)]
+    marks: animate::Marks,
 }
 
 impl PingWindow {
@@ -63,15 +372,49 @@ impl PingWindow {
             hostname: "localhost (v4)".into(),
             address: "127.0.0.1".into(),
             scratchpad: String::new(),
+            label: String::new(),
+            by_ip: false,
+            color: None,
             group: 0,
+            probes: DEFAULT_PROBES,
+            theme: None,
+            policy: Policy::default(),
+            check: Check::default(),
+            listen: None,
+            interface: String::new(),
+            ipv6: false,
+            certificate: tls::Certificate::default(),
+            probe_schedule: String::new(),
+            alert_schedule: String::new(),
+            range: None,
+            columns: table::default_columns(),
+            ack: None,
+            plot_jitter: false,
+            percentiles: stats::Span::default(),
             ctime: Instant::now(),
             open: true,
             scanning: false,
             show_plot: false,
+            show_stats: false,
             show_scratchpad: false,
+            show_settings: false,
             success: None,
-            history: vec![],
+            degraded: false,
+            misses: 0,
+            peer: None,
+            diagnosis: None,
+            outage: None,
+            probing: None,
+            history: Ring::default(),
+            table: Default::default(),
+            ack_draft: Default::default(),
+            export: Default::default(),
             last_ping: Instant::now(),
+            rect: None,
+            dragging: false,
+            snap_to: None,
+            trace: Default::default(),
+            marks: animate::Marks::default(),
         }
     }
 
@@ -85,298 +428,2239 @@ impl PingWindow {
             hostname: hostname.into(),
             address: address.into(),
             scratchpad: String::new(),
+            label: String::new(),
+            by_ip: false,
+            color: None,
             group: 0,
+            probes: DEFAULT_PROBES,
+            theme: None,
+            policy: Policy::default(),
+            check: Check::default(),
+            listen: None,
+            interface: String::new(),
+            ipv6: false,
+            certificate: tls::Certificate::default(),
+            probe_schedule: String::new(),
+            alert_schedule: String::new(),
+            range: None,
+            columns: table::default_columns(),
+            ack: None,
+            plot_jitter: false,
+            percentiles: stats::Span::default(),
             ctime: Instant::now(),
             open: true,
             scanning: false,
             show_plot: false,
+            show_stats: false,
             show_scratchpad: false,
+            show_settings: false,
             success: None,
-            history: vec![],
+            degraded: false,
+            misses: 0,
+            peer: None,
+            diagnosis: None,
+            outage: None,
+            probing: None,
+            history: Ring::default(),
+            table: Default::default(),
+            ack_draft: Default::default(),
+            export: Default::default(),
             last_ping: Instant::now(),
+            rect: None,
+            dragging: false,
+            snap_to: None,
+            trace: Default::default(),
+            marks: animate::Marks::default(),
         }
     }
-}
 
-#[derive(Serialize, Deserialize)]
-pub struct PingApp {
-    windows: Vec<PingWindow>,
-}
+    // Case-insensitive, any of the name, the secondary label or the address will do.
+    pub fn matches(&self, filter: &str) -> bool {
+        let filter = filter.trim().to_lowercase();
 
-impl PingApp {
-    fn new(cc: &CreationContext<'_>) -> Self {
-        PingApp::default()
+        [&self.hostname, &self.label, &self.address]
+            .into_iter()
+            .any(|text| text.to_lowercase().contains(&filter))
     }
-}
 
-impl Default for PingApp {
-    fn default() -> Self {
-        let windows = vec![
-            PingWindow::new("localhost (v4)", "127.0.0.1", None),
-            PingWindow::new("localhost (v6)", "::1", None),
-            PingWindow::new("Google DNS", "8.8.8.8", None),
-        ];
+    pub fn status(&self, now: DateTime<Utc>) -> Color32 {
+        match (self.scanning, self.success, self.degraded) {
+            (false, _, _) => NONE,
+            (true, None, _) => NONE,
+            (true, Some(true), false) => PASS,
+            (true, Some(true), true) => WARN,
+            (true, Some(false), _) if self.acked(now) => ACKED,
+            (true, Some(false), _) => FAIL,
+        }
+    }
 
-        Self { windows }
+    // An expired acknowledgement is as good as none, it pages again until it is cleared.
+    fn acked(&self, now: DateTime<Utc>) -> bool {
+        self.ack.as_ref().is_some_and(|ack| !ack.expired(now))
     }
-}
 
-const PLOT_LEN: usize = 20;
+    pub fn alerting(&self, clock: &Clock) -> bool {
+        !self.acked(clock.utc()) && schedule::active(&self.alert_schedule, clock.local())
+    }
 
-const NONE: Color32 = Color32::from_rgb(0x81, 0x82, 0x74);
-const PASS: Color32 = Color32::from_rgb(0xA1, 0xC2, 0x31);
-const FAIL: Color32 = Color32::from_rgb(0xF4, 0x30, 0x2F);
+    pub fn follow_up(&mut self, settings: Settings, clock: &Clock) -> (bool, bool) {
+        let alerting = settings.alert.fires(false) && self.alerting(clock);
 
-const GROUPS: [Color32; 5] = [
-    Color32::from_gray(0x1B),
-    Color32::from_rgb(0x4A, 0x42, 0x25),
-    Color32::from_rgb(0x25, 0x4A, 0x30),
-    Color32::from_rgb(0x25, 0x2D, 0x4A),
-    Color32::from_rgb(0x4A, 0x25, 0x3F),
-];
+        let Some(outage) = self.outage.as_mut().filter(|_| alerting) else {
+            return (false, false);
+        };
 
-impl App for PingApp {
-    fn update(&mut self, ctx: &Context, _: &mut eframe::Frame) {
-        ctx.style_mut(|style| style.spacing.item_spacing = Vec2::new(8., 6.));
+        let remind = !settings.repeat.is_zero() && clock.since(outage.reminded) >= settings.repeat;
+        let escalate = !settings.escalate.is_zero()
+            && !outage.escalated
+            && clock.since(outage.since) >= settings.escalate;
 
-        CentralPanel::default().show(ctx, |ui| {
-            let full_rect = ui.available_rect_before_wrap();
-            let interactable = ui.interact(full_rect, Id::new("void"), Sense::click());
+        if remind {
+            outage.reminded = clock.now();
+        }
 
-            if interactable.double_clicked() {
-                let origin = interactable.interact_pointer_pos().unwrap_or_default();
-                self.windows.push(PingWindow::empty(Some(origin)));
-            }
-        });
+        outage.escalated |= escalate;
+        (remind, escalate)
+    }
+
+    pub fn poll(
+        &mut self,
+        ctx: &Context,
+        settings: Settings,
+        beats: &Heartbeats,
+        backend: Backend,
+        clock: &Clock,
+    ) -> Duration {
+        if let (Some(source), None) = (&self.listen, self.peer) {
+            let source = [source, &self.address][source.is_empty() as usize];
+            let lookup = dns::lookup(source).unwrap_or_default();
+            self.peer = Some(lookup.first().copied());
+        }
 
-        for win in &mut self.windows {
-            if win.scanning
-                && (win.success.is_none() || win.last_ping.elapsed() > Duration::from_secs(1))
-            {
-                let now = Utc::now();
-                let pong = do_ping(&win.address);
+        if !self.scanning {
+            self.probing = None;
+            return Duration::MAX;
+        }
 
-                win.last_ping = Instant::now();
-                win.history.push((now, pong));
+        if !schedule::active(&self.probe_schedule, clock.local()) {
+            self.probing = None;
+            self.success = None;
+            return Duration::MAX;
+        }
 
-                win.success = match pong {
-                    Pong::Success(_) => Some(true),
-                    Pong::Failure => Some(false),
-                };
+        if let Some(done) = self.probing.as_ref().and_then(worker::Job::done) {
+            self.probing = None;
+
+            if let Some(probe) = done {
+                self.settle(probe, settings, clock);
             }
+        }
 
-            let (icon, color) = match (win.scanning, win.success) {
-                (false, _) => ("████", NONE),
-                (true, None) => ("████", NONE),
-                (true, Some(true)) => ("████", PASS),
-                (true, Some(false)) => ("████", FAIL),
-            };
+        let due = self.success.is_none() || clock.since(self.last_ping) > settings.interval;
+
+        if due && self.probing.is_none() {
+            let address = self.address.clone();
+            let interface = self.interface.clone();
+            let check = self.check.clone();
+            let probes = self.probes;
+            let ipv6 = self.ipv6;
+            let beats = beats.clone();
+
+            self.last_ping = clock.now();
+
+            let clock = clock.clone();
+
+            self.probing = Some(worker::spawn(ctx, move || {
+                let time = clock.utc();
+                let replies = check.kind().ping(&check::Target {
+                    address: &address,
+                    interface: &interface,
+                    probes,
+                    timeout: settings.timeout,
+                    backend,
+                    beats: &beats,
+                    clock: &clock,
+                });
 
-            let mut job = LayoutJob::default();
-            let font_id = TextStyle::Monospace.resolve(&ctx.style());
-            let title = [&win.hostname, "Sin título"][win.hostname.is_empty() as usize];
+                let diagnosis =
+                    ipv6.then(|| ipv6::diagnose(&address, &interface, settings.timeout, backend));
 
-            let title_format = TextFormat {
-                font_id,
-                italics: win.hostname.is_empty(),
-                ..TextFormat::default()
-            };
+                Probe {
+                    time,
+                    replies,
+                    diagnosis,
+                }
+            }));
+        }
 
-            let icon_format = TextFormat {
-                color,
-                italics: false,
-                ..title_format.clone()
-            };
+        settings
+            .interval
+            .saturating_sub(clock.since(self.last_ping))
+    }
 
-            job.append(icon, 12., icon_format);
-            job.append(title, 12., title_format.clone());
-            job.append(" ", 12., title_format);
+    fn settle(&mut self, probe: Probe, settings: Settings, clock: &Clock) {
+        let pongs = probe.replies.iter().map(|reply| reply.pong).collect_vec();
+        let round = Round::new(&pongs);
 
-            let frame = Frame {
-                fill: GROUPS[win.group].gamma_multiply(0.75),
-                ..Frame::window(&ctx.style())
-            };
+        if probe.diagnosis.is_some() {
+            self.diagnosis = probe.diagnosis;
+        }
 
-            let mut window = Window::new(job)
-                .id(Id::new(win.ctime))
-                .default_width(200.)
-                .frame(frame)
-                .open(&mut win.open);
+        let check = self.check.label();
 
-            if let Some(origin) = win.origin {
-                window = window.default_pos(origin);
+        let mut previous = self
+            .history
+            .iter()
+            .rev()
+            .find_map(|sample| match sample.reply.pong {
+                Pong::Success(rtt) => Some(rtt),
+                Pong::Failure => None,
+            });
+
+        for reply in probe.replies {
+            let jitter = match (previous, reply.pong) {
+                (Some(previous), Pong::Success(rtt)) => Some(rtt.abs_diff(previous)),
+                _ => None,
+            };
+
+            if let Pong::Success(rtt) = reply.pong {
+                previous = Some(rtt);
             }
 
-            window.show(ctx, |ui| {
-                let host_input = TextEdit::singleline(&mut win.hostname)
-                    .hint_text(WidgetText::italics("Nombre".into()))
-                    .desired_width(ui.available_width())
-                    .font(TextStyle::Monospace)
-                    .cursor_at_end(true);
+            self.history.push(Sample {
+                time: probe.time,
+                check,
+                reply,
+                jitter,
+            });
+        }
 
-                let last_addr = win.address.clone();
+        self.misses = match round.rtt {
+            Some(_) => 0,
+            None => self.misses + 1,
+        };
+
+        // A host that was up gets a few retries before it is reported down.
+        let success =
+            round.rtt.is_some() || (self.success == Some(true) && self.misses <= settings.retries);
+        self.success = Some(success);
+
+        if success
+            || self
+                .ack
+                .as_ref()
+                .is_some_and(|ack| ack.expired(clock.utc()))
+        {
+            self.ack = None;
+        }
 
-                let addr_input = TextEdit::singleline(&mut win.address)
-                    .hint_text(WidgetText::italics("Direccion".into()))
-                    .desired_width(ui.available_width())
-                    .font(TextStyle::Monospace)
-                    .cursor_at_end(true);
+        self.outage = match (success, self.outage) {
+            (true, _) => None,
+            (false, Some(outage)) => Some(outage),
+            (false, None) => Some(Outage {
+                since: clock.now(),
+                reminded: clock.now(),
+                escalated: false,
+            }),
+        };
+
+        self.degraded = match round.rtt {
+            Some([_, median, _]) => !settings.threshold.is_zero() && median > settings.threshold,
+            None => false,
+        };
+    }
+}
 
-                ui.horizontal(|ui| {
-                    ui.vertical(|ui| {
-                        if ui.toggle_value(&mut win.scanning, "📶").clicked() {
-                            win.success = None;
-                        }
+#[derive(Serialize, Deserialize)]
+pub struct Canvas {
+    name: String,
+    windows: Vec<PingWindow>,
 
-                        ui.toggle_value(&mut win.show_plot, "📈");
-                        ui.toggle_value(&mut win.show_scratchpad, " ¶ ");
-                    });
+    #[serde(default)]
+    widgets: Vec<Widget>,
+}
 
-                    ui.vertical_centered_justified(|ui| {
-                        ui.horizontal(|ui| {
-                            for (idx, color) in GROUPS.into_iter().enumerate() {
-                                let stroke = Stroke::new(0.5, Color32::BLACK);
-                                let button = Button::new("     ").fill(color).stroke(stroke);
+impl Canvas {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            windows: vec![],
+            widgets: vec![],
+        }
+    }
+}
 
-                                if ui.add(button).clicked() {
-                                    win.group = idx;
-                                }
-                            }
-                        });
+#[derive(Serialize, Deserialize)]
+pub struct PingApp {
+    canvases: Vec<Canvas>,
+    active: usize,
 
-                        ui.add(host_input);
+    #[serde(default)]
+    themes: [PlotTheme; GROUPS.len()],
 
-                        if ui.add(addr_input).secondary_clicked() {
-                            let open_url = OpenUrl {
-                                url: format!("http://{}", last_addr),
-                                new_tab: true,
-                            };
+    #[serde(default)]
+    policies: [Policy; GROUPS.len()],
 
-                            ctx.open_url(open_url);
-                        }
+    #[serde(default)]
+    tunnels: [String; GROUPS.len()],
 
-                        if win.show_plot {
-                            let base = win.history.len().saturating_sub(PLOT_LEN);
+    #[serde(default)]
+    archives: [archive::Archive; GROUPS.len()],
 
-                            let groups = win.history[base..].iter().enumerate().group_by(
-                                |(_, (_, pong))| match pong {
-                                    Pong::Failure => false,
-                                    Pong::Success(_) => true,
-                                },
-                            );
+    #[serde(default)]
+    backend: Backend,
 
-                            let mut lines = vec![];
+    #[serde(default)]
+    notifiers: notify::Notifiers,
 
-                            for (success, group) in groups.into_iter() {
-                                if !success {
-                                    continue;
-                                }
+    // Older configs kept the webhook at the top level, before there was a registry for it.
+    #[serde(
+        default,
+        rename = "webhook",
+        skip_serializing,
+        deserialize_with = "notify::Webhook::legacy"
+    )]
+    legacy_webhook: Option<notify::Webhook>,
 
-                                let samples = group
-                                    .map(|(idx, (_, pong))| {
-                                        let y = match pong {
-                                            Pong::Failure => unreachable!(),
-                                            Pong::Success(duration) => duration.as_secs_f64(),
-                                        };
+    #[serde(default = "notify::Webhook::escalation")]
+    escalation: notify::Webhook,
 
-                                        [idx as f64, y]
-                                    })
-                                    .collect::<PlotPoints>();
+    #[serde(default)]
+    routing: Routing,
 
-                                let line = Line::new(samples).fill(0.).color(PASS);
-                                lines.push(line);
-                            }
+    #[serde(default = "default_batch")]
+    batch: Duration,
 
-                            Plot::new("ping")
-                                .show_axes(false)
-                                .auto_bounds_y()
-                                .include_x(0.)
-                                .include_x(PLOT_LEN as f64 - 1.)
-                                .allow_drag(Vec2b::FALSE)
-                                .reset()
-                                .label_formatter(|_, sample| {
-                                    let sign = ["", "-"][(sample.y < 0.) as usize];
-                                    let secs = sample.y.abs();
-                                    let duration = Duration::from_secs_f64(secs);
-                                    format!("{}{:?}", sign, duration)
-                                })
-                                .show(ui, |ui| {
-                                    for line in lines {
-                                        ui.line(line)
-                                    }
-                                });
-                        } else {
-                            // TableBuilder::new(ui)
-                            //     .striped(true)
-                            //     .column(Column::auto())
-                            //     .resizable(true)
-                            //     .body(|body| {
-                            //         body.rows(24., win.history.len(), |idx, mut row| {
-                            //             let (instant, pong) = &win.history[idx];
-                            //             let instant = instant.format("%H:%M:%S").to_string();
-
-                            //             let pong = match pong {
-                            //                 Pong::Failure => String::from("Unreachable"),
-                            //                 Pong::Success(duration) => format!("{:?}", duration),
-                            //             };
-
-                            //             row.col(|ui| {
-                            //                 ui.add(Label::new(instant).wrap(false));
-                            //             });
-                            //         })
-                            //     });
-                        }
-
-                        if win.show_scratchpad {
-                            let scratch_input = TextEdit::multiline(&mut win.scratchpad)
-                                .font(TextStyle::Monospace)
-                                .hint_text(WidgetText::italics("Anotaciones".into()));
+    #[serde(default = "default_grace")]
+    grace: Duration,
 
-                            ui.add(scratch_input);
-                        }
-                    });
-                });
-            });
-        }
+    #[serde(default)]
+    idle_stop: Option<Duration>,
 
-        self.windows.retain(|win| win.open);
-        ctx.request_repaint_after(Duration::from_secs(1));
-    }
-}
+    #[serde(default = "default_history")]
+    history: usize,
 
-fn default_true() -> bool {
-    true
-}
+    #[serde(default)]
+    store: store::Store,
 
-fn default_now() -> Instant {
-    Instant::now()
-}
+    #[serde(default)]
+    units: Units,
 
-fn do_ping(addr: &str) -> Pong {
-    let Ok(lookup) = dns_lookup::lookup_host(addr) else {
-        return Pong::Failure;
-    };
+    #[serde(default)]
+    locked: bool,
 
-    let Some(ip) = lookup.first() else {
-        return Pong::Failure;
-    };
+    #[serde(default)]
+    snap: bool,
 
-    let pong = tokio::runtime::Builder::new_current_thread()
-        .enable_all()
-        .build()
-        .unwrap()
-        .block_on(surge_ping::ping(*ip, &[]));
+    #[serde(default = "default_grid")]
+    grid: f32,
 
-    match pong {
-        Ok((_, duration)) => Pong::Success(duration),
-        Err(_) => Pong::Failure,
-    }
-}
+    #[serde(default = "default_true")]
+    guides: bool,
 
-fn main() {
-    let _ = eframe::run_native(
-        "PingA",
-        NativeOptions::default(),
-        Box::new(|cc| Box::new(PingApp::new(cc))),
-    );
+    #[serde(default)]
+    range: TimeRange,
+
+    #[serde(skip)]
+    detail: Option<Instant>,
+
+    #[serde(default)]
+    receiver_enabled: bool,
+
+    #[serde(default = "default_receiver_bind")]
+    receiver_bind: String,
+
+    #[serde(default)]
+    stream_enabled: bool,
+
+    #[serde(default = "default_stream_bind")]
+    stream_bind: String,
+
+    #[serde(default)]
+    stream_format: stream::Format,
+
+    #[serde(default)]
+    otlp: otlp::Exporter,
+
+    #[serde(default)]
+    onboarded: bool,
+
+    #[serde(default = "default_true")]
+    dark: bool,
+
+    #[serde(default)]
+    updates: update::Checker,
+
+    #[serde(default)]
+    placement: placement::Placement,
+
+    #[serde(skip)]
+    report: Report,
+
+    #[serde(skip)]
+    compare: compare::Compare,
+
+    #[serde(skip)]
+    session: session::Export,
+
+    #[serde(skip)]
+    layout_export: diagram::Export,
+
+    #[serde(skip)]
+    backup: backup::Backup,
+
+    #[serde(skip)]
+    heartbeats: Heartbeats,
+
+    #[serde(skip)]
+    receiver: Receiver,
+
+    #[serde(skip)]
+    streamer: stream::Streamer,
+
+    #[serde(skip)]
+    wizard: Option<onboarding::Wizard>,
+
+    #[serde(skip)]
+    elevate: elevate::Helper,
+
+    #[serde(skip)]
+    inbound: Inbound,
+
+    #[serde(skip)]
+    desktop: Desktop,
+
+    #[serde(skip)]
+    import: rules::Import,
+
+    #[serde(skip)]
+    hosts_import: csv::Import,
+
+    #[serde(skip)]
+    hosts_file: hosts::Picker,
+
+    #[serde(skip)]
+    inventory: inventory::Import,
+
+    #[serde(skip)]
+    idle: idle::Idle,
+
+    #[serde(skip)]
+    resume: resume::Resume,
+
+    #[serde(skip)]
+    warmup: warmup::Warmup,
+
+    #[serde(skip)]
+    audit: audit::Audit,
+
+    #[serde(skip)]
+    inbox: inbox::Inbox,
+
+    #[serde(skip)]
+    digest: digest::Digest,
+
+    #[serde(skip)]
+    clock: Clock,
+
+    #[serde(skip)]
+    focus: focus::Focus,
+
+    #[serde(skip)]
+    capture: capture::Capture,
+
+    #[serde(default)]
+    presentation: Option<presentation::Lock>,
+
+    #[serde(skip)]
+    filter: String,
+
+    #[serde(skip)]
+    passphrase: String,
+}
+
+impl PingApp {
+    fn new(cc: &CreationContext<'_>) -> Self {
+        let mut app = cc
+            .storage
+            .and_then(|storage| eframe::get_value::<PingApp>(storage, eframe::APP_KEY))
+            .unwrap_or_default();
+
+        app.migrate();
+
+        if !app.onboarded {
+            app.wizard = Some(onboarding::Wizard::default());
+        }
+
+        app.store.restore(&mut app.canvases, app.history);
+        app.resume = resume::Resume::hold(&mut app.canvases, &app.clock);
+        app.placement.arm();
+        cc.egui_ctx.set_visuals(visuals(app.dark));
+        app.sync_receiver(&cc.egui_ctx);
+        app.sync_streamer();
+        app
+    }
+
+    fn migrate(&mut self) {
+        if let Some(webhook) = self.legacy_webhook.take() {
+            self.notifiers.webhook = webhook;
+        }
+
+        // Locked with a blank passphrase before that was refused, which could no longer be undone.
+        if self
+            .presentation
+            .as_ref()
+            .is_some_and(|lock| lock.opens(""))
+        {
+            self.presentation = None;
+        }
+
+        self.secure();
+    }
+
+    // Retried on every save until the keyring holds every plaintext secret from older configs.
+    fn secure(&mut self) {
+        self.notifiers.webhook.secure();
+        self.escalation.secure();
+    }
+
+    fn backup(&mut self) -> Result<String, String> {
+        placement::record(&mut self.canvases);
+        let config = ron::to_string(self).map_err(|err| err.to_string())?;
+        let history = self.store.snapshot()?;
+
+        backup::write(&self.backup.path, &config, history.as_deref())?;
+
+        let done = ["Copia guardada sin historial", "Copia guardada"][history.is_some() as usize];
+        Ok(done.into())
+    }
+
+    fn restore(&mut self, ctx: &Context) -> Result<String, String> {
+        let (config, history) = backup::read(&self.backup.path)?;
+        let mut app = ron::from_str::<PingApp>(&config).map_err(|err| err.to_string())?;
+
+        if let Some(history) = &history {
+            self.store.replace(history)?;
+        }
+
+        self.receiver.stop();
+        self.streamer.stop();
+
+        app.migrate();
+        app.onboarded = true;
+        app.backup = mem::take(&mut self.backup);
+        app.store.restore(&mut app.canvases, app.history);
+        app.resume = resume::Resume::hold(&mut app.canvases, &app.clock);
+        app.placement.arm();
+        ctx.set_visuals(visuals(app.dark));
+
+        *self = app;
+        self.sync_receiver(ctx);
+        self.sync_streamer();
+
+        Ok("Copia restaurada".into())
+    }
+
+    fn sync_streamer(&mut self) {
+        self.streamer.stop();
+
+        if self.stream_enabled {
+            self.streamer.start(&self.stream_bind);
+        }
+    }
+
+    fn sync_receiver(&mut self, ctx: &Context) {
+        self.receiver.stop();
+
+        if self.receiver_enabled {
+            self.receiver
+                .start(ctx, &self.receiver_bind, &self.heartbeats);
+        }
+    }
+}
+
+impl Default for PingApp {
+    fn default() -> Self {
+        let windows = vec![
+            PingWindow::new("localhost (v4)", "127.0.0.1", None),
+            PingWindow::new("localhost (v6)", "::1", None),
+            PingWindow::new("Google DNS", "8.8.8.8", None),
+        ];
+
+        let canvas = Canvas {
+            name: "Principal".into(),
+            windows,
+            widgets: vec![],
+        };
+
+        Self {
+            canvases: vec![canvas],
+            active: 0,
+            themes: Default::default(),
+            policies: Default::default(),
+            tunnels: Default::default(),
+            archives: Default::default(),
+            backend: Backend::default(),
+            notifiers: notify::Notifiers::default(),
+            legacy_webhook: None,
+            escalation: notify::Webhook::escalation(),
+            routing: Routing::default(),
+            batch: DEFAULT_BATCH,
+            grace: DEFAULT_GRACE,
+            idle_stop: None,
+            history: ring::DEFAULT_CAPACITY,
+            store: store::Store::default(),
+            units: Units::default(),
+            locked: false,
+            snap: false,
+            grid: DEFAULT_GRID,
+            guides: true,
+            range: TimeRange::default(),
+            detail: None,
+            receiver_enabled: false,
+            receiver_bind: default_receiver_bind(),
+            stream_enabled: false,
+            stream_bind: default_stream_bind(),
+            stream_format: stream::Format::default(),
+            otlp: otlp::Exporter::default(),
+            onboarded: false,
+            dark: true,
+            updates: update::Checker::default(),
+            placement: placement::Placement::default(),
+            report: Report::default(),
+            compare: compare::Compare::default(),
+            session: session::Export::default(),
+            layout_export: diagram::Export::default(),
+            backup: backup::Backup::default(),
+            heartbeats: Heartbeats::default(),
+            receiver: Receiver::default(),
+            streamer: stream::Streamer::default(),
+            wizard: None,
+            elevate: elevate::Helper::default(),
+            inbound: Inbound::default(),
+            desktop: Desktop::default(),
+            import: rules::Import::default(),
+            hosts_import: csv::Import::default(),
+            hosts_file: hosts::Picker::default(),
+            inventory: inventory::Import::default(),
+            idle: idle::Idle::default(),
+            resume: resume::Resume::default(),
+            warmup: warmup::Warmup::default(),
+            audit: audit::Audit::default(),
+            inbox: inbox::Inbox::default(),
+            digest: digest::Digest::default(),
+            clock: Clock::default(),
+            focus: focus::Focus::default(),
+            capture: capture::Capture::default(),
+            presentation: None,
+            filter: String::new(),
+            passphrase: String::new(),
+        }
+    }
+}
+
+const PLOT_LEN: usize = 20;
+const DEFAULT_PROBES: usize = 3;
+const MAX_PROBES: usize = 20;
+const DEFAULT_IDLE_STOP: Duration = Duration::from_secs(8 * 3600);
+const DEFAULT_BATCH: Duration = Duration::from_secs(5);
+const DEFAULT_GRACE: Duration = Duration::from_secs(60);
+const DEFAULT_GRID: f32 = 20.;
+const DEFAULT_RECEIVER_BIND: &str = "127.0.0.1:8089";
+const DEFAULT_STREAM_BIND: &str = "127.0.0.1:8090";
+
+const CANVAS_KEYS: [Key; 9] = [
+    Key::Num1,
+    Key::Num2,
+    Key::Num3,
+    Key::Num4,
+    Key::Num5,
+    Key::Num6,
+    Key::Num7,
+    Key::Num8,
+    Key::Num9,
+];
+
+const NONE: Color32 = Color32::from_rgb(0x81, 0x82, 0x74);
+const PASS: Color32 = Color32::from_rgb(0xA1, 0xC2, 0x31);
+const FAIL: Color32 = Color32::from_rgb(0xF4, 0x30, 0x2F);
+const WARN: Color32 = Color32::from_rgb(0xF4, 0xB4, 0x2F);
+const ACKED: Color32 = Color32::from_rgb(0x5F, 0x8F, 0xD7);
+
+const GROUPS: [Color32; 5] = [
+    Color32::from_gray(0x1B),
+    Color32::from_rgb(0x4A, 0x42, 0x25),
+    Color32::from_rgb(0x25, 0x4A, 0x30),
+    Color32::from_rgb(0x25, 0x2D, 0x4A),
+    Color32::from_rgb(0x4A, 0x25, 0x3F),
+];
+
+impl App for PingApp {
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        placement::record(&mut self.canvases);
+        self.secure();
+        self.store.flush();
+        eframe::set_value(storage, eframe::APP_KEY, self);
+    }
+
+    fn update(&mut self, ctx: &Context, frame: &mut eframe::Frame) {
+        ctx.style_mut(|style| style.spacing.item_spacing = Vec2::new(8., 6.));
+
+        for (idx, key) in CANVAS_KEYS.into_iter().enumerate() {
+            if ctx.input_mut(|input| input.consume_key(Modifiers::COMMAND, key)) {
+                self.active = idx.min(self.canvases.len() - 1);
+            }
+        }
+
+        let snapshot = audit::Snapshot::take(&self.canvases, &self.policies);
+
+        if self
+            .idle
+            .check(ctx, self.idle_stop, &mut self.canvases, &self.clock)
+        {
+            let body = "Sondeo pausado por inactividad";
+            self.desktop.toast(frame, "PingA", body, false);
+            ctx.send_viewport_cmd(ViewportCommand::RequestUserAttention(
+                UserAttentionType::Informational,
+            ));
+        }
+
+        if let Some(wizard) = &mut self.wizard {
+            if let Some(windows) = onboarding::show(ctx, wizard, &mut self.dark) {
+                self.canvases[0].windows = windows;
+                self.onboarded = true;
+                self.wizard = None;
+            }
+        }
+
+        self.updates.poll(ctx);
+
+        let editable = self.presentation.is_none();
+        let locked = self.locked || !editable;
+
+        TopBottomPanel::top("menu").show(ctx, |ui| {
+            menu::bar(ui, |ui| {
+                ui.add_enabled_ui(editable, |ui| {
+                    ui.menu_button("Temas", |ui| {
+                        for (idx, theme) in self.themes.iter_mut().enumerate() {
+                            ui.horizontal(|ui| {
+                                let stroke = Stroke::new(0.5, Color32::BLACK);
+                                let swatch = Button::new("     ").fill(GROUPS[idx]).stroke(stroke);
+
+                                ui.add_enabled(false, swatch);
+                                theme_editor(ui, theme);
+                            });
+                        }
+
+                        if ui.button("Restablecer").clicked() {
+                            self.themes = Default::default();
+                        }
+                    });
+
+                    ui.menu_button("Grupos", |ui| {
+                        for (idx, policy) in self.policies.iter_mut().enumerate() {
+                            let stroke = Stroke::new(0.5, Color32::BLACK);
+                            let swatch = Button::new("     ").fill(GROUPS[idx]).stroke(stroke);
+
+                            ui.add_enabled(false, swatch);
+                            policy::editor(ui, ("group", idx), policy, policy::DEFAULTS);
+
+                            ui.horizontal(|ui| {
+                                let tunnel_input = TextEdit::singleline(&mut self.tunnels[idx])
+                                    .hint_text(WidgetText::italics("Interfaz requerida".into()))
+                                    .font(TextStyle::Monospace);
+
+                                ui.label("Túnel");
+                                ui.add(tunnel_input);
+                            });
+
+                            archive::editor(ui, &mut self.archives[idx], &self.store);
+
+                            ui.separator();
+                        }
+
+                        if ui.button("Copiar reglas de alerta").clicked() {
+                            let rules = rules::export(&self.policies);
+                            ui.output_mut(|output| output.copied_text = rules);
+                            ui.close_menu();
+                        }
+
+                        if ui.button("Importar reglas de alerta…").clicked() {
+                            self.import.open = true;
+                            ui.close_menu();
+                        }
+
+                        ui.separator();
+
+                        if ui.button("Importar hosts desde CSV…").clicked() {
+                            self.hosts_import.open = true;
+                            ui.close_menu();
+                        }
+
+                        if ui.button("Importar del fichero hosts…").clicked() {
+                            self.hosts_file.open = Some(hosts::Source::Hosts);
+                            ui.close_menu();
+                        }
+
+                        if ui.button("Importar de la configuración SSH…").clicked() {
+                            self.hosts_file.open = Some(hosts::Source::Ssh);
+                            ui.close_menu();
+                        }
+
+                        if ui.button("Importar inventario de Ansible…").clicked() {
+                            self.inventory.open = true;
+                            ui.close_menu();
+                        }
+                    });
+
+                    ui.menu_button("Rango", |ui| {
+                        range::editor(ui, "range", &mut self.range);
+                    });
+
+                    ui.menu_button("Análisis", |ui| {
+                        if ui.button("Correlación de eventos…").clicked() {
+                            self.report.open = true;
+                            ui.close_menu();
+                        }
+
+                        if ui.button("Comparar rangos…").clicked() {
+                            self.compare.open = true;
+                            ui.close_menu();
+                        }
+
+                        if ui.button("Registro de acciones…").clicked() {
+                            self.audit.open = true;
+                            ui.close_menu();
+                        }
+
+                        ui.menu_button("Exportar sesión", |ui| {
+                            session::menu(ui, &mut self.session, &self.canvases);
+                        });
+
+                        ui.menu_button("Exportar disposición", |ui| {
+                            diagram::menu(
+                                ui,
+                                &mut self.layout_export,
+                                &self.canvases,
+                                self.clock.utc(),
+                            );
+                        });
+
+                        ui.separator();
+
+                        if ui.button("Añadir widget").clicked() {
+                            let canvas = &mut self.canvases[self.active];
+                            canvas.widgets.push(Widget::new(None));
+                            ui.close_menu();
+                        }
+                    });
+
+                    ui.menu_button("Sondeo", |ui| {
+                        backend::editor(ui, &mut self.backend);
+
+                        ui.collapsing("Permisos ICMP", |ui| {
+                            elevate::show(ui, &mut self.elevate);
+                        });
+
+                        ui.separator();
+
+                        let mut idle_stop = self.idle_stop.is_some();
+
+                        if ui
+                            .checkbox(&mut idle_stop, "Pausar tras inactividad")
+                            .changed()
+                        {
+                            self.idle_stop = idle_stop.then_some(DEFAULT_IDLE_STOP);
+                        }
+
+                        if let Some(limit) = &mut self.idle_stop {
+                            let mut hours = limit.as_secs() / 3600;
+                            let hours_input = DragValue::new(&mut hours)
+                                .clamp_range(1..=24 * 30)
+                                .suffix(" h");
+
+                            if ui.add(hours_input).changed() {
+                                *limit = Duration::from_secs(hours * 3600);
+                            }
+                        }
+
+                        ui.horizontal(|ui| {
+                            ui.label("Conservar");
+
+                            let history_input = DragValue::new(&mut self.history)
+                                .clamp_range(100..=10_000_000)
+                                .suffix(" muestras");
+
+                            ui.add(history_input)
+                                .on_hover_text("Por ventana, las más antiguas se descartan");
+                        });
+
+                        store::editor(ui, &mut self.store);
+
+                        if ui.button("Almacenamiento…").clicked() {
+                            self.store.open = true;
+                            ui.close_menu();
+                        }
+                    });
+
+                    ui.menu_button("Avisos", |ui| {
+                        let mut outlet = Outlet {
+                            ctx,
+                            frame,
+                            desktop: &mut self.desktop,
+                            clock: &self.clock,
+                        };
+
+                        self.notifiers.settings(ui, &mut outlet);
+                        self.escalation
+                            .editor(ui, "Webhook de escalado", &self.clock);
+                        ui.separator();
+
+                        let columns = self.notifiers.columns();
+                        routing::editor(ui, &mut self.routing, &columns);
+
+                        ui.horizontal(|ui| {
+                            let mut secs = self.batch.as_secs();
+
+                            ui.label("Agrupar avisos durante");
+
+                            let batch_input =
+                                DragValue::new(&mut secs).clamp_range(0..=600).suffix(" s");
+
+                            if ui.add(batch_input).changed() {
+                                self.batch = Duration::from_secs(secs);
+                            }
+                        });
+
+                        ui.horizontal(|ui| {
+                            let mut secs = self.grace.as_secs();
+
+                            ui.label("Silenciar al arrancar o reanudar durante");
+
+                            let grace_input =
+                                DragValue::new(&mut secs).clamp_range(0..=900).suffix(" s");
+
+                            if ui.add(grace_input).changed() {
+                                self.grace = Duration::from_secs(secs);
+                            }
+                        });
+                    });
+
+                    ui.menu_button("Receptor", |ui| {
+                        let toggle =
+                            ui.checkbox(&mut self.receiver_enabled, "Recibir latidos HTTP");
+                        let bind = ui.text_edit_singleline(&mut self.receiver_bind);
+
+                        if toggle.changed() || bind.lost_focus() {
+                            self.sync_receiver(ctx);
+                        }
+
+                        if let Some(error) = self.receiver.error() {
+                            ui.colored_label(FAIL, error);
+                        }
+                    });
+
+                    ui.menu_button("Difusión", |ui| {
+                        let toggle =
+                            ui.checkbox(&mut self.stream_enabled, "Emitir muestras por WebSocket");
+                        let bind = ui.text_edit_singleline(&mut self.stream_bind);
+
+                        if toggle.changed() || bind.lost_focus() {
+                            self.sync_streamer();
+                        }
+
+                        ComboBox::from_id_source("stream-format")
+                            .selected_text(self.stream_format.label())
+                            .show_ui(ui, |ui| {
+                                for format in stream::Format::ALL {
+                                    ui.selectable_value(
+                                        &mut self.stream_format,
+                                        format,
+                                        format.label(),
+                                    );
+                                }
+                            });
+
+                        if let Some(error) = self.streamer.error() {
+                            ui.colored_label(FAIL, error);
+                        } else if self.streamer.bind().is_some() {
+                            ui.weak(format!("{} clientes conectados", self.streamer.clients()));
+                        }
+                    });
+
+                    ui.menu_button("OpenTelemetry", |ui| {
+                        otlp::editor(ui, &mut self.otlp);
+                    });
+
+                    ui.menu_button("Unidades", |ui| {
+                        units::editor(ui, &mut self.units);
+                    });
+
+                    ui.menu_button("Disposición", |ui| {
+                        if ui.checkbox(&mut self.dark, "Tema oscuro").changed() {
+                            ctx.set_visuals(visuals(self.dark));
+                        }
+
+                        ui.checkbox(&mut self.guides, "Guías de alineación");
+
+                        ui.horizontal(|ui| {
+                            ui.checkbox(&mut self.snap, "Ajustar a rejilla");
+
+                            let input = DragValue::new(&mut self.grid)
+                                .clamp_range(2.0..=200.0)
+                                .suffix(" px");
+
+                            ui.add_enabled(self.snap, input);
+                        });
+
+                        ui.separator();
+                        update::editor(ui, &mut self.updates);
+
+                        ui.menu_button("Copia de seguridad", |ui| {
+                            let result = match backup::menu(ui, &mut self.backup) {
+                                Some(backup::Action::Backup) => self.backup(),
+                                Some(backup::Action::Restore) => self.restore(ctx),
+                                None => return,
+                            };
+
+                            self.backup.result = Some(result);
+                        });
+                    });
+                });
+
+                ui.separator();
+
+                let mut remove = None;
+
+                for (idx, canvas) in self.canvases.iter_mut().enumerate() {
+                    let tab = ui.selectable_value(&mut self.active, idx, &canvas.name);
+
+                    tab.context_menu(|ui| {
+                        ui.set_enabled(editable);
+                        ui.text_edit_singleline(&mut canvas.name);
+
+                        if ui.button("Eliminar").clicked() {
+                            remove = Some(idx);
+                            ui.close_menu();
+                        }
+                    });
+                }
+
+                if ui.add_enabled(editable, Button::new("➕")).clicked() {
+                    let name = format!("Lienzo {}", self.canvases.len() + 1);
+                    self.canvases.push(Canvas::new(name));
+                    self.active = self.canvases.len() - 1;
+                }
+
+                if let Some(idx) = remove.filter(|_| self.canvases.len() > 1) {
+                    self.canvases.remove(idx);
+                    self.active = self.active.min(self.canvases.len() - 1);
+                }
+
+                ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                    let label =
+                        ["Bloquear presentación", "Desbloquear presentación"][!editable as usize];
+
+                    let bell = match self.inbox.unread() {
+                        0 => "🔔".to_owned(),
+                        unread => format!("🔔 {}", unread),
+                    };
+
+                    let filter_input = TextEdit::singleline(&mut self.filter)
+                        .hint_text(WidgetText::italics("Filtrar".into()))
+                        .desired_width(120.);
+
+                    ui.add(filter_input);
+
+                    ui.toggle_value(&mut self.inbox.open, bell)
+                        .on_hover_text("Notificaciones");
+
+                    ui.toggle_value(&mut self.routing.quiet, "🔕")
+                        .on_hover_text("No molestar");
+
+                    ui.menu_button("🎬", |ui| {
+                        let passphrase_input = TextEdit::singleline(&mut self.passphrase)
+                            .hint_text(WidgetText::italics("Contraseña".into()))
+                            .password(true);
+
+                        ui.add(passphrase_input);
+
+                        let typed = !self.passphrase.trim().is_empty();
+
+                        if ui.add_enabled(typed, Button::new(label)).clicked() {
+                            match &self.presentation {
+                                None => {
+                                    self.presentation = presentation::Lock::new(&self.passphrase);
+                                    self.detail = None;
+                                }
+                                Some(lock) if lock.opens(&self.passphrase) => {
+                                    self.presentation = None
+                                }
+                                Some(_) => {}
+                            }
+
+                            self.passphrase.clear();
+                            ui.close_menu();
+                        }
+                    })
+                    .response
+                    .on_hover_text(label);
+
+                    ui.add_enabled_ui(editable, |ui| {
+                        ui.toggle_value(&mut self.locked, "🔒")
+                            .on_hover_text("Bloquear disposición");
+                    });
+                });
+            });
+        });
+
+        update::banner(ctx, &mut self.updates);
+        inbox::show(ctx, &mut self.inbox);
+        status::show(ctx, &self.canvases, &self.policies, self.units);
+
+        let canvas_rect = CentralPanel::default().show(ctx, |ui| {
+            let full_rect = ui.available_rect_before_wrap();
+            let interactable = ui.interact(full_rect, Id::new("void"), Sense::click());
+
+            if interactable.double_clicked() && !locked {
+                let origin = interactable.interact_pointer_pos().unwrap_or_default();
+                let canvas = &mut self.canvases[self.active];
+                canvas.windows.push(PingWindow::empty(Some(origin)));
+            }
+
+            if let Some(text) = paste::take(ctx).filter(|_| !locked) {
+                let origin = ctx
+                    .pointer_hover_pos()
+                    .filter(|pos| full_rect.contains(*pos))
+                    .unwrap_or(full_rect.min);
+
+                let canvas = &mut self.canvases[self.active];
+                canvas.windows.extend(paste::windows(&text, origin));
+            }
+
+            full_rect
+        });
+
+        self.placement.apply(&mut self.canvases, canvas_rect.inner);
+
+        let mut next_ping = Duration::from_secs(1);
+        let tunnels = self.tunnels.each_ref().map(|tunnel| interface_up(tunnel));
+
+        let routing = self.routing.clone();
+
+        let mut outlet = Outlet {
+            ctx,
+            frame,
+            desktop: &mut self.desktop,
+            clock: &self.clock,
+        };
+
+        let mut dispatch = |batch: &digest::Batch| {
+            let channels = routing.channels(batch.severity);
+            self.notifiers.send(
+                &mut outlet,
+                &channels,
+                &batch.title,
+                &batch.body,
+                batch.success,
+            );
+        };
+
+        let (settling, settled) = self.warmup.tick(self.grace, &self.clock);
+
+        for win in self
+            .canvases
+            .iter_mut()
+            .flat_map(|canvas| &mut canvas.windows)
+        {
+            let group = self.policies[win.group].over(policy::DEFAULTS);
+            let settings = win.policy.over(group);
+
+            if win.listen.is_some() {
+                self.inbound.start(ctx);
+            }
+
+            if !tunnels[win.group] {
+                win.success = None;
+                continue;
+            }
+
+            win.history.set_capacity(self.history);
+
+            let last = win.success;
+            let slow = win.degraded;
+            let polled = win.history.last().map(|sample| sample.time);
+            next_ping =
+                next_ping.min(win.poll(ctx, settings, &self.heartbeats, self.backend, &self.clock));
+
+            if win.scanning {
+                let endpoint = win.check.tls(&win.address);
+
+                win.certificate
+                    .poll(ctx, endpoint, settings.timeout, &self.clock);
+            }
+
+            let fresh = win
+                .history
+                .iter()
+                .rev()
+                .take_while(|sample| Some(sample.time) != polled)
+                .count();
+
+            let fresh = &win.history[win.history.len() - fresh..];
+            self.store.save(store::key(&win.address, &win.check), fresh);
+
+            if !fresh.is_empty() {
+                win.marks.sampled(ctx);
+            }
+
+            for sample in fresh {
+                self.streamer.publish(
+                    self.stream_format,
+                    &win.hostname,
+                    &win.address,
+                    win.group,
+                    sample,
+                );
+            }
+
+            if let (Some(last), Some(success)) = (last, win.success) {
+                if last != success {
+                    if !success {
+                        win.marks.down(ctx);
+                    }
+
+                    let title = [&win.hostname, &win.address][win.hostname.is_empty() as usize];
+                    let body = ["Ha dejado de responder", "Vuelve a responder"][success as usize];
+                    let alerted = !settling
+                        && settings.alert.fires(success)
+                        && win.alerting(&self.clock)
+                        && !routing.channels(Severity::Critical).is_empty();
+
+                    if alerted {
+                        self.digest.push(
+                            title,
+                            Some(win.group),
+                            Severity::Critical,
+                            success,
+                            body,
+                            self.clock.now(),
+                        );
+                    }
+
+                    self.inbox.push(title, success, alerted, &self.clock);
+                }
+            }
+
+            // Only while it keeps answering, going down or coming back is already critical.
+            if last == Some(true) && win.success == Some(true) && slow != win.degraded {
+                let title = [&win.hostname, &win.address][win.hostname.is_empty() as usize];
+                let fast = !win.degraded;
+                let body = [
+                    "Supera el umbral de latencia",
+                    "Vuelve a estar por debajo del umbral de latencia",
+                ][fast as usize];
+                let alerted = !settling
+                    && settings.alert.fires(fast)
+                    && win.alerting(&self.clock)
+                    && !routing.channels(Severity::Warning).is_empty();
+
+                if alerted {
+                    self.digest.push(
+                        title,
+                        Some(win.group),
+                        Severity::Warning,
+                        fast,
+                        body,
+                        self.clock.now(),
+                    );
+                }
+
+                self.inbox.push(title, fast, alerted, &self.clock);
+            }
+
+            let (remind, escalate) = match settling {
+                true => (false, false),
+                false => win.follow_up(settings, &self.clock),
+            };
+
+            let title = [&win.hostname, &win.address][win.hostname.is_empty() as usize];
+
+            // Whatever went down while alerts were held back and is still down gets its turn now.
+            let held = settled
+                && win.success == Some(false)
+                && settings.alert.fires(false)
+                && win.alerting(&self.clock)
+                && !routing.channels(Severity::Critical).is_empty();
+
+            if remind || held {
+                self.digest.push(
+                    title,
+                    Some(win.group),
+                    Severity::Critical,
+                    false,
+                    "Sigue sin responder",
+                    self.clock.now(),
+                );
+            }
+
+            if escalate && !routing.quiet {
+                self.escalation
+                    .send(title, "Sigue sin responder", false, &self.clock);
+            }
+        }
+
+        for batch in self.digest.flush(self.batch, self.clock.now()) {
+            dispatch(&batch);
+        }
+
+        next_ping = next_ping.min(self.digest.remaining(self.batch, self.clock.now()));
+
+        next_ping = next_ping.min(archive::run(
+            &mut self.archives,
+            &self.canvases,
+            &mut self.store,
+            &self.clock,
+        ));
+        self.store.run(
+            ctx,
+            &self.canvases,
+            archive::keep(&self.archives, &self.clock),
+            &self.clock,
+        );
+        self.otlp.export(&self.canvases, &self.clock);
+        next_ping = next_ping.min(self.otlp.remaining(&self.clock));
+
+        let scanning = self
+            .canvases
+            .iter()
+            .flat_map(|canvas| &canvas.windows)
+            .filter(|win| win.scanning && win.success.is_some())
+            .collect_vec();
+
+        let down = scanning
+            .iter()
+            .filter(|win| win.success == Some(false))
+            .map(|win| {
+                let title = [&win.hostname, &win.address][win.hostname.is_empty() as usize];
+
+                let rtt = win
+                    .history
+                    .iter()
+                    .rev()
+                    .find_map(|sample| match sample.reply.pong {
+                        Pong::Success(rtt) => Some(rtt),
+                        Pong::Failure => None,
+                    });
+
+                (title.clone(), rtt)
+            })
+            .collect_vec();
+
+        self.desktop.badge(ctx, frame, down.len());
+        self.desktop
+            .status(scanning.len() - down.len(), &down, self.units);
+
+        let windows = &mut self.canvases[self.active].windows;
+        let rects = windows.iter().map(|win| win.rect).collect_vec();
+        let mut split = vec![];
+
+        for (idx, win) in windows.iter_mut().enumerate() {
+            if !win.matches(&self.filter) {
+                win.rect = None;
+                continue;
+            }
+
+            let group = self.policies[win.group].over(policy::DEFAULTS);
+
+            let icon = "████";
+            let color = animate::fade(
+                ctx,
+                Id::new((win.ctime, "status")),
+                win.status(self.clock.utc()),
+            );
+
+            let mut job = LayoutJob::default();
+            let font_id = TextStyle::Monospace.resolve(&ctx.style());
+            let title = [&win.hostname, "Sin título"][win.hostname.is_empty() as usize];
+
+            let title_format = TextFormat {
+                font_id,
+                italics: win.hostname.is_empty(),
+                ..TextFormat::default()
+            };
+
+            let icon_format = TextFormat {
+                color,
+                italics: false,
+                ..title_format.clone()
+            };
+
+            job.append(icon, 12., icon_format);
+            job.append(title, 12., title_format.clone());
+            job.append(" ", 12., title_format);
+
+            let fill = win
+                .certificate
+                .color(self.clock.utc())
+                .unwrap_or(win.color.unwrap_or(GROUPS[win.group]))
+                .gamma_multiply(0.75);
+            let mut frame = Frame {
+                fill: animate::mix(fill, Color32::WHITE, 0.3 * win.marks.flash(ctx)),
+                ..Frame::window(&ctx.style())
+            };
+
+            let pulse = win.marks.pulse(ctx);
+
+            if pulse > 0. {
+                frame.stroke = Stroke::new(
+                    frame.stroke.width + 2. * pulse,
+                    animate::mix(frame.stroke.color, FAIL, pulse),
+                );
+            }
+
+            let mut window = Window::new(job)
+                .id(Id::new(win.ctime))
+                .default_width(200.)
+                .frame(frame)
+                .movable(!locked)
+                .resizable(!locked)
+                .collapsible(false);
+
+            if !locked {
+                window = window.open(&mut win.open);
+            }
+
+            if let Some(origin) = win.origin {
+                window = window.default_pos(origin);
+            }
+
+            if let Some(pos) = win.snap_to.take() {
+                window = window.current_pos(pos);
+            }
+
+            let mut expand = None;
+
+            let response = window.show(ctx, |ui| {
+                let content_top = ui.max_rect().top();
+
+                let last_addr = win.address.clone();
+
+                // Typed address and resolved IP, primary identity first.
+                let resolved = win
+                    .history
+                    .iter()
+                    .rev()
+                    .find_map(|sample| sample.reply.target);
+                let resolved = resolved.map_or("sin resolver".into(), |ip| ip.to_string());
+                let typed = dns::unicode(&last_addr);
+                let [primary, secondary] = match win.by_ip {
+                    false => [typed, resolved],
+                    true => [resolved, typed],
+                };
+
+                let addr_input = TextEdit::singleline(&mut win.address)
+                    .hint_text(WidgetText::italics("Direccion".into()))
+                    .desired_width(ui.available_width())
+                    .font(TextStyle::Monospace)
+                    .cursor_at_end(true);
+
+                ui.horizontal(|ui| {
+                    if editable {
+                        ui.vertical(|ui| {
+                            if ui.toggle_value(&mut win.scanning, "📶").clicked() {
+                                win.success = None;
+                            }
+
+                            ui.toggle_value(&mut win.show_plot, "📈");
+                            ui.toggle_value(&mut win.show_stats, " Σ ");
+                            ui.toggle_value(&mut win.show_scratchpad, " ¶ ");
+                            ui.toggle_value(&mut win.show_settings, "⚙");
+
+                            if ui.button("⛶").on_hover_text("Enfocar").clicked() {
+                                self.focus.enter(ctx, win.ctime);
+                            }
+
+                            if ui.button("🗺").on_hover_text("Trazar ruta").clicked() {
+                                let timeout = win.policy.over(group).timeout;
+                                win.trace.start(ctx, &last_addr, timeout);
+                            }
+                        });
+                    }
+
+                    ui.vertical_centered_justified(|ui| {
+                        if editable {
+                            ui.horizontal(|ui| {
+                                for (idx, color) in GROUPS.into_iter().enumerate() {
+                                    let stroke = Stroke::new(0.5, Color32::BLACK);
+                                    let button = Button::new("     ").fill(color).stroke(stroke);
+
+                                    if ui.add(button).clicked() {
+                                        win.group = idx;
+                                    }
+                                }
+                            });
+
+                            let host_input = TextEdit::singleline(&mut win.hostname)
+                                .hint_text(WidgetText::italics("Nombre".into()))
+                                .desired_width(ui.available_width())
+                                .font(TextStyle::Monospace)
+                                .cursor_at_end(true);
+
+                            ui.add(host_input);
+
+                            let label_input = TextEdit::singleline(&mut win.label)
+                                .hint_text(WidgetText::italics("Etiqueta".into()))
+                                .desired_width(ui.available_width())
+                                .font(TextStyle::Small)
+                                .cursor_at_end(true);
+
+                            ui.add(label_input);
+
+                            let mut addr_response = ui.add(addr_input);
+
+                            let unicode = dns::unicode(&last_addr);
+                            let ascii = dns::ascii(&last_addr);
+
+                            if unicode != ascii {
+                                let text = format!("{} ({})", unicode, ascii);
+                                addr_response = addr_response.on_hover_text(text);
+                            }
+
+                            if addr_response.changed() {
+                                win.peer = None;
+                            }
+
+                            if addr_response.secondary_clicked() {
+                                let open_url = OpenUrl {
+                                    url: format!("http://{}", last_addr),
+                                    new_tab: true,
+                                };
+
+                                ctx.open_url(open_url);
+                            }
+
+                            match cidr::hosts(&last_addr) {
+                                Some(Ok(hosts)) => {
+                                    let text = format!("Expandir en {} ventanas", hosts.len());
+
+                                    if ui.button(text).clicked() {
+                                        expand = Some(hosts);
+                                    }
+                                }
+                                Some(Err(error)) => {
+                                    ui.colored_label(FAIL, error);
+                                }
+                                None => {}
+                            }
+
+                            if win.by_ip {
+                                ui.weak(&primary);
+                            }
+                        } else {
+                            if !win.label.is_empty() {
+                                ui.small(&win.label);
+                            }
+
+                            ui.monospace(&primary).on_hover_text(&secondary);
+                        }
+
+                        if !tunnels[win.group] {
+                            let text = format!("VPN caída ({})", self.tunnels[win.group]);
+                            ui.colored_label(FAIL, text);
+                        }
+
+                        ack::show(
+                            ui,
+                            &mut win.ack,
+                            &mut win.ack_draft,
+                            win.success == Some(false),
+                            editable,
+                            self.clock.utc(),
+                        );
+
+                        if win.scanning
+                            && !schedule::active(&win.probe_schedule, self.clock.local())
+                        {
+                            ui.weak("Fuera de horario");
+                        }
+
+                        if win.listen.is_some() {
+                            let text = match win.peer.flatten().and_then(|ip| self.inbound.peer(ip))
+                            {
+                                Some(peer) => {
+                                    let last = peer.last.with_timezone(&Local).format("%H:%M:%S");
+                                    format!("⬅ {} solicitudes, última a las {}", peer.count, last)
+                                }
+                                None => "⬅ Sin solicitudes entrantes".into(),
+                            };
+
+                            ui.label(text);
+
+                            if let Some(error) = self.inbound.error() {
+                                ui.colored_label(FAIL, error);
+                            }
+                        }
+
+                        if let (true, Some(report)) = (win.ipv6, win.diagnosis) {
+                            let mark = |reply: Option<bool>| match reply {
+                                Some(true) => "✔",
+                                Some(false) => "✖",
+                                None => "—",
+                            };
+
+                            let mut text =
+                                format!("IPv4 {} · IPv6 {}", mark(report.v4), mark(report.v6));
+
+                            if let Some(hop_limit) = report.hop_limit {
+                                text += &format!(" · saltos {}", hop_limit);
+                            }
+
+                            if let Some(flow_label) = report.flow_label {
+                                text += &format!(" · flujo {:#07x}", flow_label);
+                            }
+
+                            match report.v6_broken() {
+                                true => ui.colored_label(
+                                    WARN,
+                                    text + " · responde por IPv4 pero no por IPv6",
+                                ),
+                                false => ui.label(text),
+                            };
+                        }
+
+                        tls::show(ui, &win.certificate, self.clock.utc());
+                        ntp::show(ui, win.history.last(), self.units);
+                        game::show(ui, win.history.last());
+
+                        let now = self.clock.utc();
+                        let range = win.range.unwrap_or(self.range);
+                        let start = range.start(now, win.policy.over(group).interval);
+
+                        ui.horizontal(|ui| {
+                            ui.set_visible(editable);
+                            ui.label("Sondas");
+                            ui.add(DragValue::new(&mut win.probes).clamp_range(1..=MAX_PROBES));
+
+                            if win.show_plot {
+                                ui.menu_button("🎨", |ui| {
+                                    let mut custom = win.theme.is_some();
+
+                                    if ui.checkbox(&mut custom, "Tema propio").changed() {
+                                        win.theme = custom.then_some(self.themes[win.group]);
+                                    }
+
+                                    if let Some(theme) = &mut win.theme {
+                                        theme_editor(ui, theme);
+                                    }
+                                });
+
+                                ui.toggle_value(&mut win.plot_jitter, "Jitter");
+                            }
+
+                            ui.menu_button("🕑", |ui| {
+                                let mut custom = win.range.is_some();
+
+                                if ui.checkbox(&mut custom, "Rango propio").changed() {
+                                    win.range = custom.then_some(self.range);
+                                }
+
+                                if let Some(range) = &mut win.range {
+                                    range::editor(ui, ("range", win.ctime), range);
+                                }
+                            });
+
+                            ui.menu_button("💾", |ui| {
+                                csv::menu(
+                                    ui,
+                                    &mut win.export,
+                                    &win.hostname,
+                                    &last_addr,
+                                    &win.history,
+                                );
+
+                                ui.separator();
+
+                                if ui.button("Copiar como imagen").clicked() {
+                                    self.capture.request(ctx, win.ctime);
+                                    ui.close_menu();
+                                }
+
+                                if let Some((host, result)) = &self.capture.result {
+                                    match result {
+                                        _ if *host != win.ctime => {}
+                                        Ok(done) => {
+                                            ui.colored_label(PASS, done);
+                                        }
+                                        Err(error) => {
+                                            ui.colored_label(FAIL, error);
+                                        }
+                                    }
+                                }
+                            });
+                        });
+
+                        let history = range.slice(&win.history, now);
+
+                        if win.show_stats {
+                            let span = win.percentiles.slice(&win.history, history, now);
+                            let summary = stats::Summary::of(history, span);
+
+                            stats::show(
+                                ui,
+                                ("stats", win.ctime),
+                                &summary,
+                                &mut win.percentiles,
+                                self.units,
+                            );
+                        }
+
+                        if win.show_plot {
+                            let theme = win.theme.unwrap_or(self.themes[win.group]);
+
+                            let points = plot::rounds(history)
+                                .into_iter()
+                                .map(|(time, round)| (plot::timestamp(time), round))
+                                .collect_vec();
+
+                            ui.scope(|ui| {
+                                if theme.background != Color32::TRANSPARENT {
+                                    ui.visuals_mut().extreme_bg_color = theme.background;
+                                }
+
+                                Plot::new("ping")
+                                    .show_axes(false)
+                                    .auto_bounds_y()
+                                    .include_x(plot::timestamp(start))
+                                    .include_x(plot::timestamp(now))
+                                    .allow_drag(Vec2b::FALSE)
+                                    .link_cursor(plot::CURSORS, true, false)
+                                    .reset()
+                                    .label_formatter(plot::rtt_label(self.units))
+                                    .show(ui, |ui| {
+                                        Series::new(&points, theme).show(ui);
+
+                                        if win.plot_jitter {
+                                            let jitter = Line::new(plot::jitter(history))
+                                                .color(theme.line)
+                                                .style(LineStyle::dashed_loose())
+                                                .name("Jitter");
+
+                                            ui.line(jitter);
+                                        }
+                                    });
+                            });
+                        } else {
+                            let key = store::key(&last_addr, &win.check);
+                            let store = self.store.enabled().then_some((&mut self.store, &*key));
+
+                            table::history(
+                                ui,
+                                history,
+                                &mut win.table,
+                                &mut win.columns,
+                                store,
+                                150.,
+                                self.units,
+                            );
+                        }
+
+                        if win.show_scratchpad && editable {
+                            let scratch_input = TextEdit::multiline(&mut win.scratchpad)
+                                .font(TextStyle::Monospace)
+                                .hint_text(WidgetText::italics("Anotaciones".into()));
+
+                            ui.add(scratch_input);
+                        }
+
+                        if win.show_settings && editable {
+                            let bind = self.receiver.bind();
+                            check::editor(
+                                ui,
+                                ("check", win.ctime),
+                                &mut win.check,
+                                &last_addr,
+                                bind,
+                            );
+                            let interface_input = TextEdit::singleline(&mut win.interface)
+                                .hint_text(WidgetText::italics("Interfaz".into()))
+                                .font(TextStyle::Monospace);
+
+                            ui.add(interface_input);
+
+                            for (schedule, hint) in [
+                                (&mut win.probe_schedule, "Horario de sondeo (cron)"),
+                                (&mut win.alert_schedule, "Horario de avisos (cron)"),
+                            ] {
+                                let schedule_input = TextEdit::singleline(schedule)
+                                    .hint_text(WidgetText::italics(hint.into()))
+                                    .font(TextStyle::Monospace);
+
+                                ui.add(schedule_input)
+                                    .on_hover_text("p. ej. * 9-17 * * 1-5");
+
+                                if !schedule.trim().is_empty() {
+                                    if let Err(err) = schedule::Cron::parse(schedule) {
+                                        ui.colored_label(FAIL, err);
+                                    }
+                                }
+                            }
+
+                            ui.checkbox(&mut win.by_ip, "Identificar por IP resuelta");
+
+                            ui.horizontal(|ui| {
+                                let mut custom = win.color.is_some();
+
+                                if ui.checkbox(&mut custom, "Color propio").changed() {
+                                    win.color = custom.then_some(GROUPS[win.group]);
+                                }
+
+                                if let Some(color) = &mut win.color {
+                                    ui.color_edit_button_srgba(color);
+                                }
+                            });
+
+                            if ui.checkbox(&mut win.ipv6, "Diagnóstico IPv6").changed() {
+                                win.diagnosis = None;
+                            }
+
+                            let secure = win.check.tls(&last_addr).is_some();
+                            tls::editor(ui, &mut win.certificate, secure);
+
+                            let mut listen = win.listen.is_some();
+
+                            if ui.checkbox(&mut listen, "Contar pings entrantes").changed() {
+                                win.listen = listen.then(String::new);
+                                win.peer = None;
+                            }
+
+                            if let Some(source) = &mut win.listen {
+                                let source_input = TextEdit::singleline(source)
+                                    .hint_text(WidgetText::italics("Origen".into()))
+                                    .font(TextStyle::Monospace);
+
+                                if ui.add(source_input).changed() {
+                                    win.peer = None;
+                                }
+                            }
+
+                            policy::editor(ui, ("host", win.ctime), &mut win.policy, group);
+                        }
+                    });
+                });
+
+                content_top
+            });
+
+            if let Some(hosts) = expand {
+                split.extend(cidr::split(win, &hosts));
+                win.open = false;
+            }
+
+            if let Some(response) = response {
+                let rect = response.response.rect;
+                let layer = response.response.layer_id;
+
+                if let Some(content_top) = response.inner {
+                    let title_bar = Rect::from_x_y_ranges(rect.x_range(), rect.top()..=content_top);
+
+                    let double_clicked = ctx.input(|input| {
+                        input.pointer.button_double_clicked(PointerButton::Primary)
+                            && input.pointer.interact_pos().is_some_and(|pos| {
+                                title_bar.contains(pos) && ctx.layer_id_at(pos) == Some(layer)
+                            })
+                    });
+
+                    if double_clicked && editable {
+                        self.detail = Some(win.ctime);
+                    }
+                }
+                let dragging =
+                    ctx.memory(|mem| mem.is_being_dragged(Id::new(win.ctime).with("move")));
+
+                let others = rects
+                    .iter()
+                    .enumerate()
+                    .filter(|&(other, _)| other != idx)
+                    .filter_map(|(_, rect)| *rect);
+
+                let guides = match self.guides {
+                    true => snap::guides(rect, others),
+                    false => Guides::default(),
+                };
+
+                if dragging {
+                    let layer = LayerId::new(Order::Foreground, Id::new("guides"));
+                    let stroke = ctx.style().visuals.selection.stroke;
+
+                    for &line in &guides.lines {
+                        ctx.layer_painter(layer).line_segment(line, stroke);
+                    }
+                }
+
+                if win.dragging && !dragging {
+                    let grid = snap::to_grid(rect.min, self.grid);
+
+                    let x = match (guides.dx, self.snap) {
+                        (Some(dx), _) => rect.min.x + dx,
+                        (None, true) => grid.x,
+                        (None, false) => rect.min.x,
+                    };
+
+                    let y = match (guides.dy, self.snap) {
+                        (Some(dy), _) => rect.min.y + dy,
+                        (None, true) => grid.y,
+                        (None, false) => rect.min.y,
+                    };
+
+                    win.snap_to = Some(Pos2::new(x, y));
+                }
+
+                win.dragging = dragging;
+                win.rect = Some(rect);
+            }
+        }
+
+        minimap::show(ctx, windows, canvas_rect.inner, locked, self.clock.utc());
+
+        for win in windows.iter_mut() {
+            trace::show(ctx, win, self.units);
+        }
+
+        if let Some(ctime) = self.detail {
+            let mut open = false;
+
+            if let Some(win) = windows.iter_mut().find(|win| win.ctime == ctime) {
+                let group = self.policies[win.group].over(policy::DEFAULTS);
+                let theme = win.theme.unwrap_or(self.themes[win.group]);
+                let range = win.range.unwrap_or(self.range);
+
+                let now = self.clock.utc();
+                open = detail::show(ctx, win, group, theme, range, self.units, now);
+            }
+
+            if !open {
+                self.detail = None;
+            }
+        }
+
+        self.capture.run(ctx, windows);
+
+        focus::show(
+            ctx,
+            &mut self.focus,
+            windows,
+            &self.themes,
+            self.range,
+            self.units,
+            self.clock.utc(),
+        );
+
+        windows.extend(split);
+        windows.retain(|win| win.open);
+
+        let crossings = widget::show(
+            ctx,
+            &mut self.canvases,
+            self.active,
+            self.range,
+            self.units,
+            locked,
+            &self.clock,
+        );
+
+        for (title, ok) in crossings {
+            let body = ["Supera el umbral", "Vuelve a estar por debajo del umbral"][ok as usize];
+            let alerted = !settling && !self.routing.channels(Severity::Warning).is_empty();
+
+            if alerted {
+                self.digest
+                    .push(&title, None, Severity::Warning, ok, body, self.clock.now());
+            }
+
+            self.inbox.push(&title, ok, alerted, &self.clock);
+        }
+
+        correlate::show(ctx, &mut self.report, &self.canvases, &self.policies);
+        compare::show(
+            ctx,
+            &mut self.compare,
+            &self.canvases,
+            self.units,
+            &self.clock,
+        );
+        store::show(ctx, &mut self.store, &self.canvases, &self.clock);
+        rules::show(ctx, &mut self.import, &mut self.policies);
+        csv::import(
+            ctx,
+            &mut self.hosts_import,
+            &mut self.canvases[self.active].windows,
+        );
+        hosts::show(
+            ctx,
+            &mut self.hosts_file,
+            &mut self.canvases[self.active].windows,
+        );
+        inventory::show(
+            ctx,
+            &mut self.inventory,
+            &mut self.canvases[self.active].windows,
+        );
+        idle::show(ctx, &mut self.idle, &mut self.canvases, &self.clock);
+        resume::show(ctx, &mut self.resume, &mut self.canvases, &self.clock);
+
+        self.audit
+            .compare(snapshot, &self.canvases, &self.policies, &self.clock);
+        audit::show(ctx, &mut self.audit);
+        ctx.request_repaint_after(self.clock.real(next_ping));
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_now() -> Instant {
+    Instant::now()
+}
+
+fn visuals(dark: bool) -> Visuals {
+    match dark {
+        true => Visuals::dark(),
+        false => Visuals::light(),
+    }
+}
+
+fn default_receiver_bind() -> String {
+    DEFAULT_RECEIVER_BIND.into()
+}
+
+fn default_stream_bind() -> String {
+    DEFAULT_STREAM_BIND.into()
+}
+
+fn interface_up(interface: &str) -> bool {
+    interface.is_empty() || !cfg!(unix) || ipv6::scope_id(interface) != 0
+}
+
+fn default_history() -> usize {
+    ring::DEFAULT_CAPACITY
+}
+
+fn default_batch() -> Duration {
+    DEFAULT_BATCH
+}
+
+fn default_grace() -> Duration {
+    DEFAULT_GRACE
+}
+
+fn default_grid() -> f32 {
+    DEFAULT_GRID
+}
+
+fn default_probes() -> usize {
+    DEFAULT_PROBES
+}
+
+fn theme_editor(ui: &mut Ui, theme: &mut PlotTheme) {
+    ui.horizontal(|ui| {
+        ui.label("Línea");
+        ui.color_edit_button_srgba(&mut theme.line);
+        ui.label("Relleno");
+        ui.color_edit_button_srgba(&mut theme.fill);
+        ui.label("Fondo");
+        ui.color_edit_button_srgba(&mut theme.background);
+    });
+}
+
+fn do_ping(
+    addr: &str,
+    probes: usize,
+    timeout: Duration,
+    interface: &str,
+    backend: Backend,
+) -> Vec<Reply> {
+    let Some(ip) = check::resolve(addr) else {
+        return Reply::failures(None, probes);
+    };
+
+    let backend = backend.resolve(ip);
+
+    if backend == Backend::System {
+        return check::fan_out(ip, probes, timeout, |_| async move {
+            let (pong, ttl) = backend::system_ping(ip, timeout).await;
+            Some(Reply {
+                ttl,
+                ..Reply::new(pong)
+            })
+        });
+    }
+
+    let mut config = match ip {
+        IpAddr::V4(_) => Config::builder(),
+        IpAddr::V6(_) => Config::builder().kind(ICMP::V6),
+    };
+
+    config = config.sock_type_hint(backend.socket_type());
+
+    if !interface.is_empty() {
+        config = config.interface(interface);
+    }
+
+    let config = config.build();
+    let scope = ipv6::scope_id(interface);
+
+    // The socket is registered with the runtime, so it has to be made from inside it.
+    let Ok(client) = worker::block_on(async { Client::new(&config) }) else {
+        return Reply::failures(Some(ip), probes);
+    };
+
+    check::fan_out(ip, probes, timeout, |seq| {
+        let client = client.clone();
+
+        async move {
+            let mut pinger = client.pinger(ip, PingIdentifier(random())).await;
+            pinger.timeout(timeout).scope_id(scope);
+
+            let reply = match pinger.ping(PingSequence(seq as u16), &[]).await.ok()? {
+                (IcmpPacket::V4(packet), duration) => Reply {
+                    ttl: packet.get_ttl(),
+                    size: packet.get_size(),
+                    ..Reply::new(Pong::Success(duration))
+                },
+                (IcmpPacket::V6(packet), duration) => Reply {
+                    ttl: Some(packet.get_max_hop_limit()).filter(|&hops| hops != 0),
+                    size: packet.get_size(),
+                    ..Reply::new(Pong::Success(duration))
+                },
+            };
+
+            Some(reply)
+        }
+    })
+}
+
+fn main() {
+    let args = std::env::args().skip(1).collect_vec();
+
+    if args.iter().any(|arg| arg == "--headless") {
+        std::process::exit(headless::run(&args));
+    }
+
+    let _ = eframe::run_native(
+        "PingA",
+        NativeOptions::default(),
+        Box::new(|cc| Box::new(PingApp::new(cc))),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{
+        ack::Ack,
+        clock::Clock,
+        policy::{self, Alert, Settings},
+        PingWindow, Pong, Probe, Reply, ACKED, FAIL,
+    };
+
+    fn round(clock: &Clock, up: bool) -> Probe {
+        let pong = [Pong::Failure, Pong::Success(Duration::from_millis(5))][up as usize];
+
+        Probe {
+            time: clock.utc(),
+            replies: vec![Reply::new(pong); 3],
+            diagnosis: None,
+        }
+    }
+
+    #[test]
+    fn retries_before_reporting_down() {
+        let clock = Clock::manual();
+        let settings = Settings {
+            retries: 2,
+            ..policy::DEFAULTS
+        };
+        let mut win = PingWindow::new("host", "127.0.0.1", None);
+
+        win.settle(round(&clock, true), settings, &clock);
+        assert_eq!(win.success, Some(true));
+
+        for _ in 0..settings.retries {
+            win.settle(round(&clock, false), settings, &clock);
+            assert_eq!(win.success, Some(true));
+            assert!(win.outage.is_none());
+        }
+
+        win.settle(round(&clock, false), settings, &clock);
+        assert_eq!(win.success, Some(false));
+        assert!(win.outage.is_some());
+
+        win.settle(round(&clock, true), settings, &clock);
+        assert_eq!((win.success, win.misses), (Some(true), 0));
+    }
+
+    #[test]
+    fn a_host_first_seen_down_gets_no_retries() {
+        let clock = Clock::manual();
+        let settings = Settings {
+            retries: 2,
+            ..policy::DEFAULTS
+        };
+        let mut win = PingWindow::new("host", "127.0.0.1", None);
+
+        win.settle(round(&clock, false), settings, &clock);
+        assert_eq!(win.success, Some(false));
+    }
+
+    #[test]
+    fn reminds_and_escalates_on_schedule() {
+        let clock = Clock::manual();
+        let settings = Settings {
+            alert: Alert::Down,
+            repeat: Duration::from_secs(60),
+            escalate: Duration::from_secs(150),
+            ..policy::DEFAULTS
+        };
+        let mut win = PingWindow::new("host", "127.0.0.1", None);
+
+        win.settle(round(&clock, false), settings, &clock);
+        assert_eq!(win.follow_up(settings, &clock), (false, false));
+
+        clock.advance(Duration::from_secs(60));
+        assert_eq!(win.follow_up(settings, &clock), (true, false));
+        assert_eq!(win.follow_up(settings, &clock), (false, false));
+
+        clock.advance(Duration::from_secs(90));
+        assert_eq!(win.follow_up(settings, &clock), (true, true));
+
+        clock.advance(Duration::from_secs(60));
+        assert_eq!(win.follow_up(settings, &clock), (true, false));
+    }
+
+    #[test]
+    fn an_expired_ack_pages_again() {
+        let clock = Clock::manual();
+        let settings = Settings {
+            alert: Alert::Down,
+            ..policy::DEFAULTS
+        };
+        let mut win = PingWindow::new("host", "127.0.0.1", None);
+        win.scanning = true;
+
+        win.settle(round(&clock, false), settings, &clock);
+        win.ack = Some(Ack {
+            note: String::new(),
+            until: Some(clock.utc() + chrono::Duration::minutes(5)),
+        });
+
+        assert_eq!(win.status(clock.utc()), ACKED);
+        assert!(!win.alerting(&clock));
+
+        clock.advance(Duration::from_secs(5 * 60));
+        assert_eq!(win.status(clock.utc()), FAIL);
+        assert!(win.alerting(&clock));
+    }
 }
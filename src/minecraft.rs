@@ -0,0 +1,165 @@
+use std::{
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
+
+use egui::{DragValue, Id, Ui};
+use serde::{Deserialize, Serialize};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+
+use crate::{
+    check::{Kind, Target},
+    game::{self, Players},
+    Reply,
+};
+
+const STATUS: u8 = 0x00;
+const PING: u8 = 0x01;
+const NEXT_STATUS: i32 = 1;
+const ANY_VERSION: i32 = -1;
+const MAX_PACKET: usize = 1 << 21;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Minecraft {
+    pub port: u16,
+}
+
+impl Minecraft {
+    pub const DEFAULT: Minecraft = Minecraft { port: 25565 };
+}
+
+impl Kind for Minecraft {
+    fn label(&self) -> &'static str {
+        "Minecraft"
+    }
+
+    fn ping(&self, target: &Target) -> Vec<Reply> {
+        let host = target.address.to_owned();
+
+        game::ping(
+            target.address,
+            self.port,
+            target.probes,
+            target.timeout,
+            move |target| probe(target, host.clone()),
+        )
+    }
+
+    fn editor(&mut self, ui: &mut Ui, _id: Id, _address: &str, _bind: Option<&str>) {
+        ui.horizontal(|ui| {
+            ui.label("Puerto");
+            ui.add(DragValue::new(&mut self.port).clamp_range(1..=u16::MAX));
+        });
+    }
+}
+
+#[derive(Deserialize)]
+struct Status {
+    players: StatusPlayers,
+}
+
+#[derive(Deserialize)]
+struct StatusPlayers {
+    online: u32,
+    max: u32,
+}
+
+// Server list ping as of 1.7: handshake into the status state, ask for the status JSON and time
+// the ping packet that follows it.
+async fn probe(target: SocketAddr, host: String) -> Option<(Duration, Players)> {
+    let mut stream = TcpStream::connect(target).await.ok()?;
+
+    let mut handshake = varint(ANY_VERSION);
+    handshake.extend(varint(host.len() as i32));
+    handshake.extend(host.as_bytes());
+    handshake.extend(target.port().to_be_bytes());
+    handshake.extend(varint(NEXT_STATUS));
+
+    write(&mut stream, STATUS, &handshake).await?;
+    write(&mut stream, STATUS, &[]).await?;
+
+    let (id, body) = read(&mut stream).await?;
+
+    if id != STATUS {
+        return None;
+    }
+
+    let mut body = &body[..];
+    let len = usize::try_from(read_varint(&mut body).await?).ok()?;
+    let status = serde_json::from_slice::<Status>(body.get(..len)?).ok()?;
+
+    let payload = rand::random::<i64>().to_be_bytes();
+    let start = Instant::now();
+    write(&mut stream, PING, &payload).await?;
+
+    let (id, body) = read(&mut stream).await?;
+    let elapsed = start.elapsed();
+
+    if id != PING || body != payload {
+        return None;
+    }
+
+    let players = Players {
+        online: status.players.online,
+        max: status.players.max,
+    };
+
+    Some((elapsed, players))
+}
+
+async fn read(stream: &mut TcpStream) -> Option<(u8, Vec<u8>)> {
+    let len = usize::try_from(read_varint(stream).await?).ok()?;
+
+    if len == 0 || len > MAX_PACKET {
+        return None;
+    }
+
+    let mut packet = vec![0; len];
+    stream.read_exact(&mut packet).await.ok()?;
+
+    let mut body = &packet[..];
+    let id = u8::try_from(read_varint(&mut body).await?).ok()?;
+    Some((id, body.to_vec()))
+}
+
+async fn write(stream: &mut TcpStream, id: u8, body: &[u8]) -> Option<()> {
+    let mut packet = varint(body.len() as i32 + 1);
+    packet.push(id);
+    packet.extend(body);
+    stream.write_all(&packet).await.ok()
+}
+
+fn varint(value: i32) -> Vec<u8> {
+    let mut value = value as u32;
+    let mut bytes = vec![];
+
+    loop {
+        let byte = (value & 0x7F) as u8;
+        value >>= 7;
+
+        if value == 0 {
+            bytes.push(byte);
+            return bytes;
+        }
+
+        bytes.push(byte | 0x80);
+    }
+}
+
+async fn read_varint<R: AsyncRead + Unpin>(reader: &mut R) -> Option<i32> {
+    let mut value = 0u32;
+
+    for shift in (0..35).step_by(7) {
+        let byte = reader.read_u8().await.ok()?;
+        value |= ((byte & 0x7F) as u32) << shift;
+
+        if byte & 0x80 == 0 {
+            return Some(value as i32);
+        }
+    }
+
+    None
+}
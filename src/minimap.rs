@@ -0,0 +1,114 @@
+use chrono::{DateTime, Utc};
+use egui::{
+    Align2, Area, Button, Color32, Context, Frame, Id, Order, Rect, Rounding, Sense, Stroke, Ui,
+    Vec2,
+};
+
+use crate::{PingWindow, NONE};
+
+const MARGIN: f32 = 24.;
+const SIZE: Vec2 = Vec2::new(160., 100.);
+const CASCADE: Vec2 = Vec2::new(24., 24.);
+
+// A window counts as lost once too little of it is left on the canvas to grab it.
+fn lost(area: Rect, rect: Rect) -> bool {
+    !area.shrink(MARGIN).intersects(rect)
+}
+
+pub fn show(
+    ctx: &Context,
+    windows: &mut [PingWindow],
+    area: Rect,
+    locked: bool,
+    now: DateTime<Utc>,
+) {
+    let rects = windows
+        .iter()
+        .filter_map(|win| win.rect)
+        .collect::<Vec<_>>();
+    let lost_count = rects.iter().filter(|&&rect| lost(area, rect)).count();
+
+    if lost_count == 0 {
+        return;
+    }
+
+    let mut gather = false;
+
+    Area::new(Id::new("minimap"))
+        .order(Order::Foreground)
+        .anchor(Align2::RIGHT_BOTTOM, [-8., -8.])
+        .show(ctx, |ui| {
+            Frame::popup(ui.style()).show(ui, |ui| {
+                let text = match lost_count {
+                    1 => "1 ventana fuera de la vista".to_owned(),
+                    count => format!("{} ventanas fuera de la vista", count),
+                };
+
+                ui.label(text);
+                paint(ui, windows, area, now);
+
+                gather = ui
+                    .add_enabled(!locked, Button::new("Reunir aquí"))
+                    .clicked();
+            });
+        });
+
+    if !gather {
+        return;
+    }
+
+    let mut pos = area.min + CASCADE;
+
+    for win in windows {
+        let Some(rect) = win.rect.filter(|&rect| lost(area, rect)) else {
+            continue;
+        };
+
+        win.snap_to = Some(pos);
+        win.rect = Some(Rect::from_min_size(pos, rect.size()));
+        pos += CASCADE;
+
+        if !area.contains(pos + Vec2::splat(MARGIN)) {
+            pos = area.min + CASCADE;
+        }
+    }
+}
+
+fn paint(ui: &mut Ui, windows: &[PingWindow], area: Rect, now: DateTime<Utc>) {
+    let (response, painter) = ui.allocate_painter(SIZE, Sense::hover());
+
+    let bounds = windows
+        .iter()
+        .filter_map(|win| win.rect)
+        .fold(area, |bounds, rect| bounds.union(rect));
+
+    let scale = (SIZE / bounds.size()).min_elem();
+    let offset = response.rect.center() - bounds.center() * scale;
+    let map = |rect: Rect| {
+        Rect::from_min_max(
+            (rect.min.to_vec2() * scale + offset).to_pos2(),
+            (rect.max.to_vec2() * scale + offset).to_pos2(),
+        )
+    };
+
+    let stroke = ui.visuals().widgets.noninteractive.fg_stroke;
+    painter.rect_stroke(map(area), Rounding::ZERO, stroke);
+
+    for win in windows {
+        let Some(rect) = win.rect else {
+            continue;
+        };
+
+        let color = match win.status(now) {
+            NONE => Color32::GRAY,
+            color => color,
+        };
+
+        let mapped = map(rect);
+        painter.rect_filled(mapped.expand(0.5), Rounding::ZERO, color);
+
+        if lost(area, rect) {
+            painter.circle_stroke(mapped.center(), 4., Stroke::new(1., color));
+        }
+    }
+}
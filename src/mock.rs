@@ -0,0 +1,184 @@
+use std::{f64::consts::TAU, time::Duration};
+
+use egui::{ComboBox, DragValue, Id, Ui};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use tokio::time;
+
+use crate::{
+    check::{Kind, Target},
+    clock::Clock,
+    worker, Pong, Reply,
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Distribution {
+    Constant,
+    Uniform,
+    #[default]
+    Normal,
+    LongTail,
+}
+
+impl Distribution {
+    const ALL: [Distribution; 4] = [
+        Distribution::Constant,
+        Distribution::Uniform,
+        Distribution::Normal,
+        Distribution::LongTail,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Distribution::Constant => "Constante",
+            Distribution::Uniform => "Uniforme",
+            Distribution::Normal => "Normal",
+            Distribution::LongTail => "Cola larga",
+        }
+    }
+
+    // Milliseconds around `latency`, `spread` being the half width, deviation or tail scale.
+    fn sample(self, latency: f64, spread: f64) -> f64 {
+        let value = match self {
+            Distribution::Constant => latency,
+            Distribution::Uniform => latency + spread * (2. * rand::random::<f64>() - 1.),
+            Distribution::Normal => latency + spread * gaussian(),
+            Distribution::LongTail => latency * (spread / latency.max(1.) * gaussian()).exp(),
+        };
+
+        value.max(0.)
+    }
+}
+
+// Synthetic target for rehearsing alert rules and thresholds without touching real hosts. The
+// address is ignored.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Mock {
+    pub latency: u32,
+    pub spread: u32,
+    pub distribution: Distribution,
+    pub loss: u8,
+    pub outage_every: u32,
+    pub outage_for: u32,
+}
+
+impl Mock {
+    pub const DEFAULT: Mock = Mock {
+        latency: 20,
+        spread: 5,
+        distribution: Distribution::Normal,
+        loss: 0,
+        outage_every: 0,
+        outage_for: 0,
+    };
+
+    // Outages follow the wall clock, so every window with the same schedule fails together.
+    fn in_outage(&self, clock: &Clock) -> bool {
+        if self.outage_every == 0 || self.outage_for == 0 {
+            return false;
+        }
+
+        let phase = clock.utc().timestamp().rem_euclid(self.outage_every as i64);
+        phase < self.outage_for as i64
+    }
+}
+
+impl Kind for Mock {
+    fn label(&self) -> &'static str {
+        "Simulado"
+    }
+
+    fn ping(&self, target: &Target) -> Vec<Reply> {
+        let failures = Reply::failures(None, target.probes);
+
+        if self.in_outage(target.clock) {
+            return failures;
+        }
+
+        let timeout = target.timeout;
+        let replies = failures
+            .into_iter()
+            .map(|reply| {
+                let lost = rand::random::<f64>() * 100. < self.loss as f64;
+                let rtt = self
+                    .distribution
+                    .sample(self.latency as f64, self.spread as f64);
+                let rtt = Duration::from_secs_f64(rtt / 1000.);
+
+                (reply, (!lost && rtt < timeout).then_some(rtt))
+            })
+            .collect_vec();
+
+        // The samples are waited out for real, so scheduling and timeouts behave as with a host.
+        worker::block_on(async move {
+            let longest = replies
+                .iter()
+                .filter_map(|(_, rtt)| *rtt)
+                .max()
+                .unwrap_or_default();
+
+            time::sleep(longest).await;
+
+            replies
+                .into_iter()
+                .map(|(reply, rtt)| match rtt {
+                    Some(rtt) => Reply {
+                        pong: Pong::Success(rtt),
+                        ..reply
+                    },
+                    None => reply,
+                })
+                .collect()
+        })
+    }
+
+    fn editor(&mut self, ui: &mut Ui, id: Id, _address: &str, _bind: Option<&str>) {
+        ui.horizontal(|ui| {
+            ui.label("Latencia");
+            ui.add(DragValue::new(&mut self.latency).suffix(" ms"));
+            ui.label("±");
+            ui.add(DragValue::new(&mut self.spread).suffix(" ms"));
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Distribución");
+
+            ComboBox::from_id_source(id.with("distribution"))
+                .selected_text(self.distribution.label())
+                .show_ui(ui, |ui| {
+                    for distribution in Distribution::ALL {
+                        ui.selectable_value(
+                            &mut self.distribution,
+                            distribution,
+                            distribution.label(),
+                        );
+                    }
+                });
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Pérdida");
+            ui.add(
+                DragValue::new(&mut self.loss)
+                    .clamp_range(0..=100)
+                    .suffix(" %"),
+            );
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Caída de");
+            ui.add(DragValue::new(&mut self.outage_for).suffix(" s"));
+            ui.label("cada");
+            ui.add(DragValue::new(&mut self.outage_every).suffix(" s"));
+        });
+
+        ui.weak("Objetivo de pruebas, no sale a la red");
+    }
+}
+
+// Box-Muller, one standard normal sample.
+fn gaussian() -> f64 {
+    let u = 1. - rand::random::<f64>();
+    let v = rand::random::<f64>();
+    (-2. * u.ln()).sqrt() * (TAU * v).cos()
+}
@@ -0,0 +1,236 @@
+use std::{
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
+
+use egui::{DragValue, Id, Ui};
+use ring::digest;
+use serde::{Deserialize, Serialize};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+
+use crate::{
+    check::{Kind, Target},
+    database::{self, Login},
+    Reply,
+};
+
+const DEFAULT_USER: &str = "root";
+const NATIVE: &str = "mysql_native_password";
+const CACHING: &str = "caching_sha2_password";
+
+const LONG_PASSWORD: u32 = 0x1;
+const CONNECT_WITH_DB: u32 = 0x8;
+const PROTOCOL_41: u32 = 0x200;
+const SECURE_CONNECTION: u32 = 0x8000;
+const PLUGIN_AUTH: u32 = 0x80000;
+
+const UTF8MB4: u8 = 45;
+const COM_QUIT: u8 = 0x01;
+const COM_QUERY: u8 = 0x03;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Mysql {
+    pub port: u16,
+    pub login: Login,
+}
+
+impl Mysql {
+    pub const DEFAULT: Mysql = Mysql {
+        port: 3306,
+        login: Login::EMPTY,
+    };
+}
+
+impl Kind for Mysql {
+    fn label(&self) -> &'static str {
+        "MySQL"
+    }
+
+    fn ping(&self, target: &Target) -> Vec<Reply> {
+        let login = self.login.clone();
+
+        database::ping(
+            target.address,
+            self.port,
+            target.probes,
+            target.timeout,
+            move |target| probe(target, login.clone()),
+        )
+    }
+
+    fn editor(&mut self, ui: &mut Ui, _id: Id, _address: &str, _bind: Option<&str>) {
+        ui.horizontal(|ui| {
+            ui.label("Puerto");
+            ui.add(DragValue::new(&mut self.port).clamp_range(1..=u16::MAX));
+        });
+
+        database::editor(ui, &mut self.login, DEFAULT_USER, "Base de datos (ninguna)");
+    }
+}
+
+// Without TLS, caching_sha2_password only gets through while the server has the login cached.
+async fn probe(target: SocketAddr, login: Login) -> Option<Duration> {
+    let mut stream = TcpStream::connect(target).await.ok()?;
+    let password = login.password()?;
+    let user = [&*login.user, DEFAULT_USER][login.user.is_empty() as usize];
+
+    let (seq, greeting) = read(&mut stream).await?;
+    let (scramble, plugin) = greeting_auth(&greeting)?;
+    let plugin = [plugin.as_str(), NATIVE][plugin.is_empty() as usize];
+
+    let mut flags = LONG_PASSWORD | PROTOCOL_41 | SECURE_CONNECTION | PLUGIN_AUTH;
+
+    if !login.database.is_empty() {
+        flags |= CONNECT_WITH_DB;
+    }
+
+    let auth = scramble_password(plugin, &password, &scramble)?;
+
+    let mut response = flags.to_le_bytes().to_vec();
+    response.extend((1u32 << 24).to_le_bytes());
+    response.push(UTF8MB4);
+    response.extend([0; 23]);
+    response.extend(nul(user.as_bytes()));
+    response.push(auth.len() as u8);
+    response.extend(auth);
+
+    if !login.database.is_empty() {
+        response.extend(nul(login.database.as_bytes()));
+    }
+
+    response.extend(nul(plugin.as_bytes()));
+
+    let mut seq = seq.wrapping_add(1);
+    write(&mut stream, seq, &response).await?;
+
+    loop {
+        let (next, packet) = read(&mut stream).await?;
+        seq = next.wrapping_add(1);
+
+        match packet.first()? {
+            0x00 => break,
+            0x01 if packet.get(1) == Some(&0x03) => continue,
+            0xFE => {
+                let (plugin, scramble) =
+                    packet[1..].split_at(packet[1..].iter().position(|&b| b == 0)?);
+                let plugin = std::str::from_utf8(plugin).ok()?;
+                let scramble = scramble[1..].strip_suffix(&[0]).unwrap_or(&scramble[1..]);
+
+                let auth = scramble_password(plugin, &password, scramble)?;
+                write(&mut stream, seq, &auth).await?;
+            }
+            _ => return None,
+        }
+    }
+
+    let start = Instant::now();
+
+    let mut query = vec![COM_QUERY];
+    query.extend(b"SELECT 1");
+    write(&mut stream, 0, &query).await?;
+
+    // Column count, one definition and its EOF, the row and the closing EOF.
+    let mut eofs = 0;
+
+    while eofs < 2 {
+        let (_, packet) = read(&mut stream).await?;
+
+        match packet.first()? {
+            0xFF => return None,
+            0xFE if packet.len() < 9 => eofs += 1,
+            _ => {}
+        }
+    }
+
+    let elapsed = start.elapsed();
+    let _ = write(&mut stream, 0, &[COM_QUIT]).await;
+    Some(elapsed)
+}
+
+async fn read(stream: &mut TcpStream) -> Option<(u8, Vec<u8>)> {
+    let mut header = [0; 4];
+    stream.read_exact(&mut header).await.ok()?;
+
+    let len = u32::from_le_bytes([header[0], header[1], header[2], 0]) as usize;
+    let mut packet = vec![0; len];
+    stream.read_exact(&mut packet).await.ok()?;
+    Some((header[3], packet))
+}
+
+async fn write(stream: &mut TcpStream, seq: u8, payload: &[u8]) -> Option<()> {
+    let mut packet = (payload.len() as u32).to_le_bytes()[..3].to_vec();
+    packet.push(seq);
+    packet.extend(payload);
+    stream.write_all(&packet).await.ok()
+}
+
+fn nul(text: &[u8]) -> Vec<u8> {
+    let mut text = text.to_vec();
+    text.push(0);
+    text
+}
+
+// Handshake v10: the scramble comes in two parts around the capability flags.
+fn greeting_auth(greeting: &[u8]) -> Option<(Vec<u8>, String)> {
+    let (&version, rest) = greeting.split_first()?;
+
+    if version != 10 {
+        return None;
+    }
+
+    let rest = &rest[rest.iter().position(|&b| b == 0)? + 1..];
+    let mut scramble = rest.get(4..12)?.to_vec();
+    let rest = rest.get(13..)?;
+
+    let data_len = *rest.get(7)? as usize;
+    let rest = rest.get(18..)?;
+
+    let second = data_len.saturating_sub(8).max(13);
+    let part = rest.get(..second)?;
+    scramble.extend(part.strip_suffix(&[0]).unwrap_or(part));
+
+    let rest = &rest[second..];
+    let end = rest.iter().position(|&b| b == 0).unwrap_or(rest.len());
+    let plugin = String::from_utf8_lossy(&rest[..end]).into_owned();
+
+    Some((scramble, plugin))
+}
+
+fn scramble_password(plugin: &str, password: &str, scramble: &[u8]) -> Option<Vec<u8>> {
+    if password.is_empty() {
+        return Some(vec![]);
+    }
+
+    let (algorithm, salted_first) = match plugin {
+        NATIVE => (&digest::SHA1_FOR_LEGACY_USE_ONLY, true),
+        CACHING => (&digest::SHA256, false),
+        _ => return None,
+    };
+
+    let hash = |parts: &[&[u8]]| {
+        let mut context = digest::Context::new(algorithm);
+        parts.iter().for_each(|part| context.update(part));
+        context.finish()
+    };
+
+    let stage1 = hash(&[password.as_bytes()]);
+    let stage2 = hash(&[stage1.as_ref()]);
+
+    // Native hashes the scramble before the double hash, caching_sha2 after it.
+    let mask = match salted_first {
+        true => hash(&[scramble, stage2.as_ref()]),
+        false => hash(&[stage2.as_ref(), scramble]),
+    };
+
+    Some(
+        stage1
+            .as_ref()
+            .iter()
+            .zip(mask.as_ref())
+            .map(|(a, b)| a ^ b)
+            .collect(),
+    )
+}
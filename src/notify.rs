@@ -0,0 +1,359 @@
+use std::{
+    sync::{Arc, Mutex},
+    thread,
+};
+
+use chrono::{DateTime, Utc};
+use eframe::Frame;
+use egui::{Button, Context, TextEdit, TextStyle, Ui, WidgetText};
+use handlebars::Handlebars;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::json;
+
+use crate::{
+    clock::Clock,
+    desktop::{self, Desktop},
+    routing::Channels,
+    secrets, FAIL, PASS,
+};
+
+const WEBHOOK_SECRET: &str = "webhook";
+const ESCALATION_SECRET: &str = "webhook-escalation";
+const DEFAULT_TEMPLATE: &str =
+    r#"{"host": "{{host}}", "status": "{{status}}", "body": "{{body}}", "time": "{{time}}"}"#;
+
+// Whatever a backend may need from the running app to get a notification out.
+pub struct Outlet<'a> {
+    pub ctx: &'a Context,
+    pub frame: &'a Frame,
+    pub desktop: &'a mut Desktop,
+    pub clock: &'a Clock,
+}
+
+pub trait Notifier {
+    // Routing tables are saved under this, so it must never change once released.
+    fn key(&self) -> &'static str;
+
+    fn label(&self) -> &'static str;
+
+    fn send(&mut self, outlet: &mut Outlet, title: &str, body: &str, success: bool);
+
+    fn settings(&mut self, _ui: &mut Ui, _outlet: &mut Outlet) {}
+}
+
+// A new backend is a field here and an entry in `all`, routing and settings pick it up from there.
+#[derive(Default, Serialize, Deserialize)]
+pub struct Notifiers {
+    #[serde(default)]
+    toasts: desktop::Toasts,
+
+    #[serde(default)]
+    pub webhook: Webhook,
+
+    #[serde(default)]
+    sound: desktop::Sound,
+}
+
+impl Notifiers {
+    pub fn all(&mut self) -> [&mut dyn Notifier; 3] {
+        [&mut self.toasts, &mut self.webhook, &mut self.sound]
+    }
+
+    pub fn columns(&mut self) -> Vec<(&'static str, &'static str)> {
+        self.all()
+            .iter()
+            .map(|notifier| (notifier.key(), notifier.label()))
+            .collect()
+    }
+
+    pub fn send(
+        &mut self,
+        outlet: &mut Outlet,
+        channels: &Channels,
+        title: &str,
+        body: &str,
+        success: bool,
+    ) {
+        for notifier in self.all() {
+            if channels.contains(notifier.key()) {
+                notifier.send(outlet, title, body, success);
+            }
+        }
+    }
+
+    pub fn settings(&mut self, ui: &mut Ui, outlet: &mut Outlet) {
+        for notifier in self.all() {
+            notifier.settings(ui, outlet);
+            ui.separator();
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Webhook {
+    enabled: bool,
+    url: String,
+
+    #[serde(default = "default_keyring")]
+    keyring: String,
+
+    #[serde(default)]
+    template: String,
+
+    // Older configs kept the secret in plain text. It stays saved that way until the keyring
+    // takes it, so a failing keyring never loses it.
+    #[serde(default, rename = "secret", skip_serializing_if = "Option::is_none")]
+    legacy: Option<String>,
+
+    #[serde(skip)]
+    secret: Option<String>,
+
+    #[serde(skip)]
+    error: Arc<Mutex<Option<String>>>,
+
+    #[serde(skip)]
+    test: Arc<Mutex<Option<Result<(), String>>>>,
+
+    // Rendering looks secrets up in the keyring, so it only happens again after an edit.
+    #[serde(skip)]
+    preview: Option<Result<String, String>>,
+}
+
+impl Default for Webhook {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: String::new(),
+            keyring: default_keyring(),
+            template: String::new(),
+            legacy: None,
+            secret: None,
+            error: Default::default(),
+            test: Default::default(),
+            preview: None,
+        }
+    }
+}
+
+impl Webhook {
+    pub fn escalation() -> Self {
+        Self {
+            keyring: ESCALATION_SECRET.into(),
+            ..Default::default()
+        }
+    }
+
+    // RON wants an explicit `Some(…)` for options, which a field that used to be plain never has.
+    pub fn legacy<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Self>, D::Error> {
+        Self::deserialize(deserializer).map(Some)
+    }
+
+    pub fn secure(&mut self) {
+        let Some(legacy) = &self.legacy else {
+            return;
+        };
+
+        let result = secrets::store(&self.keyring, legacy);
+        self.secret = Some(legacy.clone());
+
+        match result {
+            Ok(()) => self.legacy = None,
+            Err(err) => *self.error.lock().unwrap() = Some(err),
+        }
+    }
+
+    fn secret(&mut self) -> &str {
+        self.secret
+            .get_or_insert_with(|| secrets::load(&self.keyring).unwrap_or_default())
+    }
+
+    pub fn send(&mut self, host: &str, body: &str, success: bool, clock: &Clock) {
+        if !self.enabled || self.url.is_empty() {
+            return;
+        }
+
+        let status = ["down", "up"][success as usize];
+        let error = self.error.clone();
+        let message = Message {
+            host,
+            status,
+            body,
+            time: clock.utc(),
+        };
+
+        self.post(&message, move |result| {
+            *error.lock().unwrap() = result.err()
+        });
+    }
+
+    fn post(&mut self, message: &Message, done: impl FnOnce(Result<(), String>) + Send + 'static) {
+        let secret = self.secret().to_owned();
+
+        let url = match secrets::interpolate(&self.url) {
+            Ok(url) => url,
+            Err((_, err)) => return done(Err(err)),
+        };
+
+        let body = match render(&self.template, message) {
+            Ok(body) => body,
+            Err(err) => return done(Err(err)),
+        };
+
+        thread::spawn(move || {
+            let mut request = ureq::post(&url).set("Content-Type", "application/json");
+
+            if !secret.is_empty() {
+                request = request.set("Authorization", &format!("Bearer {}", secret));
+            }
+
+            done(
+                request
+                    .send_string(&body)
+                    .map(drop)
+                    .map_err(|err| err.to_string()),
+            );
+        });
+    }
+
+    pub fn editor(&mut self, ui: &mut Ui, label: &str, clock: &Clock) {
+        ui.checkbox(&mut self.enabled, label);
+
+        let url_input = TextEdit::singleline(&mut self.url)
+            .hint_text(WidgetText::italics("URL (admite ${VARIABLE})".into()))
+            .font(TextStyle::Monospace);
+
+        let url_changed = ui.add(url_input).changed();
+
+        self.secret();
+
+        let secret = self.secret.get_or_insert_with(String::new);
+
+        let secret_input = TextEdit::singleline(secret)
+            .hint_text(WidgetText::italics("Secreto".into()))
+            .font(TextStyle::Monospace)
+            .password(true);
+
+        if ui.add(secret_input).lost_focus() {
+            let result = secrets::store(&self.keyring, secret).err();
+
+            if self.legacy.is_some() {
+                self.legacy = result.is_some().then(|| secret.clone());
+            }
+
+            *self.error.lock().unwrap() = result;
+        }
+
+        if let Some(error) = self.error.lock().unwrap().as_ref() {
+            ui.colored_label(FAIL, error);
+        }
+
+        ui.collapsing("Plantilla", |ui| {
+            let template_input = TextEdit::multiline(&mut self.template)
+                .hint_text(WidgetText::italics(DEFAULT_TEMPLATE.into()))
+                .font(TextStyle::Monospace)
+                .desired_rows(4);
+
+            if ui.add(template_input).changed() || url_changed {
+                self.preview = None;
+            }
+
+            ui.weak("Variables: {{host}}, {{status}}, {{body}}, {{time}}");
+
+            let preview = self
+                .preview
+                .get_or_insert_with(|| render(&self.template, &Message::test(clock)));
+
+            match preview {
+                Ok(preview) => ui.monospace(&*preview),
+                Err(error) => ui.colored_label(FAIL, error),
+            };
+        });
+
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(!self.url.is_empty(), Button::new("Enviar prueba"))
+                .clicked()
+            {
+                let test = self.test.clone();
+                *test.lock().unwrap() = None;
+                self.post(&Message::test(clock), move |result| {
+                    *test.lock().unwrap() = Some(result)
+                });
+            }
+
+            match self.test.lock().unwrap().as_ref() {
+                Some(Ok(())) => ui.colored_label(PASS, "✔ Recibida"),
+                Some(Err(error)) => ui.colored_label(FAIL, error),
+                None => ui.label(""),
+            };
+        });
+    }
+}
+
+impl Notifier for Webhook {
+    fn key(&self) -> &'static str {
+        "webhook"
+    }
+
+    fn label(&self) -> &'static str {
+        "Webhook"
+    }
+
+    fn send(&mut self, outlet: &mut Outlet, title: &str, body: &str, success: bool) {
+        Webhook::send(self, title, body, success, outlet.clock);
+    }
+
+    fn settings(&mut self, ui: &mut Ui, outlet: &mut Outlet) {
+        self.editor(ui, "Webhook", outlet.clock);
+    }
+}
+
+// What a template gets to fill in. `host` is the title, a host or a digest's group, and `body`
+// carries everything else, like the host list of a digest.
+struct Message<'a> {
+    host: &'a str,
+    status: &'a str,
+    body: &'a str,
+    time: DateTime<Utc>,
+}
+
+impl Message<'_> {
+    fn test(clock: &Clock) -> Self {
+        Message {
+            host: "PingA",
+            status: "test",
+            body: "Mensaje de prueba",
+            time: clock.utc(),
+        }
+    }
+}
+
+fn render(template: &str, message: &Message) -> Result<String, String> {
+    let data = json!({
+        "host": message.host,
+        "status": message.status,
+        "body": message.body,
+        "time": message.time.to_rfc3339(),
+    });
+
+    if template.trim().is_empty() {
+        return Ok(data.to_string());
+    }
+
+    let template = secrets::interpolate(template).map_err(|(_, err)| err)?;
+
+    // Values land inside JSON strings, so escape them as such instead of as HTML.
+    let mut handlebars = Handlebars::new();
+    handlebars.register_escape_fn(|value| {
+        let quoted = serde_json::Value::from(value).to_string();
+        quoted[1..quoted.len() - 1].to_owned()
+    });
+
+    handlebars
+        .render_template(&template, &data)
+        .map_err(|err| err.to_string())
+}
+
+fn default_keyring() -> String {
+    WEBHOOK_SECRET.into()
+}
@@ -0,0 +1,139 @@
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    time::Duration,
+};
+
+use chrono::{DateTime, TimeDelta, Utc};
+use egui::Ui;
+use serde::{Deserialize, Serialize};
+use tokio::net::UdpSocket;
+
+use crate::{
+    check::{self, Kind, Target},
+    units::Units,
+    Pong, Reply, Sample, FAIL, WARN,
+};
+
+const PORT: u16 = 123;
+const PACKET: usize = 48;
+
+// Seconds between the NTP era (1900) and the Unix one.
+const ERA: i64 = 2_208_988_800;
+
+// Past this ntpd steps the clock instead of slewing it, well past it nobody is keeping time.
+const WARN_OFFSET: TimeDelta = TimeDelta::milliseconds(128);
+const FAIL_OFFSET: TimeDelta = TimeDelta::seconds(1);
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Ntp;
+
+impl Kind for Ntp {
+    fn label(&self) -> &'static str {
+        "NTP"
+    }
+
+    fn ping(&self, target: &Target) -> Vec<Reply> {
+        ping(target.address, target.probes, target.timeout)
+    }
+}
+
+// The round trip leaves out the time the server spent on it, as NTP itself reports delay.
+pub fn ping(addr: &str, probes: usize, timeout: Duration) -> Vec<Reply> {
+    let Some(ip) = check::resolve(addr) else {
+        return Reply::failures(None, probes);
+    };
+
+    let target = SocketAddr::new(ip, PORT);
+
+    check::fan_out(ip, probes, timeout, |_| async move {
+        let (delay, offset) = probe(target).await?;
+
+        Some(Reply {
+            size: PACKET,
+            offset: Some(offset),
+            ..Reply::new(Pong::Success(delay))
+        })
+    })
+}
+
+async fn probe(target: SocketAddr) -> Option<(Duration, TimeDelta)> {
+    let local = match target.ip() {
+        IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+        IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+    };
+
+    let socket = UdpSocket::bind((local, 0)).await.ok()?;
+    socket.connect(target).await.ok()?;
+
+    // Version 4, client mode. The transmit time comes back as the origin of the answer.
+    let mut request = [0; PACKET];
+    request[0] = 0x23;
+
+    let sent = Utc::now();
+    let origin = timestamp(sent);
+    request[40..].copy_from_slice(&origin);
+    socket.send(&request).await.ok()?;
+
+    let mut buf = [0; 1024];
+
+    loop {
+        let len = socket.recv(&mut buf).await.ok()?;
+        let received = Utc::now();
+        let reply = &buf[..len];
+
+        if len < PACKET || reply[0] & 0x07 != 4 || reply[24..32] != origin {
+            continue;
+        }
+
+        // Unsynchronized servers and kiss-o'-death packets carry no usable time.
+        if reply[0] >> 6 == 3 || reply[1] == 0 {
+            return None;
+        }
+
+        let arrived = datetime(&reply[32..40])?;
+        let left = datetime(&reply[40..48])?;
+
+        let delay = (received - sent) - (left - arrived);
+        let offset = ((arrived - sent) + (left - received)) / 2;
+
+        return Some((delay.to_std().unwrap_or_default(), offset));
+    }
+}
+
+fn timestamp(time: DateTime<Utc>) -> [u8; 8] {
+    let secs = (time.timestamp() + ERA) as u32;
+    let fraction = ((time.timestamp_subsec_nanos() as u64) << 32) / 1_000_000_000;
+
+    let mut bytes = [0; 8];
+    bytes[..4].copy_from_slice(&secs.to_be_bytes());
+    bytes[4..].copy_from_slice(&(fraction as u32).to_be_bytes());
+    bytes
+}
+
+fn datetime(bytes: &[u8]) -> Option<DateTime<Utc>> {
+    let secs = u32::from_be_bytes(bytes[..4].try_into().ok()?) as i64;
+    let fraction = u32::from_be_bytes(bytes[4..8].try_into().ok()?) as u64;
+    let nanos = (fraction * 1_000_000_000) >> 32;
+
+    DateTime::from_timestamp(secs - ERA, nanos as u32)
+}
+
+pub fn format(offset: TimeDelta, units: Units) -> String {
+    let secs = offset.num_microseconds().unwrap_or_default() as f64 / 1e6;
+    let sign = ["", "+"][(secs > 0.) as usize];
+    format!("{}{}", sign, units.format_secs(secs))
+}
+
+pub fn show(ui: &mut Ui, last: Option<&Sample>, units: Units) {
+    let Some(offset) = last.and_then(|sample| sample.reply.offset) else {
+        return;
+    };
+
+    let text = format!("⏱ Desfase {}", format(offset, units));
+
+    match offset.abs() {
+        offset if offset > FAIL_OFFSET => ui.colored_label(FAIL, text),
+        offset if offset > WARN_OFFSET => ui.colored_label(WARN, text),
+        _ => ui.label(text),
+    };
+}
@@ -0,0 +1,181 @@
+use std::{
+    fs,
+    net::{IpAddr, Ipv4Addr},
+};
+
+use egui::{Align2, Button, ComboBox, Context, Window};
+
+use crate::{elevate, visuals, PingWindow};
+
+const STEPS: usize = 4;
+const ANCHOR: (&str, &str) = ("Cloudflare DNS", "1.1.1.1");
+
+pub struct Wizard {
+    step: usize,
+    gateway: Option<Ipv4Addr>,
+    dns: Option<IpAddr>,
+    elevate: elevate::Helper,
+    add_gateway: bool,
+    add_dns: bool,
+    add_anchor: bool,
+}
+
+impl Default for Wizard {
+    fn default() -> Self {
+        let gateway = gateway();
+        let dns = dns();
+
+        Self {
+            step: 0,
+            gateway,
+            dns,
+            elevate: elevate::Helper::default(),
+            add_gateway: gateway.is_some(),
+            add_dns: dns.is_some(),
+            add_anchor: true,
+        }
+    }
+}
+
+impl Wizard {
+    fn windows(&self) -> Vec<PingWindow> {
+        let gateway = self
+            .gateway
+            .filter(|_| self.add_gateway)
+            .map(|ip| ("Puerta de enlace".to_owned(), ip.to_string()));
+
+        let dns = self
+            .dns
+            .filter(|_| self.add_dns)
+            .map(|ip| ("DNS".to_owned(), ip.to_string()));
+
+        let anchor = self
+            .add_anchor
+            .then(|| (ANCHOR.0.to_owned(), ANCHOR.1.to_owned()));
+
+        [gateway, dns, anchor]
+            .into_iter()
+            .flatten()
+            .map(|(name, address)| PingWindow::new(name, address, None))
+            .collect()
+    }
+}
+
+// Returns the windows to start with once the wizard is done.
+pub fn show(ctx: &Context, wizard: &mut Wizard, dark: &mut bool) -> Option<Vec<PingWindow>> {
+    let mut done = None;
+
+    Window::new(format!(
+        "Bienvenido a PingA ({}/{})",
+        wizard.step + 1,
+        STEPS
+    ))
+    .anchor(Align2::CENTER_CENTER, [0., 0.])
+    .collapsible(false)
+    .resizable(false)
+    .show(ctx, |ui| {
+        match wizard.step {
+            0 => {
+                ui.label("Idioma de la interfaz");
+
+                ComboBox::from_id_source("language")
+                    .selected_text("Español")
+                    .show_ui(ui, |ui| {
+                        let _ = ui.selectable_label(true, "Español");
+                    });
+            }
+            1 => {
+                ui.label("Permisos para enviar pings ICMP");
+
+                elevate::show(ui, &mut wizard.elevate);
+            }
+            2 => {
+                ui.label("Hosts con los que empezar");
+
+                let gateway = wizard
+                    .gateway
+                    .map_or("no detectada".into(), |ip| ip.to_string());
+                let dns = wizard
+                    .dns
+                    .map_or("no detectado".into(), |ip| ip.to_string());
+
+                ui.add_enabled_ui(wizard.gateway.is_some(), |ui| {
+                    ui.checkbox(
+                        &mut wizard.add_gateway,
+                        format!("Puerta de enlace ({})", gateway),
+                    );
+                });
+
+                ui.add_enabled_ui(wizard.dns.is_some(), |ui| {
+                    ui.checkbox(&mut wizard.add_dns, format!("Servidor DNS ({})", dns));
+                });
+
+                ui.checkbox(
+                    &mut wizard.add_anchor,
+                    format!("{} ({})", ANCHOR.0, ANCHOR.1),
+                );
+            }
+            _ => {
+                ui.label("Apariencia");
+
+                ui.horizontal(|ui| {
+                    if ui.radio_value(dark, false, "Clara").changed()
+                        | ui.radio_value(dark, true, "Oscura").changed()
+                    {
+                        ctx.set_visuals(visuals(*dark));
+                    }
+                });
+            }
+        }
+
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(wizard.step > 0, Button::new("Atrás"))
+                .clicked()
+            {
+                wizard.step -= 1;
+            }
+
+            match wizard.step + 1 < STEPS {
+                true if ui.button("Siguiente").clicked() => wizard.step += 1,
+                false if ui.button("Empezar").clicked() => done = Some(wizard.windows()),
+                _ => {}
+            }
+        });
+    });
+
+    done
+}
+
+// Only Linux exposes the routing table as a plain file, elsewhere the option stays disabled.
+fn gateway() -> Option<Ipv4Addr> {
+    let routes = fs::read_to_string("/proc/net/route").ok()?;
+
+    routes.lines().skip(1).find_map(|line| {
+        let fields = line.split_whitespace().collect::<Vec<_>>();
+
+        match fields[..] {
+            [_, "00000000", gateway, ..] => {
+                let gateway = u32::from_str_radix(gateway, 16).ok()?;
+                Some(Ipv4Addr::from(gateway.to_le_bytes())).filter(|ip| !ip.is_unspecified())
+            }
+            _ => None,
+        }
+    })
+}
+
+fn dns() -> Option<IpAddr> {
+    let resolv = fs::read_to_string("/etc/resolv.conf").ok()?;
+
+    // Local stub resolvers (systemd-resolved, dnsmasq) say nothing about the network.
+    resolv.lines().find_map(|line| {
+        let ip = line
+            .strip_prefix("nameserver")?
+            .trim()
+            .parse::<IpAddr>()
+            .ok()?;
+        Some(ip).filter(|ip| !ip.is_loopback())
+    })
+}
@@ -0,0 +1,221 @@
+use std::{
+    sync::{Arc, Mutex},
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Utc};
+use egui::{DragValue, TextEdit, TextStyle, Ui, WidgetText};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::{
+    clock::Clock,
+    secrets,
+    stats::{self, PERCENTILES},
+    Canvas, FAIL,
+};
+
+const DEFAULT_ENDPOINT: &str = "http://localhost:4318";
+const DEFAULT_EVERY: Duration = Duration::from_secs(15);
+
+#[derive(Serialize, Deserialize)]
+pub struct Exporter {
+    enabled: bool,
+    endpoint: String,
+
+    #[serde(default = "default_every")]
+    every: Duration,
+
+    #[serde(skip)]
+    exported: Option<(Instant, DateTime<Utc>)>,
+
+    #[serde(skip)]
+    error: Arc<Mutex<Option<String>>>,
+}
+
+impl Default for Exporter {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: DEFAULT_ENDPOINT.into(),
+            every: DEFAULT_EVERY,
+            exported: None,
+            error: Default::default(),
+        }
+    }
+}
+
+impl Exporter {
+    pub fn remaining(&self, clock: &Clock) -> Duration {
+        match (self.enabled, self.exported) {
+            (false, _) => Duration::MAX,
+            (true, None) => Duration::ZERO,
+            (true, Some((at, _))) => self.every.saturating_sub(clock.since(at)),
+        }
+    }
+
+    pub fn export(&mut self, canvases: &[Canvas], clock: &Clock) {
+        if !self.enabled {
+            self.exported = None;
+        }
+
+        if !self.remaining(clock).is_zero() {
+            return;
+        }
+
+        self.send(canvases, clock);
+    }
+
+    // Sends whatever came in since the last export and waits for it to go out, before exiting.
+    pub fn flush(&mut self, canvases: &[Canvas], clock: &Clock) {
+        if let Some(sending) = self.enabled.then(|| self.send(canvases, clock)).flatten() {
+            let _ = sending.join();
+        }
+    }
+
+    fn send(&mut self, canvases: &[Canvas], clock: &Clock) -> Option<JoinHandle<()>> {
+        let now = clock.utc();
+        let since = self.exported.map(|(_, since)| since);
+        self.exported = Some((clock.now(), now));
+
+        // The first round only sets the starting point, there is nothing to aggregate yet.
+        let since = since?;
+
+        let mut rtts = vec![];
+        let mut losses = vec![];
+        let mut quantiles = PERCENTILES.map(|_| vec![]);
+
+        for win in canvases.iter().flat_map(|canvas| &canvas.windows) {
+            let samples =
+                &win.history[win.history.partition_point(|sample| sample.time <= since)..];
+
+            if samples.is_empty() {
+                continue;
+            }
+
+            let host = [&win.hostname, &win.address][win.hostname.is_empty() as usize];
+
+            let attributes = json!([
+                attribute("host.name", host),
+                attribute("net.peer.name", &win.address),
+                attribute("pinga.group", &(win.group + 1).to_string()),
+                attribute("pinga.check", samples[0].check),
+            ]);
+
+            let successes = stats::rtts(samples);
+
+            let loss = 1. - successes.len() as f64 / samples.len() as f64;
+            losses.push(point(&attributes, now, loss));
+
+            if !successes.is_empty() {
+                let rtt = successes.iter().sum::<f64>() / successes.len() as f64;
+                rtts.push(point(&attributes, now, rtt * 1000.));
+            }
+
+            let mut sorted = stats::rtts(win.percentiles.slice(&win.history, samples, now));
+            sorted.sort_by(f64::total_cmp);
+
+            if let Some(values) = stats::percentiles(&sorted) {
+                for (points, value) in quantiles.iter_mut().zip(values) {
+                    points.push(point(&attributes, now, value * 1000.));
+                }
+            }
+        }
+
+        let mut metrics = vec![
+            json!({
+                "name": "pinga.rtt",
+                "description": "Tiempo de ida y vuelta medio",
+                "unit": "ms",
+                "gauge": { "dataPoints": rtts },
+            }),
+            json!({
+                "name": "pinga.loss",
+                "description": "Fracción de sondas perdidas",
+                "unit": "1",
+                "gauge": { "dataPoints": losses },
+            }),
+        ];
+
+        for (percentile, points) in PERCENTILES.into_iter().zip(quantiles) {
+            metrics.push(json!({
+                "name": format!("pinga.rtt.p{}", percentile),
+                "description": format!("Percentil {} del tiempo de ida y vuelta", percentile),
+                "unit": "ms",
+                "gauge": { "dataPoints": points },
+            }));
+        }
+
+        let body = json!({
+            "resourceMetrics": [{
+                "resource": {
+                    "attributes": [attribute("service.name", "pinga")],
+                },
+                "scopeMetrics": [{
+                    "scope": { "name": "pinga", "version": env!("CARGO_PKG_VERSION") },
+                    "metrics": metrics,
+                }],
+            }],
+        });
+
+        let error = self.error.clone();
+
+        let endpoint = match secrets::interpolate(&self.endpoint) {
+            Ok(endpoint) => endpoint,
+            Err((_, err)) => {
+                *error.lock().unwrap() = Some(err);
+                return None;
+            }
+        };
+
+        let url = format!("{}/v1/metrics", endpoint.trim_end_matches('/'));
+
+        Some(thread::spawn(move || {
+            let result = ureq::post(&url).send_json(body);
+            *error.lock().unwrap() = result.err().map(|err| err.to_string());
+        }))
+    }
+}
+
+pub fn editor(ui: &mut Ui, exporter: &mut Exporter) {
+    ui.checkbox(&mut exporter.enabled, "Exportar métricas OTLP");
+
+    let endpoint_input = TextEdit::singleline(&mut exporter.endpoint)
+        .hint_text(WidgetText::italics(DEFAULT_ENDPOINT.into()))
+        .font(TextStyle::Monospace);
+
+    ui.add(endpoint_input);
+
+    ui.horizontal(|ui| {
+        let mut secs = exporter.every.as_secs();
+
+        ui.label("Cada");
+
+        let every_input = DragValue::new(&mut secs).clamp_range(1..=3600).suffix(" s");
+
+        if ui.add(every_input).changed() {
+            exporter.every = Duration::from_secs(secs);
+        }
+    });
+
+    if let Some(error) = exporter.error.lock().unwrap().as_ref() {
+        ui.colored_label(FAIL, error);
+    }
+}
+
+fn attribute(key: &str, value: &str) -> Value {
+    json!({ "key": key, "value": { "stringValue": value } })
+}
+
+fn point(attributes: &Value, time: DateTime<Utc>, value: f64) -> Value {
+    json!({
+        "attributes": attributes,
+        "timeUnixNano": time.timestamp_nanos_opt().unwrap_or_default().to_string(),
+        "asDouble": value,
+    })
+}
+
+fn default_every() -> Duration {
+    DEFAULT_EVERY
+}
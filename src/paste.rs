@@ -0,0 +1,42 @@
+use egui::{Context, Event, Pos2, Vec2};
+
+use crate::PingWindow;
+
+const CELL: Vec2 = Vec2::new(216., 160.);
+
+// Text pasted while no widget has focus lands on the canvas, it would be lost otherwise.
+pub fn take(ctx: &Context) -> Option<String> {
+    if ctx.memory(|memory| memory.focus().is_some()) {
+        return None;
+    }
+
+    ctx.input(|input| {
+        input.events.iter().find_map(|event| match event {
+            Event::Paste(text) => Some(text.clone()),
+            _ => None,
+        })
+    })
+}
+
+// One `address [name]` per line, laid out row by row on the squarest grid that fits them.
+pub fn windows(text: &str, origin: Pos2) -> Vec<PingWindow> {
+    let targets = text
+        .lines()
+        .filter_map(|line| {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let (address, name) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            (!address.is_empty()).then(|| (name.trim(), address))
+        })
+        .collect::<Vec<_>>();
+
+    let columns = (targets.len() as f32).sqrt().ceil().max(1.) as usize;
+
+    targets
+        .into_iter()
+        .enumerate()
+        .map(|(idx, (name, address))| {
+            let cell = Vec2::new((idx % columns) as f32, (idx / columns) as f32);
+            PingWindow::new(name, address, Some(origin + cell * CELL))
+        })
+        .collect()
+}
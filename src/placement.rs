@@ -0,0 +1,65 @@
+use egui::{Pos2, Rect, Vec2};
+use serde::{Deserialize, Serialize};
+
+use crate::Canvas;
+
+// How much of a restored window has to stay on the canvas for it to be grabbed again.
+const MARGIN: Vec2 = Vec2::new(120., 40.);
+
+// The canvas the windows were last laid out on, so a layout saved on another monitor setup can be
+// scaled into the screen it is restored on.
+#[derive(Default, Serialize, Deserialize)]
+pub struct Placement {
+    area: Option<Rect>,
+
+    #[serde(skip)]
+    pending: bool,
+}
+
+impl Placement {
+    pub fn arm(&mut self) {
+        self.pending = true;
+    }
+
+    pub fn apply(&mut self, canvases: &mut [Canvas], area: Rect) {
+        let saved = self.area.replace(area);
+
+        if !std::mem::take(&mut self.pending) {
+            return;
+        }
+
+        // Layouts from before positions were recorded only know where windows were created.
+        let Some(saved) = saved.filter(|saved| saved.is_positive()) else {
+            return;
+        };
+
+        let scale = area.size() / saved.size();
+
+        for win in canvases.iter_mut().flat_map(|canvas| &mut canvas.windows) {
+            let Some(origin) = win.origin else {
+                continue;
+            };
+
+            let pos = area.min + (origin - saved.min) * scale;
+            let pos = clamp(pos, area);
+
+            if pos != origin {
+                win.origin = Some(pos);
+                win.snap_to = Some(pos);
+            }
+        }
+    }
+}
+
+pub fn record(canvases: &mut [Canvas]) {
+    for win in canvases.iter_mut().flat_map(|canvas| &mut canvas.windows) {
+        if let Some(rect) = win.rect {
+            win.origin = Some(rect.min);
+        }
+    }
+}
+
+fn clamp(pos: Pos2, area: Rect) -> Pos2 {
+    let max = (area.max - MARGIN).max(area.min);
+    pos.clamp(area.min, max)
+}
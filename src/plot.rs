@@ -0,0 +1,142 @@
+use chrono::{DateTime, Utc};
+use egui::Color32;
+use egui_plot::{Line, PlotPoint, PlotUi, Points, Polygon};
+use itertools::Itertools;
+
+use crate::{units::Units, PlotTheme, Round, Sample, FAIL};
+
+pub const CURSORS: &str = "cursors";
+
+#[derive(Default)]
+pub struct Series {
+    bands: Vec<Polygon>,
+    medians: Vec<Line>,
+    points: Vec<Points>,
+}
+
+impl Series {
+    pub fn new(rounds: &[(f64, Round)], theme: PlotTheme) -> Self {
+        let mut series = Series::default();
+
+        for (idx, &(x, round)) in rounds.iter().enumerate() {
+            let Some([min, median, max]) = round.rtt else {
+                continue;
+            };
+
+            let color = loss_color(theme.line, round.loss);
+            let point = Points::new([x, median.as_secs_f64()])
+                .color(color)
+                .radius(2.);
+
+            series.points.push(point);
+
+            let Some(&(next_x, next)) = rounds.get(idx + 1) else {
+                continue;
+            };
+
+            let Some([next_min, next_median, next_max]) = next.rtt else {
+                continue;
+            };
+
+            let band = Polygon::new(vec![
+                [x, min.as_secs_f64()],
+                [next_x, next_min.as_secs_f64()],
+                [next_x, next_max.as_secs_f64()],
+                [x, max.as_secs_f64()],
+            ]);
+
+            series.bands.push(band.fill_color(theme.fill).width(0.));
+
+            let median = Line::new(vec![
+                [x, median.as_secs_f64()],
+                [next_x, next_median.as_secs_f64()],
+            ]);
+
+            series
+                .medians
+                .push(median.color(loss_color(theme.line, next.loss)));
+        }
+
+        series
+    }
+
+    pub fn show(self, ui: &mut PlotUi) {
+        for band in self.bands {
+            ui.polygon(band);
+        }
+
+        for median in self.medians {
+            ui.line(median);
+        }
+
+        for point in self.points {
+            ui.points(point);
+        }
+    }
+}
+
+pub fn rounds(history: &[Sample]) -> Vec<(DateTime<Utc>, Round)> {
+    history
+        .iter()
+        .group_by(|sample| sample.time)
+        .into_iter()
+        .map(|(time, group)| {
+            let pongs = group.map(|sample| sample.reply.pong).collect_vec();
+            (time, Round::new(&pongs))
+        })
+        .collect_vec()
+}
+
+// Smoothed like RTP does it (RFC 3550), so a single spike doesn't dominate the reading.
+pub fn jitter(history: &[Sample]) -> Vec<[f64; 2]> {
+    let mut smoothed = 0.;
+
+    history
+        .iter()
+        .filter_map(|sample| {
+            let jitter = sample.jitter?.as_secs_f64();
+            smoothed += (jitter - smoothed) / 16.;
+            Some([timestamp(sample.time), smoothed])
+        })
+        .collect()
+}
+
+pub fn outages(rounds: &[(DateTime<Utc>, Round)]) -> Vec<(DateTime<Utc>, Option<DateTime<Utc>>)> {
+    let mut outages = vec![];
+    let mut since = None;
+
+    for &(time, round) in rounds {
+        match (since, round.rtt) {
+            (None, None) => since = Some(time),
+            (Some(start), Some(_)) => {
+                outages.push((start, Some(time)));
+                since = None;
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(start) = since {
+        outages.push((start, None));
+    }
+
+    outages
+}
+
+pub fn timestamp(time: DateTime<Utc>) -> f64 {
+    time.timestamp_millis() as f64 / 1000.
+}
+
+pub fn rtt_label(units: Units) -> impl Fn(&str, &PlotPoint) -> String {
+    move |_, sample| units.format_secs(sample.y)
+}
+
+fn loss_color(pass: Color32, loss: f32) -> Color32 {
+    let channel = |pass: u8, fail: u8| egui::lerp(pass as f32..=fail as f32, loss) as u8;
+
+    Color32::from_rgb(
+        channel(pass.r(), FAIL.r()),
+        channel(pass.g(), FAIL.g()),
+        channel(pass.b(), FAIL.b()),
+    )
+}
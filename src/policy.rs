@@ -0,0 +1,220 @@
+use std::{hash::Hash, ops::RangeInclusive, time::Duration};
+
+use egui::{ComboBox, DragValue, Grid, Ui};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Alert {
+    #[default]
+    Off,
+    Down,
+    Change,
+}
+
+impl Alert {
+    pub const ALL: [Alert; 3] = [Alert::Off, Alert::Down, Alert::Change];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Alert::Off => "Nunca",
+            Alert::Down => "Al caer",
+            Alert::Change => "Al cambiar",
+        }
+    }
+
+    pub fn fires(self, success: bool) -> bool {
+        !matches!((self, success), (Alert::Off, _) | (Alert::Down, true))
+    }
+}
+
+pub const MAX_RETRIES: u32 = 10;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Settings {
+    pub interval: Duration,
+    pub timeout: Duration,
+    pub threshold: Duration,
+    pub alert: Alert,
+    pub repeat: Duration,
+    pub escalate: Duration,
+    pub retries: u32,
+}
+
+pub const DEFAULTS: Settings = Settings {
+    interval: Duration::from_secs(1),
+    timeout: Duration::from_secs(2),
+    threshold: Duration::ZERO,
+    alert: Alert::Off,
+    repeat: Duration::ZERO,
+    escalate: Duration::ZERO,
+    retries: 0,
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Policy {
+    pub interval: Option<Duration>,
+    pub timeout: Option<Duration>,
+    pub threshold: Option<Duration>,
+    pub alert: Option<Alert>,
+
+    #[serde(default)]
+    pub repeat: Option<Duration>,
+
+    #[serde(default)]
+    pub escalate: Option<Duration>,
+
+    #[serde(default)]
+    pub retries: Option<u32>,
+}
+
+impl Policy {
+    pub fn over(&self, base: Settings) -> Settings {
+        Settings {
+            interval: self.interval.unwrap_or(base.interval),
+            timeout: self.timeout.unwrap_or(base.timeout),
+            threshold: self.threshold.unwrap_or(base.threshold),
+            alert: self.alert.unwrap_or(base.alert),
+            repeat: self.repeat.unwrap_or(base.repeat),
+            escalate: self.escalate.unwrap_or(base.escalate),
+            retries: self.retries.unwrap_or(base.retries),
+        }
+    }
+}
+
+pub fn editor(ui: &mut Ui, id_source: impl Hash, policy: &mut Policy, base: Settings) {
+    Grid::new(id_source).num_columns(2).show(ui, |ui| {
+        row(
+            ui,
+            "Intervalo",
+            &mut policy.interval,
+            base.interval,
+            |ui, value| duration_edit(ui, value, 100..=3_600_000),
+        );
+
+        row(
+            ui,
+            "Tiempo límite",
+            &mut policy.timeout,
+            base.timeout,
+            |ui, value| duration_edit(ui, value, 100..=60_000),
+        );
+
+        row(
+            ui,
+            "Umbral",
+            &mut policy.threshold,
+            base.threshold,
+            |ui, value| duration_edit(ui, value, 0..=60_000),
+        );
+
+        row(ui, "Alertar", &mut policy.alert, base.alert, |ui, value| {
+            ComboBox::from_id_source(ui.id().with("alert"))
+                .selected_text(value.label())
+                .show_ui(ui, |ui| {
+                    for alert in Alert::ALL {
+                        ui.selectable_value(value, alert, alert.label());
+                    }
+                });
+        });
+
+        row(
+            ui,
+            "Repetir cada",
+            &mut policy.repeat,
+            base.repeat,
+            |ui, value| duration_edit(ui, value, 0..=86_400_000),
+        );
+
+        row(
+            ui,
+            "Escalar tras",
+            &mut policy.escalate,
+            base.escalate,
+            |ui, value| duration_edit(ui, value, 0..=86_400_000),
+        );
+
+        row(
+            ui,
+            "Reintentos",
+            &mut policy.retries,
+            base.retries,
+            |ui, value| {
+                ui.add(DragValue::new(value).clamp_range(0..=MAX_RETRIES));
+            },
+        );
+    });
+}
+
+pub fn changes(old: &Policy, new: &Policy) -> Vec<(&'static str, String, String)> {
+    let mut changes = vec![];
+
+    let durations = [
+        ("Intervalo", old.interval, new.interval),
+        ("Tiempo límite", old.timeout, new.timeout),
+        ("Umbral", old.threshold, new.threshold),
+        ("Repetir cada", old.repeat, new.repeat),
+        ("Escalar tras", old.escalate, new.escalate),
+    ];
+
+    for (label, old, new) in durations {
+        if old != new {
+            changes.push((label, describe(old), describe(new)));
+        }
+    }
+
+    if old.retries != new.retries {
+        let label = |retries: Option<u32>| retries.map_or("heredado".into(), |n| n.to_string());
+        changes.push(("Reintentos", label(old.retries), label(new.retries)));
+    }
+
+    if old.alert != new.alert {
+        let label = |alert: Option<Alert>| alert.map_or("heredado", Alert::label).to_owned();
+        changes.push(("Alertar", label(old.alert), label(new.alert)));
+    }
+
+    changes
+}
+
+fn describe(value: Option<Duration>) -> String {
+    match value {
+        Some(value) => format!("{} ms", value.as_millis()),
+        None => "heredado".into(),
+    }
+}
+
+fn row<T: Copy>(
+    ui: &mut Ui,
+    label: &str,
+    value: &mut Option<T>,
+    inherited: T,
+    edit: impl FnOnce(&mut Ui, &mut T),
+) {
+    let mut custom = value.is_some();
+
+    if ui.checkbox(&mut custom, label).changed() {
+        *value = custom.then_some(inherited);
+    }
+
+    match value {
+        Some(value) => edit(ui, value),
+        None => {
+            let mut inherited = inherited;
+            ui.add_enabled_ui(false, |ui| edit(ui, &mut inherited));
+        }
+    }
+
+    ui.end_row();
+}
+
+fn duration_edit(ui: &mut Ui, value: &mut Duration, range: RangeInclusive<u64>) {
+    let mut millis = value.as_millis() as u64;
+
+    let input = DragValue::new(&mut millis)
+        .clamp_range(range)
+        .speed(10)
+        .suffix(" ms");
+
+    if ui.add(input).changed() {
+        *value = Duration::from_millis(millis);
+    }
+}
@@ -0,0 +1,250 @@
+use std::{
+    net::SocketAddr,
+    num::NonZeroU32,
+    time::{Duration, Instant},
+};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use egui::{DragValue, Id, Ui};
+use md5::{Digest, Md5};
+use ring::{digest, hmac, pbkdf2};
+use serde::{Deserialize, Serialize};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+
+use crate::{
+    check::{Kind, Target},
+    database::{self, Login},
+    Reply,
+};
+
+const PROTOCOL: i32 = 3 << 16;
+const DEFAULT_USER: &str = "postgres";
+const SCRAM: &str = "SCRAM-SHA-256";
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Postgres {
+    pub port: u16,
+    pub login: Login,
+}
+
+impl Postgres {
+    pub const DEFAULT: Postgres = Postgres {
+        port: 5432,
+        login: Login::EMPTY,
+    };
+}
+
+impl Kind for Postgres {
+    fn label(&self) -> &'static str {
+        "PostgreSQL"
+    }
+
+    fn ping(&self, target: &Target) -> Vec<Reply> {
+        let login = self.login.clone();
+
+        database::ping(
+            target.address,
+            self.port,
+            target.probes,
+            target.timeout,
+            move |target| probe(target, login.clone()),
+        )
+    }
+
+    fn editor(&mut self, ui: &mut Ui, _id: Id, _address: &str, _bind: Option<&str>) {
+        ui.horizontal(|ui| {
+            ui.label("Puerto");
+            ui.add(DragValue::new(&mut self.port).clamp_range(1..=u16::MAX));
+        });
+
+        database::editor(
+            ui,
+            &mut self.login,
+            DEFAULT_USER,
+            "Base de datos (la del usuario)",
+        );
+    }
+}
+
+// Plain connections only, as with `sslmode=disable`.
+async fn probe(target: SocketAddr, login: Login) -> Option<Duration> {
+    let mut stream = TcpStream::connect(target).await.ok()?;
+    let password = login.password()?;
+    let user = [&*login.user, DEFAULT_USER][login.user.is_empty() as usize];
+    let database = [&*login.database, user][login.database.is_empty() as usize];
+
+    let mut startup = PROTOCOL.to_be_bytes().to_vec();
+
+    for (key, value) in [("user", user), ("database", database)] {
+        startup.extend(key.as_bytes());
+        startup.push(0);
+        startup.extend(value.as_bytes());
+        startup.push(0);
+    }
+
+    startup.push(0);
+
+    let mut packet = (startup.len() as i32 + 4).to_be_bytes().to_vec();
+    packet.extend(startup);
+    stream.write_all(&packet).await.ok()?;
+
+    let mut scram = None;
+    let mut verifier = None;
+
+    loop {
+        let (kind, body) = read(&mut stream).await?;
+
+        match kind {
+            b'R' => {
+                let (code, data) = body.split_first_chunk::<4>()?;
+                let response = match i32::from_be_bytes(*code) {
+                    0 => continue,
+                    3 => nul(password.as_bytes()),
+                    5 => nul(md5_password(user, &password, data).as_bytes()),
+                    10 => {
+                        let (nonce, first) = scram_first();
+                        scram = Some((nonce, first.clone()));
+
+                        let mut response = nul(SCRAM.as_bytes());
+                        response.extend((first.len() as i32).to_be_bytes());
+                        response.extend(first.as_bytes());
+                        response
+                    }
+                    11 => {
+                        let (nonce, first) = scram.take()?;
+                        let server = std::str::from_utf8(data).ok()?;
+                        let (last, signature) = scram_last(&nonce, &first, server, &password)?;
+
+                        verifier = Some(signature);
+                        last.into_bytes()
+                    }
+                    12 => {
+                        let signature = std::str::from_utf8(data).ok()?.strip_prefix("v=")?;
+
+                        // A server that cannot prove it knows the password is not the one meant.
+                        if verifier.take()? != signature {
+                            return None;
+                        }
+
+                        continue;
+                    }
+                    _ => return None,
+                };
+
+                write(&mut stream, b'p', &response).await?;
+            }
+            b'E' => return None,
+            b'Z' => break,
+            _ => {}
+        }
+    }
+
+    let start = Instant::now();
+    write(&mut stream, b'Q', &nul(b"SELECT 1")).await?;
+
+    loop {
+        match read(&mut stream).await?.0 {
+            b'E' => return None,
+            b'Z' => break,
+            _ => {}
+        }
+    }
+
+    let elapsed = start.elapsed();
+    let _ = write(&mut stream, b'X', &[]).await;
+    Some(elapsed)
+}
+
+async fn read(stream: &mut TcpStream) -> Option<(u8, Vec<u8>)> {
+    let kind = stream.read_u8().await.ok()?;
+    let len = stream.read_i32().await.ok()?;
+    let mut body = vec![0; usize::try_from(len).ok()?.checked_sub(4)?];
+    stream.read_exact(&mut body).await.ok()?;
+    Some((kind, body))
+}
+
+async fn write(stream: &mut TcpStream, kind: u8, body: &[u8]) -> Option<()> {
+    let mut packet = vec![kind];
+    packet.extend((body.len() as i32 + 4).to_be_bytes());
+    packet.extend(body);
+    stream.write_all(&packet).await.ok()
+}
+
+fn nul(text: &[u8]) -> Vec<u8> {
+    let mut text = text.to_vec();
+    text.push(0);
+    text
+}
+
+fn md5_password(user: &str, password: &str, salt: &[u8]) -> String {
+    let inner = hex(&Md5::digest(format!("{}{}", password, user)));
+    let outer = Md5::new()
+        .chain_update(inner.as_bytes())
+        .chain_update(salt)
+        .finalize();
+
+    format!("md5{}", hex(&outer))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+// The user name travels in the startup packet, SCRAM gets an empty one as libpq does.
+fn scram_first() -> (String, String) {
+    let nonce = STANDARD.encode(rand::random::<[u8; 18]>());
+    let first = format!("n,,n=,r={}", nonce);
+    (nonce, first)
+}
+
+// Returns the final client message and the server signature expected back.
+fn scram_last(nonce: &str, first: &str, server: &str, password: &str) -> Option<(String, String)> {
+    let mut attributes = server.split(',');
+    let combined = attributes.next()?.strip_prefix("r=")?;
+    let salt = STANDARD
+        .decode(attributes.next()?.strip_prefix("s=")?)
+        .ok()?;
+    let iterations = attributes.next()?.strip_prefix("i=")?.parse().ok()?;
+
+    if !combined.starts_with(nonce) {
+        return None;
+    }
+
+    let mut salted = [0; digest::SHA256_OUTPUT_LEN];
+    pbkdf2::derive(
+        pbkdf2::PBKDF2_HMAC_SHA256,
+        NonZeroU32::new(iterations)?,
+        &salt,
+        password.as_bytes(),
+        &mut salted,
+    );
+
+    let salted = hmac::Key::new(hmac::HMAC_SHA256, &salted);
+    let client_key = hmac::sign(&salted, b"Client Key");
+    let stored_key = digest::digest(&digest::SHA256, client_key.as_ref());
+
+    let without_proof = format!("c=biws,r={}", combined);
+    let message = format!("{},{},{}", &first[3..], server, without_proof);
+
+    let stored_key = hmac::Key::new(hmac::HMAC_SHA256, stored_key.as_ref());
+    let signature = hmac::sign(&stored_key, message.as_bytes());
+
+    let proof = client_key
+        .as_ref()
+        .iter()
+        .zip(signature.as_ref())
+        .map(|(key, signature)| key ^ signature)
+        .collect::<Vec<_>>();
+
+    let server_key = hmac::sign(&salted, b"Server Key");
+    let server_key = hmac::Key::new(hmac::HMAC_SHA256, server_key.as_ref());
+    let server_signature = hmac::sign(&server_key, message.as_bytes());
+
+    Some((
+        format!("{},p={}", without_proof, STANDARD.encode(proof)),
+        STANDARD.encode(server_signature),
+    ))
+}
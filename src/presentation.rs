@@ -0,0 +1,74 @@
+use std::num::NonZeroU32;
+
+use ring::pbkdf2;
+use serde::{Deserialize, Serialize};
+
+const ITERATIONS: u32 = 100_000;
+
+// Saved with the rest of the app so a restart does not unlock the screen. Only a salted PBKDF2
+// of the passphrase is kept, which reads the same on every build.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lock {
+    salt: [u8; 16],
+    hash: [u8; 32],
+}
+
+impl Lock {
+    // A blank passphrase would lock the screen behind nothing, so there is no lock for it.
+    pub fn new(passphrase: &str) -> Option<Self> {
+        if passphrase.trim().is_empty() {
+            return None;
+        }
+
+        let salt = rand::random::<[u8; 16]>();
+        let mut hash = [0; 32];
+
+        pbkdf2::derive(
+            pbkdf2::PBKDF2_HMAC_SHA256,
+            iterations(),
+            &salt,
+            passphrase.as_bytes(),
+            &mut hash,
+        );
+
+        Some(Self { salt, hash })
+    }
+
+    pub fn opens(&self, passphrase: &str) -> bool {
+        pbkdf2::verify(
+            pbkdf2::PBKDF2_HMAC_SHA256,
+            iterations(),
+            &self.salt,
+            passphrase.as_bytes(),
+            &self.hash,
+        )
+        .is_ok()
+    }
+}
+
+fn iterations() -> NonZeroU32 {
+    NonZeroU32::new(ITERATIONS).unwrap_or(NonZeroU32::MIN)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Lock;
+
+    #[test]
+    fn opens_only_with_its_passphrase_after_a_restart() {
+        let lock = Lock::new("vestíbulo").unwrap();
+        let saved = ron::to_string(&lock).unwrap();
+        let loaded = ron::from_str::<Lock>(&saved).unwrap();
+
+        assert!(loaded.opens("vestíbulo"));
+        assert!(!loaded.opens("vestibulo"));
+        assert!(!loaded.opens(""));
+        assert_ne!(Lock::new("vestíbulo"), Some(lock));
+    }
+
+    #[test]
+    fn blank_passphrases_lock_nothing() {
+        assert_eq!(Lock::new(""), None);
+        assert_eq!(Lock::new("  \t"), None);
+    }
+}
@@ -0,0 +1,102 @@
+use std::{hash::Hash, time::Duration};
+
+use chrono::{DateTime, Utc};
+use egui::{ComboBox, DragValue, Ui};
+use serde::{Deserialize, Serialize};
+
+use crate::{Sample, PLOT_LEN};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum TimeRange {
+    #[default]
+    Latest,
+    Last(Duration),
+}
+
+const PRESETS: [TimeRange; 4] = [
+    TimeRange::Latest,
+    TimeRange::Last(Duration::from_secs(5 * 60)),
+    TimeRange::Last(Duration::from_secs(60 * 60)),
+    TimeRange::Last(Duration::from_secs(24 * 60 * 60)),
+];
+
+impl TimeRange {
+    pub fn label(self) -> String {
+        match self {
+            TimeRange::Latest => format!("Últimas {} rondas", PLOT_LEN),
+            TimeRange::Last(span) if span.as_secs() % 3600 == 0 => {
+                format!("Últimas {} h", span.as_secs() / 3600)
+            }
+            TimeRange::Last(span) => format!("Últimos {} min", span.as_secs() / 60),
+        }
+    }
+
+    pub fn start(self, now: DateTime<Utc>, interval: Duration) -> DateTime<Utc> {
+        let span = match self {
+            TimeRange::Latest => interval * PLOT_LEN as u32,
+            TimeRange::Last(span) => span,
+        };
+
+        now - chrono::Duration::from_std(span).unwrap_or(chrono::Duration::zero())
+    }
+
+    pub fn slice(self, history: &[Sample], now: DateTime<Utc>) -> &[Sample] {
+        let base = match self {
+            TimeRange::Latest => {
+                let mut rounds = 0;
+                let mut base = history.len();
+
+                while base > 0 {
+                    let time = history[base - 1].time;
+
+                    if history.get(base).is_none_or(|next| next.time != time) {
+                        rounds += 1;
+                    }
+
+                    if rounds > PLOT_LEN {
+                        break;
+                    }
+
+                    base -= 1;
+                }
+
+                base
+            }
+            TimeRange::Last(_) => {
+                let start = self.start(now, Duration::ZERO);
+                history.partition_point(|sample| sample.time < start)
+            }
+        };
+
+        &history[base..]
+    }
+}
+
+pub fn editor(ui: &mut Ui, id_source: impl Hash, range: &mut TimeRange) {
+    let custom = !PRESETS.contains(range);
+
+    ComboBox::from_id_source(id_source)
+        .selected_text(range.label())
+        .show_ui(ui, |ui| {
+            for preset in PRESETS {
+                ui.selectable_value(range, preset, preset.label());
+            }
+
+            if ui.selectable_label(custom, "Personalizado").clicked() && !custom {
+                *range = TimeRange::Last(Duration::from_secs(15 * 60));
+            }
+        });
+
+    if let TimeRange::Last(span) = range {
+        if custom {
+            let mut minutes = span.as_secs() / 60;
+            let input = DragValue::new(&mut minutes)
+                .clamp_range(1..=7 * 24 * 60)
+                .suffix(" min");
+
+            if ui.add(input).changed() {
+                *span = Duration::from_secs(minutes * 60);
+            }
+        }
+    }
+}
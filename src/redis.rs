@@ -0,0 +1,105 @@
+use std::{
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
+
+use egui::{DragValue, Id, Ui};
+use serde::{Deserialize, Serialize};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::TcpStream,
+};
+
+use crate::{
+    check::{Kind, Target},
+    database::{self, Login},
+    Reply,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Redis {
+    pub port: u16,
+    pub login: Login,
+}
+
+impl Redis {
+    pub const DEFAULT: Redis = Redis {
+        port: 6379,
+        login: Login::EMPTY,
+    };
+}
+
+impl Kind for Redis {
+    fn label(&self) -> &'static str {
+        "Redis"
+    }
+
+    fn ping(&self, target: &Target) -> Vec<Reply> {
+        let login = self.login.clone();
+
+        database::ping(
+            target.address,
+            self.port,
+            target.probes,
+            target.timeout,
+            move |target| probe(target, login.clone()),
+        )
+    }
+
+    fn editor(&mut self, ui: &mut Ui, _id: Id, _address: &str, _bind: Option<&str>) {
+        ui.horizontal(|ui| {
+            ui.label("Puerto");
+            ui.add(DragValue::new(&mut self.port).clamp_range(1..=u16::MAX));
+        });
+
+        database::editor(ui, &mut self.login, "default", "Base de datos (0)");
+    }
+}
+
+async fn probe(target: SocketAddr, login: Login) -> Option<Duration> {
+    let stream = TcpStream::connect(target).await.ok()?;
+    let mut stream = BufReader::new(stream);
+    let password = login.password()?;
+
+    if !password.is_empty() {
+        let auth = match login.user.is_empty() {
+            true => command(&["AUTH", &password]),
+            false => command(&["AUTH", &login.user, &password]),
+        };
+
+        call(&mut stream, &auth, "+OK").await?;
+    }
+
+    if !login.database.is_empty() {
+        call(&mut stream, &command(&["SELECT", &login.database]), "+OK").await?;
+    }
+
+    let start = Instant::now();
+    call(&mut stream, &command(&["PING"]), "+PONG").await?;
+    let elapsed = start.elapsed();
+
+    let _ = stream.get_mut().write_all(&command(&["QUIT"])).await;
+    Some(elapsed)
+}
+
+async fn call(stream: &mut BufReader<TcpStream>, command: &[u8], expect: &str) -> Option<()> {
+    stream.get_mut().write_all(command).await.ok()?;
+
+    let mut line = String::new();
+    stream.read_line(&mut line).await.ok()?;
+
+    (line.trim_end() == expect).then_some(())
+}
+
+// Commands go out as RESP arrays of bulk strings, which take any bytes in a password.
+fn command(args: &[&str]) -> Vec<u8> {
+    let mut command = format!("*{}\r\n", args.len()).into_bytes();
+
+    for arg in args {
+        command.extend(format!("${}\r\n", arg.len()).into_bytes());
+        command.extend(arg.as_bytes());
+        command.extend(b"\r\n");
+    }
+
+    command
+}
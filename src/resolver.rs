@@ -0,0 +1,255 @@
+use std::{
+    fs,
+    net::{IpAddr, SocketAddr},
+    time::{Duration, Instant},
+};
+
+use egui::{ComboBox, Id, TextEdit, TextStyle, Ui, WidgetText};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use tokio::net::UdpSocket;
+
+use crate::{
+    check::{self, Kind, Target},
+    dns, Pong, Reply,
+};
+
+const PORT: u16 = 53;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Record {
+    #[default]
+    A,
+    Aaaa,
+    Cname,
+    Mx,
+    Ns,
+    Ptr,
+    Soa,
+    Txt,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Dns {
+    pub server: String,
+    pub record: Record,
+}
+
+impl Record {
+    pub const ALL: [Record; 8] = [
+        Record::A,
+        Record::Aaaa,
+        Record::Cname,
+        Record::Mx,
+        Record::Ns,
+        Record::Ptr,
+        Record::Soa,
+        Record::Txt,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Record::A => "A",
+            Record::Aaaa => "AAAA",
+            Record::Cname => "CNAME",
+            Record::Mx => "MX",
+            Record::Ns => "NS",
+            Record::Ptr => "PTR",
+            Record::Soa => "SOA",
+            Record::Txt => "TXT",
+        }
+    }
+
+    fn code(self) -> u16 {
+        match self {
+            Record::A => 1,
+            Record::Ns => 2,
+            Record::Cname => 5,
+            Record::Soa => 6,
+            Record::Ptr => 12,
+            Record::Mx => 15,
+            Record::Txt => 16,
+            Record::Aaaa => 28,
+        }
+    }
+}
+
+impl Dns {
+    pub const DEFAULT: Dns = Dns {
+        server: String::new(),
+        record: Record::A,
+    };
+}
+
+impl Kind for Dns {
+    fn label(&self) -> &'static str {
+        "DNS"
+    }
+
+    fn ping(&self, target: &Target) -> Vec<Reply> {
+        ping(
+            target.address,
+            &self.server,
+            self.record,
+            target.probes,
+            target.timeout,
+        )
+    }
+
+    fn editor(&mut self, ui: &mut Ui, id: Id, address: &str, _bind: Option<&str>) {
+        ui.horizontal(|ui| {
+            ui.label("Registro");
+
+            ComboBox::from_id_source(id.with("record"))
+                .selected_text(self.record.label())
+                .show_ui(ui, |ui| {
+                    for kind in Record::ALL {
+                        ui.selectable_value(&mut self.record, kind, kind.label());
+                    }
+                });
+        });
+
+        let server_input = TextEdit::singleline(&mut self.server)
+            .hint_text(WidgetText::italics("Servidor (el del sistema)".into()))
+            .font(TextStyle::Monospace);
+
+        ui.add(server_input);
+        ui.weak(format!("Consulta {} {}", self.record.label(), address));
+    }
+}
+
+// Only an answer with records counts, NXDOMAIN and empty answers are as bad as silence.
+pub fn ping(
+    name: &str,
+    server: &str,
+    record: Record,
+    probes: usize,
+    timeout: Duration,
+) -> Vec<Reply> {
+    let Some(server) = server_addr(server) else {
+        return Reply::failures(None, probes);
+    };
+
+    let Some(query) = query(name, record) else {
+        return Reply::failures(Some(server.ip()), probes);
+    };
+
+    check::fan_out(server.ip(), probes, timeout, |_| {
+        let query = query.clone();
+
+        async move {
+            let (duration, size) = probe(server, query).await?;
+
+            Some(Reply {
+                size,
+                ..Reply::new(Pong::Success(duration))
+            })
+        }
+    })
+}
+
+async fn probe(server: SocketAddr, mut query: Vec<u8>) -> Option<(Duration, usize)> {
+    let local: SocketAddr = match server {
+        SocketAddr::V4(_) => "0.0.0.0:0".parse().ok()?,
+        SocketAddr::V6(_) => "[::]:0".parse().ok()?,
+    };
+
+    let id = rand::random::<u16>().to_be_bytes();
+    query[..2].copy_from_slice(&id);
+
+    let socket = UdpSocket::bind(local).await.ok()?;
+    socket.connect(server).await.ok()?;
+
+    let start = Instant::now();
+    socket.send(&query).await.ok()?;
+
+    let mut buf = [0; 65536];
+
+    loop {
+        let len = socket.recv(&mut buf).await.ok()?;
+        let reply = &buf[..len];
+
+        if len < 12 || reply[..2] != id || reply[2] & 0x80 == 0 {
+            continue;
+        }
+
+        let rcode = reply[3] & 0x0F;
+        let answers = u16::from_be_bytes([reply[6], reply[7]]);
+
+        return (rcode == 0 && answers > 0).then(|| (start.elapsed(), len));
+    }
+}
+
+// The id is filled in per probe. A PTR lookup on an IP is turned into its reverse name.
+fn query(name: &str, record: Record) -> Option<Vec<u8>> {
+    let name = match (record, name.trim().parse::<IpAddr>()) {
+        (Record::Ptr, Ok(IpAddr::V4(ip))) => {
+            let octets = ip.octets().iter().rev().join(".");
+            format!("{}.in-addr.arpa", octets)
+        }
+        (Record::Ptr, Ok(IpAddr::V6(ip))) => {
+            let nibbles = ip
+                .octets()
+                .iter()
+                .rev()
+                .flat_map(|byte| [byte & 0x0F, byte >> 4])
+                .map(|nibble| format!("{:x}", nibble))
+                .join(".");
+            format!("{}.ip6.arpa", nibbles)
+        }
+        _ => dns::ascii(name),
+    };
+
+    let mut packet = vec![0, 0, 0x01, 0x00, 0, 1, 0, 0, 0, 0, 0, 0];
+
+    for label in name.trim_end_matches('.').split('.') {
+        if label.is_empty() || label.len() > 63 {
+            return None;
+        }
+
+        packet.push(label.len() as u8);
+        packet.extend(label.as_bytes());
+    }
+
+    packet.push(0);
+    packet.extend(record.code().to_be_bytes());
+    packet.extend(1u16.to_be_bytes());
+
+    Some(packet)
+}
+
+// An empty server means the system one, as far as `/etc/resolv.conf` tells.
+fn server_addr(server: &str) -> Option<SocketAddr> {
+    let server = match server.trim() {
+        "" => system()?,
+        server => server.to_owned(),
+    };
+
+    if let Ok(addr) = server.parse::<SocketAddr>() {
+        return Some(addr);
+    }
+
+    if let Ok(ip) = server.parse::<IpAddr>() {
+        return Some(SocketAddr::new(ip, PORT));
+    }
+
+    let (host, port) = match server.rsplit_once(':') {
+        Some((host, port)) => (host, port.parse().ok()?),
+        None => (&*server, PORT),
+    };
+
+    let ip = dns::lookup(host).ok()?.first().copied()?;
+    Some(SocketAddr::new(ip, port))
+}
+
+fn system() -> Option<String> {
+    let conf = fs::read_to_string("/etc/resolv.conf").ok()?;
+
+    conf.lines().find_map(|line| {
+        let mut fields = line.split_whitespace();
+        (fields.next() == Some("nameserver"))
+            .then(|| fields.next())
+            .flatten()
+            .map(Into::into)
+    })
+}
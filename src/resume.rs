@@ -0,0 +1,138 @@
+use std::time::{Duration, Instant};
+
+use egui::{Align2, Context, Window};
+
+use crate::{clock::Clock, Canvas};
+
+const GRACE: Duration = Duration::from_secs(30);
+
+// Windows that were polling when the app last closed. They are held back for a while so a
+// reboot into a different network does not greet you with a wall of alerts.
+#[derive(Default)]
+pub struct Resume {
+    pending: Vec<Instant>,
+    deadline: Option<Instant>,
+}
+
+impl Resume {
+    pub fn hold(canvases: &mut [Canvas], clock: &Clock) -> Self {
+        let mut pending = vec![];
+
+        for win in canvases.iter_mut().flat_map(|canvas| &mut canvas.windows) {
+            if win.scanning {
+                win.scanning = false;
+                pending.push(win.ctime);
+            }
+        }
+
+        Self {
+            deadline: (!pending.is_empty()).then(|| clock.now() + GRACE),
+            pending,
+        }
+    }
+
+    fn release(&mut self, canvases: &mut [Canvas], resume: bool) {
+        if resume {
+            for win in canvases.iter_mut().flat_map(|canvas| &mut canvas.windows) {
+                if self.pending.contains(&win.ctime) {
+                    win.scanning = true;
+                    win.success = None;
+                }
+            }
+        }
+
+        self.pending.clear();
+        self.deadline = None;
+    }
+
+    // What is left of the grace period, resuming every held window once it runs out.
+    fn left(&mut self, canvases: &mut [Canvas], clock: &Clock) -> Option<Duration> {
+        let left = self.deadline?.saturating_duration_since(clock.now());
+
+        if left.is_zero() {
+            self.release(canvases, true);
+            return None;
+        }
+
+        Some(left)
+    }
+}
+
+pub fn show(ctx: &Context, resume: &mut Resume, canvases: &mut [Canvas], clock: &Clock) {
+    let Some(left) = resume.left(canvases, clock) else {
+        return;
+    };
+
+    Window::new("Reanudar sondeo")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(Align2::CENTER_TOP, [0., 48.])
+        .show(ctx, |ui| {
+            ui.label(format!(
+                "{} hosts se estaban sondeando al cerrar, se reanudarán en {} s",
+                resume.pending.len(),
+                left.as_secs() + 1
+            ));
+
+            ui.horizontal(|ui| {
+                if ui.button("Reanudar ahora").clicked() {
+                    resume.release(canvases, true);
+                }
+
+                if ui.button("Empezar en pausa").clicked() {
+                    resume.release(canvases, false);
+                }
+            });
+        });
+
+    ctx.request_repaint_after(left.min(Duration::from_secs(1)));
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{Resume, GRACE};
+    use crate::{clock::Clock, Canvas, PingWindow};
+
+    fn canvases(scanning: bool) -> [Canvas; 1] {
+        let mut canvas = Canvas::new("Prueba");
+        let mut win = PingWindow::new("host", "127.0.0.1", None);
+        win.scanning = scanning;
+
+        canvas.windows.push(win);
+        [canvas]
+    }
+
+    #[test]
+    fn resumes_held_windows_after_the_grace_period() {
+        let clock = Clock::manual();
+        let mut canvases = canvases(true);
+        let mut resume = Resume::hold(&mut canvases, &clock);
+
+        assert!(!canvases[0].windows[0].scanning);
+        assert_eq!(resume.left(&mut canvases, &clock), Some(GRACE));
+
+        clock.advance(GRACE - Duration::from_secs(1));
+        assert_eq!(
+            resume.left(&mut canvases, &clock),
+            Some(Duration::from_secs(1))
+        );
+        assert!(!canvases[0].windows[0].scanning);
+
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(resume.left(&mut canvases, &clock), None);
+        assert!(canvases[0].windows[0].scanning);
+        assert!(resume.pending.is_empty());
+    }
+
+    #[test]
+    fn holds_nothing_when_nothing_was_scanning() {
+        let clock = Clock::manual();
+        let mut canvases = canvases(false);
+        let mut resume = Resume::hold(&mut canvases, &clock);
+
+        assert_eq!(resume.left(&mut canvases, &clock), None);
+        assert!(!canvases[0].windows[0].scanning);
+    }
+}
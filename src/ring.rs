@@ -0,0 +1,55 @@
+use std::ops::Deref;
+
+pub const DEFAULT_CAPACITY: usize = 100_000;
+
+// Keeps the last `capacity` items as one contiguous slice, so plots and tables can keep
+// borrowing `&[T]`. The backing Vec grows to twice the capacity before the stale half is
+// dropped in one go, which keeps pushes amortized O(1).
+pub struct Ring<T> {
+    items: Vec<T>,
+    capacity: usize,
+}
+
+impl<T> Ring<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            items: Vec::new(),
+            capacity: capacity.max(1),
+        }
+    }
+
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+
+        if self.items.len() > 2 * self.capacity {
+            self.compact();
+        }
+    }
+
+    pub fn push(&mut self, item: T) {
+        if self.items.len() >= 2 * self.capacity {
+            self.compact();
+        }
+
+        self.items.push(item);
+    }
+
+    fn compact(&mut self) {
+        let excess = self.items.len().saturating_sub(self.capacity);
+        self.items.drain(..excess);
+    }
+}
+
+impl<T> Default for Ring<T> {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl<T> Deref for Ring<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.items[self.items.len().saturating_sub(self.capacity)..]
+    }
+}
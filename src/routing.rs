@@ -0,0 +1,147 @@
+use std::collections::BTreeSet;
+
+use egui::{Grid, Ui};
+use serde::{Deserialize, Serialize};
+
+// A recovery is sent with the severity of whatever it recovers from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Warning,
+    Critical,
+}
+
+// Keyed by `Notifier::key`, keys of backends that are gone are kept around but ignored.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "Stored")]
+pub struct Channels {
+    on: BTreeSet<String>,
+}
+
+// Older configs had a flag per built-in backend, they are folded into the set on load.
+#[derive(Deserialize)]
+struct Stored {
+    #[serde(default)]
+    on: BTreeSet<String>,
+
+    #[serde(default)]
+    desktop: bool,
+
+    #[serde(default)]
+    webhook: bool,
+
+    #[serde(default)]
+    sound: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Routing {
+    pub quiet: bool,
+    warning: Channels,
+    critical: Channels,
+}
+
+impl Severity {
+    fn label(self) -> &'static str {
+        match self {
+            Severity::Warning => "Aviso (degradado)",
+            Severity::Critical => "Crítico (caída)",
+        }
+    }
+}
+
+impl From<Stored> for Channels {
+    fn from(stored: Stored) -> Self {
+        let legacy = [
+            ("desktop", stored.desktop),
+            ("webhook", stored.webhook),
+            ("sound", stored.sound),
+        ];
+
+        let mut on = stored.on;
+        on.extend(
+            legacy
+                .into_iter()
+                .filter(|&(_, on)| on)
+                .map(|(key, _)| key.into()),
+        );
+
+        Self { on }
+    }
+}
+
+impl Channels {
+    pub fn contains(&self, key: &str) -> bool {
+        self.on.contains(key)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.on.is_empty()
+    }
+
+    fn toggle(&mut self, key: &str, on: bool) {
+        match on {
+            true => self.on.insert(key.into()),
+            false => self.on.remove(key),
+        };
+    }
+}
+
+impl Default for Routing {
+    fn default() -> Self {
+        let all = Channels {
+            on: ["desktop".into(), "webhook".into()].into(),
+        };
+
+        Self {
+            quiet: false,
+            warning: all.clone(),
+            critical: all,
+        }
+    }
+}
+
+impl Routing {
+    pub fn channels(&self, severity: Severity) -> Channels {
+        match (self.quiet, severity) {
+            (true, _) => Channels::default(),
+            (false, Severity::Warning) => self.warning.clone(),
+            (false, Severity::Critical) => self.critical.clone(),
+        }
+    }
+}
+
+// One column per registered backend, as `(key, label)`.
+pub fn editor(ui: &mut Ui, routing: &mut Routing, columns: &[(&str, &str)]) {
+    ui.checkbox(&mut routing.quiet, "No molestar");
+
+    ui.add_enabled_ui(!routing.quiet, |ui| {
+        Grid::new("routing")
+            .num_columns(columns.len() + 1)
+            .show(ui, |ui| {
+                ui.label("");
+
+                for (_, label) in columns {
+                    ui.label(*label);
+                }
+
+                ui.end_row();
+
+                for (severity, channels) in [
+                    (Severity::Warning, &mut routing.warning),
+                    (Severity::Critical, &mut routing.critical),
+                ] {
+                    ui.label(severity.label());
+
+                    for (key, _) in columns {
+                        let mut on = channels.contains(key);
+
+                        if ui.checkbox(&mut on, "").changed() {
+                            channels.toggle(key, on);
+                        }
+                    }
+
+                    ui.end_row();
+                }
+            });
+    });
+}
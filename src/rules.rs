@@ -0,0 +1,87 @@
+use std::time::Duration;
+
+use egui::{Context, TextEdit, TextStyle, Window};
+use serde::{Deserialize, Serialize};
+
+use crate::{policy::Alert, Policy, FAIL, GROUPS};
+
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+struct Rule {
+    threshold: Option<Duration>,
+    alert: Option<Alert>,
+
+    #[serde(default)]
+    repeat: Option<Duration>,
+
+    #[serde(default)]
+    escalate: Option<Duration>,
+
+    #[serde(default)]
+    retries: Option<u32>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct Rules {
+    groups: [Rule; GROUPS.len()],
+}
+
+#[derive(Default)]
+pub struct Import {
+    pub open: bool,
+    text: String,
+    error: Option<String>,
+}
+
+pub fn export(policies: &[Policy; GROUPS.len()]) -> String {
+    let rules = Rules {
+        groups: policies.map(|policy| Rule {
+            threshold: policy.threshold,
+            alert: policy.alert,
+            repeat: policy.repeat,
+            escalate: policy.escalate,
+            retries: policy.retries,
+        }),
+    };
+
+    ron::ser::to_string_pretty(&rules, Default::default()).unwrap_or_default()
+}
+
+pub fn show(ctx: &Context, import: &mut Import, policies: &mut [Policy; GROUPS.len()]) {
+    let mut open = import.open;
+
+    Window::new("Importar reglas de alerta")
+        .open(&mut open)
+        .default_width(320.)
+        .show(ctx, |ui| {
+            let input = TextEdit::multiline(&mut import.text)
+                .font(TextStyle::Monospace)
+                .desired_width(f32::INFINITY);
+
+            ui.add(input);
+
+            if let Some(error) = &import.error {
+                ui.colored_label(FAIL, error);
+            }
+
+            if ui.button("Importar").clicked() {
+                match ron::from_str::<Rules>(&import.text) {
+                    Ok(rules) => {
+                        for (policy, rule) in policies.iter_mut().zip(rules.groups) {
+                            policy.threshold = rule.threshold;
+                            policy.alert = rule.alert;
+                            policy.repeat = rule.repeat;
+                            policy.escalate = rule.escalate;
+                            policy.retries = rule.retries;
+                        }
+
+                        import.text.clear();
+                        import.error = None;
+                        import.open = false;
+                    }
+                    Err(err) => import.error = Some(err.to_string()),
+                }
+            }
+        });
+
+    import.open &= open;
+}
@@ -0,0 +1,84 @@
+use chrono::{DateTime, Datelike, Local, Timelike};
+
+const FIELDS: [(u32, u32); 5] = [(0, 59), (0, 23), (1, 31), (1, 12), (0, 7)];
+
+pub struct Cron {
+    fields: [Vec<bool>; 5],
+}
+
+impl Cron {
+    pub fn parse(expr: &str) -> Result<Cron, String> {
+        let parts = expr.split_whitespace().collect::<Vec<_>>();
+
+        let Ok(parts) = <[&str; 5]>::try_from(parts) else {
+            return Err("Se esperan 5 campos: minuto hora día mes día-semana".into());
+        };
+
+        let mut fields: [Vec<bool>; 5] = Default::default();
+
+        for (idx, part) in parts.into_iter().enumerate() {
+            fields[idx] = field(part, FIELDS[idx])?;
+        }
+
+        // Both 0 and 7 stand for Sunday.
+        fields[4][0] |= fields[4][7];
+
+        Ok(Cron { fields })
+    }
+
+    pub fn matches(&self, time: DateTime<Local>) -> bool {
+        let values = [
+            time.minute(),
+            time.hour(),
+            time.day(),
+            time.month(),
+            time.weekday().num_days_from_sunday(),
+        ];
+
+        values
+            .iter()
+            .zip(&self.fields)
+            .zip(FIELDS)
+            .all(|((&value, field), (min, _))| field[(value - min) as usize])
+    }
+}
+
+pub fn active(expr: &str, time: DateTime<Local>) -> bool {
+    expr.trim().is_empty() || Cron::parse(expr).map_or(true, |cron| cron.matches(time))
+}
+
+fn field(part: &str, (min, max): (u32, u32)) -> Result<Vec<bool>, String> {
+    let mut set = vec![false; (max - min + 1) as usize];
+
+    for item in part.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => (range, step.parse().map_err(|_| invalid(item))?),
+            None => (item, 1),
+        };
+
+        let (lo, hi) = match range.split_once('-') {
+            _ if range == "*" => (min, max),
+            Some((lo, hi)) => (number(lo, item)?, number(hi, item)?),
+            None if step > 1 => (number(range, item)?, max),
+            None => (number(range, item)?, number(range, item)?),
+        };
+
+        if step == 0 || lo < min || hi > max || lo > hi {
+            return Err(invalid(item));
+        }
+
+        for value in (lo..=hi).step_by(step) {
+            set[(value - min) as usize] = true;
+        }
+    }
+
+    Ok(set)
+}
+
+fn number(text: &str, item: &str) -> Result<u32, String> {
+    text.parse().map_err(|_| invalid(item))
+}
+
+fn invalid(item: &str) -> String {
+    format!("Campo no válido: {}", item)
+}
@@ -0,0 +1,64 @@
+use keyring::Entry;
+
+const SERVICE: &str = "pinga";
+
+pub fn load(name: &str) -> Option<String> {
+    Entry::new(SERVICE, name).ok()?.get_password().ok()
+}
+
+pub fn store(name: &str, secret: &str) -> Result<(), String> {
+    let entry = Entry::new(SERVICE, name).map_err(|err| err.to_string())?;
+
+    let result = match secret.is_empty() {
+        true => entry.delete_password(),
+        false => entry.set_password(secret),
+    };
+
+    match result {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+// Expands ${VAR} from the environment and ${keyring:NAME} from the keyring, $$ stands for a
+// plain $. Failures carry the byte offset of the offending reference.
+pub fn interpolate(text: &str) -> Result<String, (usize, String)> {
+    let mut expanded = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('$') {
+        let offset = text.len() - rest.len() + start;
+        expanded.push_str(&rest[..start]);
+        rest = &rest[start + 1..];
+
+        if let Some(after) = rest.strip_prefix('$') {
+            expanded.push('$');
+            rest = after;
+            continue;
+        }
+
+        let Some(reference) = rest.strip_prefix('{') else {
+            expanded.push('$');
+            continue;
+        };
+
+        let Some(end) = reference.find('}') else {
+            return Err((offset, "falta «}» al final de la variable".into()));
+        };
+
+        let name = &reference[..end];
+
+        let value = match name.strip_prefix("keyring:") {
+            Some(entry) => load(entry).ok_or_else(|| format!("el llavero no guarda «{}»", entry)),
+            None => {
+                std::env::var(name).map_err(|_| format!("la variable «{}» no está definida", name))
+            }
+        };
+
+        expanded.push_str(&value.map_err(|error| (offset, error))?);
+        rest = &reference[end + 1..];
+    }
+
+    expanded.push_str(rest);
+    Ok(expanded)
+}
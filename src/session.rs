@@ -0,0 +1,97 @@
+use std::{fs::File, io::BufWriter};
+
+use chrono::Utc;
+use egui::{TextEdit, TextStyle, Ui};
+use serde_json::{json, Value};
+
+use crate::{csv, stream, Canvas, PingWindow, FAIL, PASS};
+
+#[derive(Default)]
+pub struct Export {
+    path: String,
+    result: Option<Result<String, String>>,
+}
+
+pub fn menu(ui: &mut Ui, export: &mut Export, canvases: &[Canvas]) {
+    if export.path.is_empty() {
+        export.path = csv::path("sesion", "json").display().to_string();
+    }
+
+    let path_input = TextEdit::singleline(&mut export.path)
+        .font(TextStyle::Monospace)
+        .desired_width(320.);
+
+    ui.add(path_input);
+
+    if ui.button("Exportar sesión").clicked() {
+        let result = File::create(&export.path)
+            .and_then(|file| {
+                let writer = BufWriter::new(file);
+                serde_json::to_writer_pretty(writer, &session(canvases)).map_err(Into::into)
+            })
+            .map(|_| {
+                let windows = canvases
+                    .iter()
+                    .map(|canvas| canvas.windows.len())
+                    .sum::<usize>();
+                format!("{} ventanas guardadas", windows)
+            })
+            .map_err(|err| err.to_string());
+
+        export.result = Some(result);
+    }
+
+    if let Some(result) = &export.result {
+        match result {
+            Ok(done) => ui.colored_label(PASS, done),
+            Err(error) => ui.colored_label(FAIL, error),
+        };
+    }
+}
+
+fn session(canvases: &[Canvas]) -> Value {
+    let canvases = canvases
+        .iter()
+        .map(|canvas| {
+            json!({
+                "name": canvas.name,
+                "windows": canvas.windows.iter().map(window).collect::<Vec<_>>(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "exported": Utc::now().to_rfc3339(),
+        "canvases": canvases,
+    })
+}
+
+// Samples use the streaming format minus the fields that are already in the window.
+fn window(win: &PingWindow) -> Value {
+    let history = win
+        .history
+        .iter()
+        .map(|sample| {
+            let mut sample = stream::json(&win.hostname, &win.address, win.group, sample);
+
+            if let Some(fields) = sample.as_object_mut() {
+                for key in ["host", "address", "group"] {
+                    fields.remove(key);
+                }
+            }
+
+            sample
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "hostname": win.hostname,
+        "label": win.label,
+        "address": win.address,
+        "group": win.group + 1,
+        "check": win.check.label(),
+        "scratchpad": win.scratchpad,
+        "history": history,
+    })
+}
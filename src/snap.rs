@@ -0,0 +1,70 @@
+use egui::{Pos2, Rect};
+
+pub const THRESHOLD: f32 = 8.;
+
+#[derive(Default)]
+pub struct Guides {
+    pub dx: Option<f32>,
+    pub dy: Option<f32>,
+    pub lines: Vec<[Pos2; 2]>,
+}
+
+pub fn to_grid(pos: Pos2, size: f32) -> Pos2 {
+    if size <= 0. {
+        return pos;
+    }
+
+    (pos.to_vec2() / size).round().to_pos2() * size
+}
+
+pub fn guides(rect: Rect, others: impl IntoIterator<Item = Rect>) -> Guides {
+    let mut best_x: Option<(f32, Rect, f32)> = None;
+    let mut best_y: Option<(f32, Rect, f32)> = None;
+
+    for other in others {
+        if !other.is_positive() {
+            continue;
+        }
+
+        for (mine, theirs) in pairs([rect.left(), rect.right()], [other.left(), other.right()]) {
+            let delta = theirs - mine;
+
+            if delta.abs() < THRESHOLD && best_x.is_none_or(|(best, ..)| delta.abs() < best.abs()) {
+                best_x = Some((delta, other, theirs));
+            }
+        }
+
+        for (mine, theirs) in pairs([rect.top(), rect.bottom()], [other.top(), other.bottom()]) {
+            let delta = theirs - mine;
+
+            if delta.abs() < THRESHOLD && best_y.is_none_or(|(best, ..)| delta.abs() < best.abs()) {
+                best_y = Some((delta, other, theirs));
+            }
+        }
+    }
+
+    let mut guides = Guides::default();
+
+    if let Some((delta, other, x)) = best_x {
+        let top = rect.top().min(other.top());
+        let bottom = rect.bottom().max(other.bottom());
+
+        guides.dx = Some(delta);
+        guides.lines.push([Pos2::new(x, top), Pos2::new(x, bottom)]);
+    }
+
+    if let Some((delta, other, y)) = best_y {
+        let left = rect.left().min(other.left());
+        let right = rect.right().max(other.right());
+
+        guides.dy = Some(delta);
+        guides.lines.push([Pos2::new(left, y), Pos2::new(right, y)]);
+    }
+
+    guides
+}
+
+fn pairs(mine: [f32; 2], theirs: [f32; 2]) -> impl Iterator<Item = (f32, f32)> {
+    mine.into_iter()
+        .flat_map(move |mine| theirs.into_iter().map(move |theirs| (mine, theirs)))
+}
@@ -0,0 +1,643 @@
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    time::{Duration, Instant},
+};
+
+use aes::{
+    cipher::{BlockEncrypt, KeyInit},
+    Aes128,
+};
+use egui::{ComboBox, DragValue, Id, TextEdit, TextStyle, Ui, WidgetText};
+use itertools::Itertools;
+use md5::{Digest, Md5};
+use ring::digest;
+use serde::{Deserialize, Serialize};
+use tokio::net::UdpSocket;
+
+use crate::{
+    check::{self, Kind, Target},
+    secrets, Pong, Reply,
+};
+
+// sysUpTime.0, 1.3.6.1.2.1.1.3.0
+const SYS_UP_TIME: &[u8] = &[0x2B, 6, 1, 2, 1, 1, 3, 0];
+
+const INTEGER: u8 = 0x02;
+const OCTETS: u8 = 0x04;
+const NULL: u8 = 0x05;
+const OID: u8 = 0x06;
+const SEQUENCE: u8 = 0x30;
+const TIME_TICKS: u8 = 0x43;
+const GET: u8 = 0xA0;
+const RESPONSE: u8 = 0xA2;
+
+const AUTH_FLAG: u8 = 0x01;
+const PRIV_FLAG: u8 = 0x02;
+const REPORTABLE: u8 = 0x04;
+const USM: i64 = 3;
+const MAX_SIZE: i64 = 65507;
+const MAC_LEN: usize = 12;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Version {
+    V1,
+    #[default]
+    V2c,
+    V3,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Auth {
+    #[default]
+    None,
+    Md5,
+    Sha,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Privacy {
+    #[default]
+    None,
+    Aes,
+}
+
+impl Version {
+    const ALL: [Version; 3] = [Version::V1, Version::V2c, Version::V3];
+
+    fn label(self) -> &'static str {
+        match self {
+            Version::V1 => "v1",
+            Version::V2c => "v2c",
+            Version::V3 => "v3",
+        }
+    }
+}
+
+impl Auth {
+    const ALL: [Auth; 3] = [Auth::None, Auth::Md5, Auth::Sha];
+
+    fn label(self) -> &'static str {
+        match self {
+            Auth::None => "Sin autenticación",
+            Auth::Md5 => "MD5",
+            Auth::Sha => "SHA-1",
+        }
+    }
+
+    fn hash(self, parts: &[&[u8]]) -> Vec<u8> {
+        match self {
+            Auth::None => vec![],
+            Auth::Md5 => {
+                let mut md5 = Md5::new();
+                parts.iter().for_each(|part| md5.update(part));
+                md5.finalize().to_vec()
+            }
+            Auth::Sha => {
+                let mut sha = digest::Context::new(&digest::SHA1_FOR_LEGACY_USE_ONLY);
+                parts.iter().for_each(|part| sha.update(part));
+                sha.finish().as_ref().to_vec()
+            }
+        }
+    }
+
+    // Both digests use 64 byte blocks, and the USM keys always fit in one.
+    fn hmac(self, key: &[u8], message: &[u8]) -> Vec<u8> {
+        let mut ipad = [0x36; 64];
+        let mut opad = [0x5C; 64];
+
+        for (idx, byte) in key.iter().enumerate() {
+            ipad[idx] ^= byte;
+            opad[idx] ^= byte;
+        }
+
+        let inner = self.hash(&[&ipad, message]);
+        self.hash(&[&opad, &inner])
+    }
+
+    // RFC 3414 A.2: the password stretched over a megabyte, localized later to each engine.
+    fn stretch(self, password: &str) -> Vec<u8> {
+        let stretched = password.bytes().cycle().take(1 << 20).collect::<Vec<_>>();
+
+        self.hash(&[&stretched])
+    }
+
+    fn localize(self, key: &[u8], engine: &[u8]) -> Vec<u8> {
+        self.hash(&[key, engine, key])
+    }
+}
+
+impl Privacy {
+    const ALL: [Privacy; 2] = [Privacy::None, Privacy::Aes];
+
+    fn label(self) -> &'static str {
+        match self {
+            Privacy::None => "Sin cifrado",
+            Privacy::Aes => "AES-128",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snmp {
+    pub port: u16,
+    pub version: Version,
+    pub community: String,
+    pub user: String,
+    pub auth: Auth,
+    pub auth_password: String,
+    pub privacy: Privacy,
+    pub privacy_password: String,
+}
+
+impl Snmp {
+    pub const DEFAULT: Snmp = Snmp {
+        port: 161,
+        version: Version::V2c,
+        community: String::new(),
+        user: String::new(),
+        auth: Auth::None,
+        auth_password: String::new(),
+        privacy: Privacy::None,
+        privacy_password: String::new(),
+    };
+}
+
+impl Kind for Snmp {
+    fn label(&self) -> &'static str {
+        "SNMP"
+    }
+
+    fn ping(&self, target: &Target) -> Vec<Reply> {
+        ping(target.address, self, target.probes, target.timeout)
+    }
+
+    fn editor(&mut self, ui: &mut Ui, id: Id, _address: &str, _bind: Option<&str>) {
+        ui.horizontal(|ui| {
+            ui.label("Puerto");
+            ui.add(DragValue::new(&mut self.port).clamp_range(1..=u16::MAX));
+
+            ComboBox::from_id_source(id.with("snmp_version"))
+                .selected_text(self.version.label())
+                .show_ui(ui, |ui| {
+                    for version in Version::ALL {
+                        ui.selectable_value(&mut self.version, version, version.label());
+                    }
+                });
+        });
+
+        if self.version != Version::V3 {
+            let community_input = TextEdit::singleline(&mut self.community)
+                .hint_text(WidgetText::italics("Comunidad (public)".into()))
+                .font(TextStyle::Monospace)
+                .password(true);
+
+            ui.add(community_input);
+            return;
+        }
+
+        let user_input = TextEdit::singleline(&mut self.user)
+            .hint_text(WidgetText::italics("Usuario".into()))
+            .font(TextStyle::Monospace);
+
+        ui.add(user_input);
+
+        ComboBox::from_id_source(id.with("snmp_auth"))
+            .selected_text(self.auth.label())
+            .show_ui(ui, |ui| {
+                for auth in Auth::ALL {
+                    ui.selectable_value(&mut self.auth, auth, auth.label());
+                }
+            });
+
+        if self.auth == Auth::None {
+            return;
+        }
+
+        let auth_input = TextEdit::singleline(&mut self.auth_password)
+            .hint_text(WidgetText::italics(
+                "Clave de autenticación (admite ${VARIABLE})".into(),
+            ))
+            .font(TextStyle::Monospace)
+            .password(true);
+
+        ui.add(auth_input);
+
+        ComboBox::from_id_source(id.with("snmp_privacy"))
+            .selected_text(self.privacy.label())
+            .show_ui(ui, |ui| {
+                for privacy in Privacy::ALL {
+                    ui.selectable_value(&mut self.privacy, privacy, privacy.label());
+                }
+            });
+
+        if self.privacy == Privacy::None {
+            return;
+        }
+
+        let privacy_input = TextEdit::singleline(&mut self.privacy_password)
+            .hint_text(WidgetText::italics(
+                "Clave de cifrado (admite ${VARIABLE})".into(),
+            ))
+            .font(TextStyle::Monospace)
+            .password(true);
+
+        ui.add(privacy_input);
+    }
+}
+
+#[derive(Clone)]
+enum Security {
+    Community(i64, String),
+    Usm(Usm),
+}
+
+#[derive(Clone)]
+struct Usm {
+    user: String,
+    auth: Auth,
+    auth_key: Vec<u8>,
+    privacy: Privacy,
+    privacy_key: Vec<u8>,
+}
+
+// Only a sysUpTime in the answer counts, an error status or noSuchObject is a failure.
+pub fn ping(addr: &str, snmp: &Snmp, probes: usize, timeout: Duration) -> Vec<Reply> {
+    let Some(security) = security(snmp) else {
+        return Reply::failures(None, probes);
+    };
+
+    let Some(ip) = check::resolve(addr) else {
+        return Reply::failures(None, probes);
+    };
+
+    let target = SocketAddr::new(ip, snmp.port);
+
+    check::fan_out(ip, probes, timeout, |_| {
+        let security = security.clone();
+
+        async move {
+            let duration = probe(target, security).await?;
+            Some(Reply::new(Pong::Success(duration)))
+        }
+    })
+}
+
+// Passwords are stretched once per round, only localizing them depends on the agent.
+fn security(snmp: &Snmp) -> Option<Security> {
+    let community = match snmp.community.as_str() {
+        "" => "public".to_owned(),
+        community => secrets::interpolate(community).ok()?,
+    };
+
+    let version = match snmp.version {
+        Version::V1 => 0,
+        Version::V2c => 1,
+        Version::V3 => {
+            let auth_password = secrets::interpolate(&snmp.auth_password).ok()?;
+            let privacy_password = secrets::interpolate(&snmp.privacy_password).ok()?;
+            let privacy = [snmp.privacy, Privacy::None][(snmp.auth == Auth::None) as usize];
+
+            return Some(Security::Usm(Usm {
+                user: snmp.user.clone(),
+                auth: snmp.auth,
+                auth_key: snmp.auth.stretch(&auth_password),
+                privacy,
+                privacy_key: match privacy {
+                    Privacy::None => vec![],
+                    Privacy::Aes => snmp.auth.stretch(&privacy_password),
+                },
+            }));
+        }
+    };
+
+    Some(Security::Community(version, community))
+}
+
+async fn probe(target: SocketAddr, security: Security) -> Option<Duration> {
+    let local = match target.ip() {
+        IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+        IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+    };
+
+    let socket = UdpSocket::bind((local, 0)).await.ok()?;
+    socket.connect(target).await.ok()?;
+
+    let request_id = rand::random::<i32>() as i64;
+    let pdu = get(request_id);
+
+    match security {
+        Security::Community(version, community) => {
+            let message = sequence(&[&integer(version), &tlv(OCTETS, community.as_bytes()), &pdu]);
+
+            let start = Instant::now();
+            let answer = exchange(&socket, &message).await?;
+            let elapsed = start.elapsed();
+
+            let mut fields = tlv_content(&answer, SEQUENCE)?;
+            take(&mut fields, INTEGER)?;
+            take(&mut fields, OCTETS)?;
+            uptime(take(&mut fields, RESPONSE)?, request_id)?;
+
+            Some(elapsed)
+        }
+        Security::Usm(usm) => {
+            // An empty engine id draws a report carrying the agent's engine id, boots and time.
+            let params = usm_params(&Engine::default(), "", &[], &[]);
+            let scoped = sequence(&[&tlv(OCTETS, &[]), &tlv(OCTETS, &[]), &get(0)]);
+            let discovery = v3_message(rand::random::<u16>() as i64, REPORTABLE, &params, &scoped);
+            let report = exchange(&socket, &discovery).await?;
+            let engine = v3_parse(&report)?.engine;
+
+            let auth_key = usm.auth.localize(&usm.auth_key, &engine.id);
+            let privacy_key = usm.auth.localize(&usm.privacy_key, &engine.id);
+
+            let mut flags = REPORTABLE;
+            let scoped = sequence(&[&tlv(OCTETS, &engine.id), &tlv(OCTETS, &[]), &pdu]);
+            let salt = rand::random::<[u8; 8]>();
+
+            let data = match usm.privacy {
+                Privacy::None => scoped,
+                Privacy::Aes => {
+                    flags |= PRIV_FLAG;
+                    let iv = iv(engine.boots, engine.time, &salt);
+                    tlv(OCTETS, &cfb(&privacy_key, iv, &scoped, true))
+                }
+            };
+
+            let signed = usm.auth != Auth::None;
+            let encrypted = usm.privacy != Privacy::None;
+
+            if signed {
+                flags |= AUTH_FLAG;
+            }
+
+            // The MAC goes in as zeros, it is computed over the whole message and patched in.
+            let mac = &[0; MAC_LEN][..[0, MAC_LEN][signed as usize]];
+            let params = usm_params(&engine, &usm.user, mac, &salt[..[0, 8][encrypted as usize]]);
+
+            let id = rand::random::<u16>() as i64;
+            let mut message = v3_message(id, flags, &params, &data);
+
+            if signed {
+                let mac = usm.auth.hmac(&auth_key, &message);
+                let at = find(&message, &tlv(OCTETS, &[0; MAC_LEN]))? + 2;
+                message[at..at + MAC_LEN].copy_from_slice(&mac[..MAC_LEN]);
+            }
+
+            let start = Instant::now();
+            let answer = exchange(&socket, &message).await?;
+            let elapsed = start.elapsed();
+
+            let parsed = v3_parse(&answer)?;
+
+            if signed {
+                let mut unsigned = answer.clone();
+                let at = parsed.mac_at?;
+                unsigned[at..at + MAC_LEN].fill(0);
+
+                if usm.auth.hmac(&auth_key, &unsigned)[..MAC_LEN] != answer[at..at + MAC_LEN] {
+                    return None;
+                }
+            }
+
+            let scoped = match usm.privacy {
+                Privacy::None => parsed.data,
+                Privacy::Aes => {
+                    let encrypted = tlv_content(&parsed.data, OCTETS)?;
+                    let iv = iv(parsed.engine.boots, parsed.engine.time, &parsed.salt);
+                    cfb(&privacy_key, iv, encrypted, false)
+                }
+            };
+
+            let mut fields = tlv_content(&scoped, SEQUENCE)?;
+            take(&mut fields, OCTETS)?;
+            take(&mut fields, OCTETS)?;
+            uptime(take(&mut fields, RESPONSE)?, request_id)?;
+
+            Some(elapsed)
+        }
+    }
+}
+
+async fn exchange(socket: &UdpSocket, message: &[u8]) -> Option<Vec<u8>> {
+    socket.send(message).await.ok()?;
+
+    let mut buf = vec![0; 65536];
+    let len = socket.recv(&mut buf).await.ok()?;
+    buf.truncate(len);
+    Some(buf)
+}
+
+fn get(request_id: i64) -> Vec<u8> {
+    let binding = sequence(&[&tlv(OID, SYS_UP_TIME), &tlv(NULL, &[])]);
+
+    tlv(
+        GET,
+        &[
+            integer(request_id),
+            integer(0),
+            integer(0),
+            sequence(&[&binding]),
+        ]
+        .concat(),
+    )
+}
+
+fn uptime(mut pdu: &[u8], request_id: i64) -> Option<Duration> {
+    let id = read_integer(take(&mut pdu, INTEGER)?)?;
+    let status = read_integer(take(&mut pdu, INTEGER)?)?;
+    take(&mut pdu, INTEGER)?;
+
+    if id != request_id || status != 0 {
+        return None;
+    }
+
+    let mut bindings = take(&mut pdu, SEQUENCE)?;
+    let mut binding = take(&mut bindings, SEQUENCE)?;
+
+    if take(&mut binding, OID)? != SYS_UP_TIME {
+        return None;
+    }
+
+    let ticks = read_integer(take(&mut binding, TIME_TICKS)?)?;
+    Some(Duration::from_millis(ticks as u64 * 10))
+}
+
+#[derive(Default)]
+struct Engine {
+    id: Vec<u8>,
+    boots: i64,
+    time: i64,
+}
+
+struct Parsed {
+    engine: Engine,
+    mac_at: Option<usize>,
+    salt: Vec<u8>,
+    data: Vec<u8>,
+}
+
+fn v3_message(id: i64, flags: u8, params: &[u8], data: &[u8]) -> Vec<u8> {
+    let header = sequence(&[
+        &integer(id),
+        &integer(MAX_SIZE),
+        &tlv(OCTETS, &[flags]),
+        &integer(USM),
+    ]);
+
+    sequence(&[&integer(3), &header, &tlv(OCTETS, params), data])
+}
+
+fn usm_params(engine: &Engine, user: &str, mac: &[u8], salt: &[u8]) -> Vec<u8> {
+    sequence(&[
+        &tlv(OCTETS, &engine.id),
+        &integer(engine.boots),
+        &integer(engine.time),
+        &tlv(OCTETS, user.as_bytes()),
+        &tlv(OCTETS, mac),
+        &tlv(OCTETS, salt),
+    ])
+}
+
+fn v3_parse(message: &[u8]) -> Option<Parsed> {
+    let mut fields = tlv_content(message, SEQUENCE)?;
+
+    if read_integer(take(&mut fields, INTEGER)?)? != 3 {
+        return None;
+    }
+
+    take(&mut fields, SEQUENCE)?;
+    let mut params = tlv_content(take(&mut fields, OCTETS)?, SEQUENCE)?;
+
+    let id = take(&mut params, OCTETS)?.to_vec();
+    let boots = read_integer(take(&mut params, INTEGER)?)?;
+    let time = read_integer(take(&mut params, INTEGER)?)?;
+    take(&mut params, OCTETS)?;
+    let mac = take(&mut params, OCTETS)?;
+    let salt = take(&mut params, OCTETS)?.to_vec();
+
+    let mac_at = (mac.len() == MAC_LEN).then(|| mac.as_ptr() as usize - message.as_ptr() as usize);
+
+    // Whatever follows is the scoped PDU, plain or wrapped in an encrypted octet string. A report
+    // in its place means the agent refused the request, and fails to parse as a response later.
+    Some(Parsed {
+        engine: Engine { id, boots, time },
+        mac_at,
+        salt,
+        data: fields.to_vec(),
+    })
+}
+
+// RFC 3826: the IV is the engine boots and time followed by the salt sent along.
+fn iv(boots: i64, time: i64, salt: &[u8]) -> [u8; 16] {
+    let mut iv = [0; 16];
+    iv[..4].copy_from_slice(&(boots as u32).to_be_bytes());
+    iv[4..8].copy_from_slice(&(time as u32).to_be_bytes());
+    iv[8..].copy_from_slice(&salt[..8.min(salt.len())]);
+    iv
+}
+
+fn cfb(key: &[u8], iv: [u8; 16], input: &[u8], encrypt: bool) -> Vec<u8> {
+    let cipher = Aes128::new(key[..16].into());
+    let mut register = iv;
+    let mut output = Vec::with_capacity(input.len());
+
+    for chunk in input.chunks(16) {
+        let mut stream = register.into();
+        cipher.encrypt_block(&mut stream);
+
+        let out = chunk.iter().zip(stream).map(|(a, b)| a ^ b).collect_vec();
+        let feedback = [&out[..], chunk][!encrypt as usize];
+        register[..feedback.len()].copy_from_slice(feedback);
+        output.extend(out);
+    }
+
+    output
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+fn tlv(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut tlv = vec![tag];
+
+    match content.len() {
+        len if len < 0x80 => tlv.push(len as u8),
+        len if len < 0x100 => tlv.extend([0x81, len as u8]),
+        len => tlv.extend([0x82, (len >> 8) as u8, len as u8]),
+    }
+
+    tlv.extend(content);
+    tlv
+}
+
+fn sequence(parts: &[&[u8]]) -> Vec<u8> {
+    tlv(SEQUENCE, &parts.concat())
+}
+
+fn integer(value: i64) -> Vec<u8> {
+    let bytes = value.to_be_bytes();
+    let mut start = 0;
+
+    // Drop leading bytes that only repeat the sign.
+    while start < 7
+        && ((bytes[start] == 0x00 && bytes[start + 1] & 0x80 == 0)
+            || (bytes[start] == 0xFF && bytes[start + 1] & 0x80 != 0))
+    {
+        start += 1;
+    }
+
+    tlv(INTEGER, &bytes[start..])
+}
+
+fn read_integer(bytes: &[u8]) -> Option<i64> {
+    if bytes.is_empty() || bytes.len() > 8 {
+        return None;
+    }
+
+    let fill = [0x00, 0xFF][(bytes[0] & 0x80 != 0) as usize];
+    let mut value = [fill; 8];
+    value[8 - bytes.len()..].copy_from_slice(bytes);
+    Some(i64::from_be_bytes(value))
+}
+
+fn tlv_content(input: &[u8], tag: u8) -> Option<&[u8]> {
+    let mut input = input;
+    take(&mut input, tag)
+}
+
+// Takes the next TLV off `input` if it carries the wanted tag, returning its contents.
+fn take<'a>(input: &mut &'a [u8], tag: u8) -> Option<&'a [u8]> {
+    let (&found, rest) = input.split_first()?;
+
+    if found != tag {
+        return None;
+    }
+
+    let (&first, rest) = rest.split_first()?;
+
+    let (len, rest) = match first {
+        len if len < 0x80 => (len as usize, rest),
+        long => {
+            let count = (long & 0x7F) as usize;
+
+            if count == 0 || count > 4 {
+                return None;
+            }
+
+            let len = rest
+                .get(..count)?
+                .iter()
+                .fold(0, |len, &byte| len << 8 | byte as usize);
+
+            (len, &rest[count..])
+        }
+    };
+
+    let content = rest.get(..len)?;
+    *input = &rest[len..];
+    Some(content)
+}
@@ -0,0 +1,205 @@
+use std::{hash::Hash, mem};
+
+use chrono::{DateTime, Utc};
+use egui::{ComboBox, DragValue, Grid, Ui};
+use serde::{Deserialize, Serialize};
+
+use crate::{plot, units::Units, Pong, Sample, FAIL};
+
+pub const PERCENTILES: [usize; 3] = [50, 95, 99];
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Span {
+    #[default]
+    Range,
+    Samples(usize),
+    Minutes(u32),
+}
+
+pub struct Summary {
+    sent: usize,
+    rtts: Vec<f64>,
+    jitter: Option<f64>,
+    percentiles: Option<[f64; PERCENTILES.len()]>,
+}
+
+impl Span {
+    const CHOICES: [Span; 3] = [Span::Range, Span::Samples(100), Span::Minutes(5)];
+
+    fn label(self) -> &'static str {
+        match self {
+            Span::Range => "Rango visible",
+            Span::Samples(_) => "Últimas muestras",
+            Span::Minutes(_) => "Últimos minutos",
+        }
+    }
+
+    // `visible` is whatever the time range picker selected, the other spans look at everything.
+    pub fn slice<'a>(
+        self,
+        history: &'a [Sample],
+        visible: &'a [Sample],
+        now: DateTime<Utc>,
+    ) -> &'a [Sample] {
+        match self {
+            Span::Range => visible,
+            Span::Samples(count) => &history[history.len().saturating_sub(count)..],
+            Span::Minutes(minutes) => {
+                let since = now - chrono::Duration::minutes(minutes as i64);
+                &history[history.partition_point(|sample| sample.time < since)..]
+            }
+        }
+    }
+}
+
+impl Summary {
+    pub fn of(history: &[Sample], span: &[Sample]) -> Self {
+        let mut sorted = rtts(span);
+        sorted.sort_by(f64::total_cmp);
+
+        Self {
+            sent: history.len(),
+            rtts: rtts(history),
+            jitter: plot::jitter(history).last().map(|&[_, jitter]| jitter),
+            percentiles: percentiles(&sorted),
+        }
+    }
+
+    fn lost(&self) -> usize {
+        self.sent - self.rtts.len()
+    }
+
+    pub fn sent(&self) -> usize {
+        self.sent
+    }
+
+    pub fn loss(&self) -> f64 {
+        match self.sent {
+            0 => 0.,
+            sent => self.lost() as f64 / sent as f64 * 100.,
+        }
+    }
+
+    // Same figures as the summary line `ping` prints on exit, in seconds.
+    pub fn spread(&self) -> Option<[f64; 4]> {
+        if self.rtts.is_empty() {
+            return None;
+        }
+
+        let count = self.rtts.len() as f64;
+        let min = self.rtts.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = self.rtts.iter().copied().fold(0., f64::max);
+        let avg = self.rtts.iter().sum::<f64>() / count;
+        let variance = self.rtts.iter().map(|rtt| (rtt - avg).powi(2)).sum::<f64>() / count;
+
+        Some([min, avg, max, variance.sqrt()])
+    }
+
+    pub fn percentiles(&self) -> Option<[f64; PERCENTILES.len()]> {
+        self.percentiles
+    }
+
+    fn rows(&self, units: Units) -> Vec<(String, String)> {
+        let format =
+            |value: Option<f64>| value.map_or("—".into(), |value| units.format_secs(value));
+        let spread = self.spread();
+
+        let mut rows = vec![
+            ("Enviados".into(), self.sent.to_string()),
+            (
+                "Perdidos".into(),
+                format!("{} ({:.1} %)", self.lost(), self.loss()),
+            ),
+        ];
+
+        let labels = ["Mínimo", "Media", "Máximo", "Desviación"];
+
+        for (idx, label) in labels.into_iter().enumerate() {
+            rows.push((label.into(), format(spread.map(|spread| spread[idx]))));
+        }
+
+        for (idx, percentile) in PERCENTILES.into_iter().enumerate() {
+            let value = self.percentiles.map(|percentiles| percentiles[idx]);
+            rows.push((format!("p{}", percentile), format(value)));
+        }
+
+        rows.push(("Jitter".into(), format(self.jitter)));
+        rows
+    }
+}
+
+pub fn rtts(history: &[Sample]) -> Vec<f64> {
+    history
+        .iter()
+        .filter_map(|sample| match sample.reply.pong {
+            Pong::Success(rtt) => Some(rtt.as_secs_f64()),
+            Pong::Failure => None,
+        })
+        .collect()
+}
+
+pub fn percentiles(sorted: &[f64]) -> Option<[f64; PERCENTILES.len()]> {
+    if sorted.is_empty() {
+        return None;
+    }
+
+    Some(PERCENTILES.map(|percentile| sorted[(sorted.len() - 1) * percentile / 100]))
+}
+
+pub fn show(ui: &mut Ui, id_source: impl Hash, summary: &Summary, span: &mut Span, units: Units) {
+    let rows = summary.rows(units);
+
+    Grid::new(id_source).num_columns(2).show(ui, |ui| {
+        for (idx, (label, value)) in rows.iter().enumerate() {
+            ui.label(label);
+
+            // The second row is the packet loss.
+            match (idx, summary.lost()) {
+                (1, 1..) => ui.colored_label(FAIL, value),
+                _ => ui.monospace(value),
+            };
+
+            ui.end_row();
+        }
+    });
+
+    ui.horizontal(|ui| {
+        ui.label("Percentiles sobre");
+
+        ComboBox::from_id_source(ui.id().with("span"))
+            .selected_text(span.label())
+            .show_ui(ui, |ui| {
+                for choice in Span::CHOICES {
+                    let selected = mem::discriminant(span) == mem::discriminant(&choice);
+
+                    if ui.selectable_label(selected, choice.label()).clicked() && !selected {
+                        *span = choice;
+                    }
+                }
+            });
+
+        match span {
+            Span::Range => {}
+            Span::Samples(count) => {
+                ui.add(DragValue::new(count).clamp_range(1..=100_000));
+            }
+            Span::Minutes(minutes) => {
+                ui.add(
+                    DragValue::new(minutes)
+                        .clamp_range(1..=10_080)
+                        .suffix(" min"),
+                );
+            }
+        }
+    });
+
+    if ui.button("Copiar").clicked() {
+        let text = rows
+            .iter()
+            .map(|(label, value)| format!("{}\t{}", label, value))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        ui.ctx().copy_text(text);
+    }
+}
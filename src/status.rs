@@ -0,0 +1,77 @@
+use std::mem;
+
+use egui::{Context, TopBottomPanel};
+
+use crate::{
+    policy::{self, Policy},
+    units::Units,
+    Canvas, Pong, Sample, GROUPS,
+};
+
+pub fn show(ctx: &Context, canvases: &[Canvas], policies: &[Policy; GROUPS.len()], units: Units) {
+    let windows = canvases.iter().flat_map(|canvas| &canvas.windows);
+
+    let mut hosts = 0;
+    let mut scanning = 0;
+    let mut down = 0;
+    let mut rate = 0.;
+    let mut samples = 0;
+    let mut worst = None;
+
+    for win in windows {
+        hosts += 1;
+        samples += win.history.len();
+
+        if !win.scanning {
+            continue;
+        }
+
+        scanning += 1;
+        down += (win.success == Some(false)) as usize;
+
+        let group = policies[win.group].over(policy::DEFAULTS);
+        rate += win.probes as f64 / win.policy.over(group).interval.as_secs_f64();
+
+        if win.success != Some(true) {
+            continue;
+        }
+
+        let rtt = win
+            .history
+            .iter()
+            .rev()
+            .find_map(|sample| match sample.reply.pong {
+                Pong::Success(rtt) => Some(rtt),
+                Pong::Failure => None,
+            });
+
+        let title = [&win.hostname, &win.address][win.hostname.is_empty() as usize];
+
+        if let Some(rtt) = rtt.filter(|&rtt| worst.is_none_or(|(_, worst)| rtt > worst)) {
+            worst = Some((title, rtt));
+        }
+    }
+
+    let megabytes = (samples * mem::size_of::<Sample>()) as f64 / (1024. * 1024.);
+
+    TopBottomPanel::bottom("status").show(ctx, |ui| {
+        ui.horizontal(|ui| {
+            ui.label(format!("{} hosts", hosts));
+            ui.separator();
+            ui.label(format!("{} sondeando", scanning));
+            ui.separator();
+            ui.label(format!("{} caídos", down));
+            ui.separator();
+
+            match worst {
+                Some((host, rtt)) => ui.label(format!("Peor: {} ({})", units.format(rtt), host)),
+                None => ui.label("Peor: —"),
+            };
+
+            ui.separator();
+            ui.label(format!("{:.1} sondas/s", rate));
+            ui.separator();
+            ui.label(format!("{} muestras ({:.1} MB)", samples, megabytes));
+        });
+    });
+}
@@ -0,0 +1,962 @@
+use std::{
+    collections::HashMap,
+    env, fs, mem,
+    net::IpAddr,
+    path::PathBuf,
+    process,
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Local, TimeDelta, Utc};
+use egui::{Button, Color32, Context, DragValue, Grid, ScrollArea, Stroke, Ui, Window};
+use ring::digest;
+use rusqlite::{params, Connection, Row};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    check::Check,
+    clock::Clock,
+    game,
+    worker::{self, Job},
+    Canvas, Pong, Reply, Sample, FAIL, GROUPS,
+};
+
+const COMPACT_EVERY: Duration = Duration::from_secs(3600);
+const MINUTE: i64 = 60_000_000;
+
+const COLUMNS: [(&str, &str); 4] = [
+    ("clock_offset", "INTEGER"),
+    ("players_online", "INTEGER"),
+    ("players_max", "INTEGER"),
+    ("setup", "INTEGER"),
+];
+
+const SELECT: &str = "SELECT time, check_kind, seq, ip, ttl, size, rtt, jitter, clock_offset,
+                             players_online, players_max, setup FROM samples";
+
+const SCHEMA: &str = "
+    PRAGMA journal_mode = WAL;
+    PRAGMA synchronous = NORMAL;
+
+    CREATE TABLE IF NOT EXISTS samples (
+        target TEXT NOT NULL,
+        time INTEGER NOT NULL,
+        check_kind TEXT NOT NULL,
+        seq INTEGER NOT NULL,
+        ip TEXT,
+        ttl INTEGER,
+        size INTEGER NOT NULL,
+        rtt INTEGER,
+        jitter INTEGER,
+        clock_offset INTEGER,
+        players_online INTEGER,
+        players_max INTEGER,
+        setup INTEGER
+    );
+
+    CREATE INDEX IF NOT EXISTS samples_by_target ON samples (target, time);
+
+    CREATE TABLE IF NOT EXISTS rollups (
+        target TEXT NOT NULL,
+        minute INTEGER NOT NULL,
+        sent INTEGER NOT NULL,
+        lost INTEGER NOT NULL,
+        rtt_min INTEGER,
+        rtt_avg INTEGER,
+        rtt_max INTEGER,
+        PRIMARY KEY (target, minute)
+    );
+";
+
+// Raw samples are folded into one row per minute once they age out, then dropped for good.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct Retention {
+    raw: Duration,
+    aggregated: Duration,
+}
+
+impl Default for Retention {
+    fn default() -> Self {
+        Self {
+            raw: Duration::from_secs(24 * 3600),
+            aggregated: Duration::from_secs(90 * 24 * 3600),
+        }
+    }
+}
+
+struct Usage {
+    target: String,
+    samples: u64,
+    rollups: u64,
+    since: Option<DateTime<Utc>>,
+}
+
+// Times are stored as UNIX microseconds and durations as microseconds, a lost probe has no RTT.
+#[derive(Default, Serialize, Deserialize)]
+pub struct Store {
+    enabled: bool,
+
+    #[serde(default)]
+    retention: [Retention; GROUPS.len()],
+
+    #[serde(skip)]
+    pub open: bool,
+
+    #[serde(skip)]
+    db: Option<Connection>,
+
+    // Inserts go through a second connection off the UI thread, one batch at a time.
+    #[serde(skip)]
+    writer: Option<Connection>,
+
+    #[serde(skip)]
+    writing: Option<Job<(Connection, Result<(), String>)>>,
+
+    #[serde(skip)]
+    queue: Vec<(String, Vec<Sample>)>,
+
+    #[serde(skip)]
+    compacted: Option<Instant>,
+
+    // Per group, raw samples from here on wait for the daily archive and are never compacted.
+    #[serde(skip)]
+    keep: [Option<DateTime<Utc>>; GROUPS.len()],
+
+    #[serde(skip)]
+    usage: Option<Vec<Usage>>,
+
+    #[serde(skip)]
+    error: Option<String>,
+}
+
+impl Store {
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn restore(&mut self, canvases: &mut [Canvas], limit: usize) {
+        let Some(db) = self.open() else {
+            return;
+        };
+
+        let windows = canvases.iter_mut().flat_map(|canvas| &mut canvas.windows);
+        let mut error = None;
+
+        for win in windows {
+            win.history.set_capacity(limit);
+            let key = key(&win.address, &win.check);
+
+            if let Err(err) = adopt(db, &win.address, &key, win.check.label()) {
+                error = Some(err.to_string());
+            }
+
+            match load(db, &key, limit) {
+                Ok(samples) => samples
+                    .into_iter()
+                    .for_each(|sample| win.history.push(sample)),
+                Err(err) => error = Some(err.to_string()),
+            }
+        }
+
+        if error.is_some() {
+            self.error = error;
+        }
+    }
+
+    // Queued until the next `run`, which writes everything gathered since in one go.
+    pub fn save(&mut self, key: String, samples: &[Sample]) {
+        if self.enabled && !samples.is_empty() {
+            self.queue.push((key, samples.to_vec()));
+        }
+    }
+
+    pub fn run(
+        &mut self,
+        ctx: &Context,
+        canvases: &[Canvas],
+        keep: [Option<DateTime<Utc>>; GROUPS.len()],
+        clock: &Clock,
+    ) {
+        self.keep = keep;
+        self.write(Some(ctx));
+
+        if self
+            .compacted
+            .is_some_and(|at| clock.since(at) < COMPACT_EVERY)
+        {
+            return;
+        }
+
+        if self.enabled {
+            self.compact(canvases, false, clock);
+        }
+    }
+
+    fn compact(&mut self, canvases: &[Canvas], vacuum: bool, clock: &Clock) {
+        self.compacted = Some(clock.now());
+        self.usage = None;
+
+        // Targets no window points at anymore fall back to the first group.
+        let groups = canvases
+            .iter()
+            .flat_map(|canvas| &canvas.windows)
+            .map(|win| (key(&win.address, &win.check), win.group))
+            .collect::<HashMap<_, _>>();
+
+        let retention = self.retention;
+        let keep = self.keep;
+
+        let Some(db) = self.open() else {
+            return;
+        };
+
+        if let Err(err) = compact(db, &groups, &retention, &keep, clock.utc(), vacuum) {
+            self.error = Some(err.to_string());
+        }
+    }
+
+    // Every sample for the target in `[from, to)`, oldest first. Where the raw samples are gone,
+    // the minutes folded from them stand in (see `rollups`).
+    pub fn range(
+        &mut self,
+        target: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<Sample>, String> {
+        if !self.enabled {
+            return Err("El historial en disco está desactivado".into());
+        }
+
+        let Some(db) = self.open() else {
+            return Err(self.error.clone().unwrap_or_default());
+        };
+
+        range(db, target, from, to).map_err(|err| err.to_string())
+    }
+
+    // Without a context the batch is written in place, waiting for the one in flight first.
+    fn write(&mut self, ctx: Option<&Context>) {
+        let done = match (&self.writing, ctx) {
+            (None, _) => None,
+            (Some(job), Some(_)) => job.done(),
+            (Some(_), None) => self.writing.take().map(Job::wait),
+        };
+
+        match done {
+            None if self.writing.is_some() => return,
+            None => {}
+            Some(done) => {
+                self.writing = None;
+
+                if let Some((writer, result)) = done {
+                    self.writer = Some(writer);
+                    self.error = result.err().or(self.error.take());
+                }
+            }
+        }
+
+        if self.queue.is_empty() || !self.enabled {
+            return;
+        }
+
+        let writer = match self.writer.take() {
+            Some(writer) => writer,
+            None => match connect() {
+                Ok(writer) => writer,
+                Err(err) => {
+                    self.error = Some(err);
+                    return;
+                }
+            },
+        };
+
+        let queue = mem::take(&mut self.queue);
+        let task = move || {
+            let mut writer = writer;
+            let result = insert(&mut writer, &queue).map_err(|err| err.to_string());
+            (writer, result)
+        };
+
+        match ctx {
+            Some(ctx) => self.writing = Some(worker::spawn(ctx, task)),
+            None => {
+                let (writer, result) = task();
+                self.writer = Some(writer);
+                self.error = result.err().or(self.error.take());
+            }
+        }
+    }
+
+    // Writes whatever is still queued before returning.
+    pub fn flush(&mut self) {
+        self.write(None);
+    }
+
+    // When the oldest sample or minute kept for the target starts.
+    pub fn since(&mut self, target: &str) -> Result<Option<DateTime<Utc>>, String> {
+        let Some(db) = self.open() else {
+            return Err(self.error.clone().unwrap_or_default());
+        };
+
+        db.query_row(
+            "SELECT MIN(since) FROM (
+                 SELECT MIN(time) AS since FROM samples WHERE target = ?1
+                 UNION ALL
+                 SELECT MIN(minute) * ?2 FROM rollups WHERE target = ?1
+             )",
+            params![target, MINUTE],
+            |row| row.get::<_, Option<i64>>(0),
+        )
+        .map(|since| since.and_then(DateTime::from_timestamp_micros))
+        .map_err(|err| err.to_string())
+    }
+
+    // A consistent copy of the database, even while the WAL still holds recent writes.
+    pub fn snapshot(&mut self) -> Result<Option<Vec<u8>>, String> {
+        self.flush();
+
+        let Some(db) = self.open() else {
+            return Ok(None);
+        };
+
+        let copy = env::temp_dir().join(format!("pinga-{}.sqlite3", process::id()));
+        let _ = fs::remove_file(&copy);
+
+        db.execute("VACUUM INTO ?1", params![copy.display().to_string()])
+            .map_err(|err| err.to_string())?;
+
+        let bytes = fs::read(&copy).map_err(|err| err.to_string());
+        let _ = fs::remove_file(&copy);
+        bytes.map(Some)
+    }
+
+    pub fn replace(&mut self, bytes: &[u8]) -> Result<(), String> {
+        self.flush();
+        self.db = None;
+        self.writer = None;
+        self.usage = None;
+
+        let path = path().ok_or("No hay carpeta de datos")?;
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+        }
+
+        for stale in ["sqlite3-wal", "sqlite3-shm"] {
+            let _ = fs::remove_file(path.with_extension(stale));
+        }
+
+        fs::write(path, bytes).map_err(|err| err.to_string())
+    }
+
+    fn open(&mut self) -> Option<&mut Connection> {
+        if !self.enabled {
+            self.db = None;
+            self.writer = None;
+            self.queue.clear();
+            return None;
+        }
+
+        if self.db.is_none() && self.error.is_none() {
+            match connect() {
+                Ok(db) => self.db = Some(db),
+                Err(err) => self.error = Some(err),
+            }
+        }
+
+        self.db.as_mut()
+    }
+}
+
+pub fn editor(ui: &mut Ui, store: &mut Store) {
+    if ui
+        .checkbox(&mut store.enabled, "Guardar historial en disco")
+        .changed()
+    {
+        store.error = None;
+    }
+
+    if let Some(path) = path().filter(|_| store.enabled) {
+        ui.weak(path.display().to_string());
+    }
+
+    if let Some(error) = &store.error {
+        ui.colored_label(FAIL, error);
+    }
+}
+
+pub fn show(ctx: &Context, store: &mut Store, canvases: &[Canvas], clock: &Clock) {
+    let mut open = store.open;
+
+    Window::new("Almacenamiento")
+        .open(&mut open)
+        .default_width(360.)
+        .show(ctx, |ui| {
+            if !store.enabled {
+                ui.weak("El historial no se guarda en disco");
+                return;
+            }
+
+            let size = path()
+                .into_iter()
+                .flat_map(|path| [path.clone(), path.with_extension("sqlite3-wal")])
+                .filter_map(|path| fs::metadata(path).ok())
+                .map(|meta| meta.len())
+                .sum::<u64>();
+
+            ui.label(format!("{:.1} MiB en disco", size as f64 / (1024. * 1024.)));
+
+            ui.separator();
+            ui.strong("Retención");
+
+            Grid::new("retention").num_columns(3).show(ui, |ui| {
+                for (idx, retention) in store.retention.iter_mut().enumerate() {
+                    let stroke = Stroke::new(0.5, Color32::BLACK);
+                    let swatch = Button::new("     ").fill(GROUPS[idx]).stroke(stroke);
+
+                    ui.add_enabled(false, swatch);
+
+                    let mut hours = retention.raw.as_secs() / 3600;
+                    let raw_input = DragValue::new(&mut hours)
+                        .clamp_range(1..=24 * 365)
+                        .prefix("crudo ")
+                        .suffix(" h");
+
+                    if ui.add(raw_input).changed() {
+                        retention.raw = Duration::from_secs(hours * 3600);
+                    }
+
+                    let mut days = retention.aggregated.as_secs() / (24 * 3600);
+                    let aggregated_input = DragValue::new(&mut days)
+                        .clamp_range(1..=3650)
+                        .prefix("agregado ")
+                        .suffix(" d");
+
+                    if ui.add(aggregated_input).changed() {
+                        retention.aggregated = Duration::from_secs(days * 24 * 3600);
+                    }
+
+                    ui.end_row();
+                }
+            });
+
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                if ui.button("Actualizar").clicked() {
+                    store.usage = None;
+                }
+
+                if ui.button("Compactar ahora").clicked() {
+                    store.compact(canvases, true, clock);
+                }
+            });
+
+            if let Some(error) = &store.error {
+                ui.colored_label(FAIL, error);
+            }
+
+            if store.usage.is_none() {
+                let usage = store.open().map(|db| usage(db));
+
+                match usage {
+                    Some(Ok(usage)) => store.usage = Some(usage),
+                    Some(Err(err)) => store.error = Some(err.to_string()),
+                    None => {}
+                }
+            }
+
+            let Some(usage) = &store.usage else {
+                return;
+            };
+
+            let memory = canvases
+                .iter()
+                .flat_map(|canvas| &canvas.windows)
+                .map(|win| (key(&win.address, &win.check), win.history.len()))
+                .collect::<HashMap<_, _>>();
+
+            ScrollArea::vertical().max_height(240.).show(ui, |ui| {
+                Grid::new("usage")
+                    .num_columns(5)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for header in ["Destino", "En memoria", "Muestras", "Minutos", "Desde"] {
+                            ui.strong(header);
+                        }
+
+                        ui.end_row();
+
+                        for row in usage {
+                            let since = row.since.map_or("—".into(), |since| {
+                                since
+                                    .with_timezone(&Local)
+                                    .format("%Y-%m-%d %H:%M")
+                                    .to_string()
+                            });
+
+                            ui.monospace(&row.target);
+                            ui.monospace(memory.get(&row.target).copied().unwrap_or(0).to_string());
+                            ui.monospace(row.samples.to_string());
+                            ui.monospace(row.rollups.to_string());
+                            ui.monospace(since);
+                            ui.end_row();
+                        }
+                    });
+            });
+        });
+
+    store.open &= open;
+}
+
+// Samples belong to an address probed by one particular check, two windows on the same address
+// with different checks keep apart. The check goes in as a short digest of its saved form.
+pub fn key(address: &str, check: &Check) -> String {
+    let saved = ron::to_string(check).unwrap_or_default();
+    let digest = digest::digest(&digest::SHA256, saved.as_bytes());
+    let hex = digest.as_ref()[..4]
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<String>();
+
+    format!("{} {}-{}", address, check.label(), hex)
+}
+
+fn path() -> Option<PathBuf> {
+    eframe::storage_dir("PingA").map(|dir| dir.join("history.sqlite3"))
+}
+
+fn connect() -> Result<Connection, String> {
+    let path = path().ok_or("No hay carpeta de datos")?;
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+    }
+
+    let db = Connection::open(path).map_err(|err| err.to_string())?;
+    db.execute_batch(SCHEMA).map_err(|err| err.to_string())?;
+    migrate(&db).map_err(|err| err.to_string())?;
+    Ok(db)
+}
+
+// `ALTER TABLE` has no `IF NOT EXISTS`, so columns added later are probed for one by one.
+fn migrate(db: &Connection) -> rusqlite::Result<()> {
+    for (column, kind) in COLUMNS {
+        if db
+            .prepare(&format!("SELECT {} FROM samples LIMIT 0", column))
+            .is_err()
+        {
+            db.execute_batch(&format!(
+                "ALTER TABLE samples ADD COLUMN {} {}",
+                column, kind
+            ))?;
+        }
+    }
+
+    Ok(())
+}
+
+// History saved before the check was part of the key goes to the first window on that address
+// with the same type of check.
+fn adopt(db: &mut Connection, address: &str, key: &str, label: &str) -> rusqlite::Result<()> {
+    let tx = db.transaction()?;
+
+    tx.execute(
+        "UPDATE samples SET target = ?2 WHERE target = ?1 AND check_kind = ?3",
+        params![address, key, label],
+    )?;
+
+    tx.execute(
+        "UPDATE OR IGNORE rollups SET target = ?2 WHERE target = ?1",
+        params![address, key],
+    )?;
+
+    tx.commit()
+}
+
+fn insert(db: &mut Connection, batch: &[(String, Vec<Sample>)]) -> rusqlite::Result<()> {
+    let tx = db.transaction()?;
+
+    {
+        let mut statement = tx.prepare_cached(
+            "INSERT INTO samples
+                 (target, time, check_kind, seq, ip, ttl, size, rtt, jitter, clock_offset,
+                  players_online, players_max, setup)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+        )?;
+
+        for (target, sample) in batch
+            .iter()
+            .flat_map(|(target, samples)| samples.iter().map(move |sample| (target, sample)))
+        {
+            let reply = sample.reply;
+
+            let rtt = match reply.pong {
+                Pong::Success(rtt) => Some(micros(rtt)),
+                Pong::Failure => None,
+            };
+
+            statement.execute(params![
+                target,
+                sample.time.timestamp_micros(),
+                sample.check,
+                reply.seq,
+                reply.target.map(|ip| ip.to_string()),
+                reply.ttl,
+                reply.size as i64,
+                rtt,
+                sample.jitter.map(micros),
+                reply.offset.and_then(|offset| offset.num_microseconds()),
+                reply.players.map(|players| players.online),
+                reply.players.map(|players| players.max),
+                reply.setup.map(micros),
+            ])?;
+        }
+    }
+
+    tx.commit()
+}
+
+// The newest `limit` samples for the target, oldest first.
+fn load(db: &Connection, target: &str, limit: usize) -> rusqlite::Result<Vec<Sample>> {
+    let mut statement = db.prepare(&format!(
+        "{} WHERE target = ?1 ORDER BY time DESC, seq DESC LIMIT ?2",
+        SELECT
+    ))?;
+
+    let rows = statement.query_map(params![target, limit as i64], sample)?;
+    let mut samples = rows.collect::<rusqlite::Result<Vec<_>>>()?;
+    samples.reverse();
+    Ok(samples)
+}
+
+fn range(
+    db: &Connection,
+    target: &str,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> rusqlite::Result<Vec<Sample>> {
+    let mut statement = db.prepare(&format!(
+        "{} WHERE target = ?1 AND time >= ?2 AND time < ?3 ORDER BY time, seq",
+        SELECT
+    ))?;
+
+    let bounds = [from, to].map(|time| time.timestamp_micros());
+    let rows = statement.query_map(params![target, bounds[0], bounds[1]], sample)?;
+    let raw = rows.collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut samples = rollups(db, target, from, to)?;
+    samples.extend(raw);
+    Ok(samples)
+}
+
+// Minutes older than the first raw sample left, each spread back into as many samples as probes
+// it counted: the lost ones as failures, the rest at the average RTT. Loss and mean survive, the
+// spread within the minute does not.
+fn rollups(
+    db: &Connection,
+    target: &str,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> rusqlite::Result<Vec<Sample>> {
+    let mut statement = db.prepare(
+        "SELECT minute, sent, lost, rtt_avg FROM rollups
+         WHERE target = ?1 AND minute * ?2 >= ?3 AND minute * ?2 < ?4
+           AND minute * ?2 < (SELECT IFNULL(MIN(time), ?4) FROM samples WHERE target = ?1)
+         ORDER BY minute",
+    )?;
+
+    // The key is the address, then the label and digest of the check.
+    let check = target
+        .split_once(' ')
+        .and_then(|(_, check)| check.rsplit_once('-'))
+        .map_or("", |(label, _)| label);
+
+    let check = label(check);
+    let bounds = [from, to].map(|time| time.timestamp_micros());
+    let params = params![target, MINUTE, bounds[0], bounds[1]];
+
+    let minutes = statement.query_map(params, |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, i64>(1)?,
+            row.get::<_, i64>(2)?,
+            row.get::<_, Option<f64>>(3)?,
+        ))
+    })?;
+
+    let mut samples = vec![];
+
+    for minute in minutes {
+        let (minute, sent, lost, rtt) = minute?;
+        let time = DateTime::from_timestamp_micros(minute * MINUTE).unwrap_or_default();
+
+        samples.extend((0..sent).map(|seq| Sample {
+            time,
+            check,
+            reply: Reply {
+                pong: match (seq < sent - lost, rtt) {
+                    (true, Some(rtt)) => Pong::Success(duration(rtt as i64)),
+                    _ => Pong::Failure,
+                },
+                seq: seq as u16,
+                ..Reply::new(Pong::Failure)
+            },
+            jitter: None,
+        }));
+    }
+
+    Ok(samples)
+}
+
+fn label(saved: &str) -> &'static str {
+    Check::KINDS
+        .iter()
+        .map(Check::label)
+        .find(|label| *label == saved)
+        .unwrap_or(Check::default().label())
+}
+
+fn sample(row: &Row) -> rusqlite::Result<Sample> {
+    let check = row.get::<_, String>(1)?;
+    let ip = row.get::<_, Option<String>>(3)?;
+    let rtt = row.get::<_, Option<i64>>(6)?;
+    let online = row.get::<_, Option<u32>>(9)?;
+    let max = row.get::<_, Option<u32>>(10)?;
+
+    Ok(Sample {
+        time: DateTime::from_timestamp_micros(row.get(0)?).unwrap_or_default(),
+        check: label(&check),
+        reply: Reply {
+            pong: rtt.map_or(Pong::Failure, |rtt| Pong::Success(duration(rtt))),
+            seq: row.get(2)?,
+            target: ip.and_then(|ip| ip.parse::<IpAddr>().ok()),
+            ttl: row.get(4)?,
+            size: row.get::<_, i64>(5)? as usize,
+            offset: row.get::<_, Option<i64>>(8)?.map(TimeDelta::microseconds),
+            players: online
+                .zip(max)
+                .map(|(online, max)| game::Players { online, max }),
+            setup: row.get::<_, Option<i64>>(11)?.map(duration),
+        },
+        jitter: row.get::<_, Option<i64>>(7)?.map(duration),
+    })
+}
+
+fn compact(
+    db: &mut Connection,
+    groups: &HashMap<String, usize>,
+    retention: &[Retention; GROUPS.len()],
+    keep: &[Option<DateTime<Utc>>; GROUPS.len()],
+    now: DateTime<Utc>,
+    vacuum: bool,
+) -> rusqlite::Result<()> {
+    let now = now.timestamp_micros();
+    let tx = db.transaction()?;
+
+    let targets = tx
+        .prepare("SELECT DISTINCT target FROM samples UNION SELECT DISTINCT target FROM rollups")?
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    for target in targets {
+        let group = groups.get(&target).copied().unwrap_or(0);
+        let retention = retention[group];
+        let kept = keep[group].map_or(i64::MAX, |time| time.timestamp_micros());
+
+        // Cut on a minute boundary so no minute is ever folded in two halves.
+        let raw = (now - micros(retention.raw)).min(kept).div_euclid(MINUTE) * MINUTE;
+        let aggregated = (now - micros(retention.aggregated)).div_euclid(MINUTE);
+
+        tx.execute(
+            "INSERT INTO rollups (target, minute, sent, lost, rtt_min, rtt_avg, rtt_max)
+             SELECT target, time / ?2, COUNT(*), COUNT(*) - COUNT(rtt), MIN(rtt), AVG(rtt), MAX(rtt)
+             FROM samples WHERE target = ?1 AND time < ?3
+             GROUP BY time / ?2
+             ON CONFLICT (target, minute) DO NOTHING",
+            params![target, MINUTE, raw],
+        )?;
+
+        tx.execute(
+            "DELETE FROM samples WHERE target = ?1 AND time < ?2",
+            params![target, raw],
+        )?;
+
+        tx.execute(
+            "DELETE FROM rollups WHERE target = ?1 AND minute < ?2",
+            params![target, aggregated],
+        )?;
+    }
+
+    tx.commit()?;
+
+    match vacuum {
+        true => db.execute_batch("PRAGMA wal_checkpoint(TRUNCATE); VACUUM;"),
+        false => Ok(()),
+    }
+}
+
+fn usage(db: &Connection) -> rusqlite::Result<Vec<Usage>> {
+    let mut statement = db.prepare(
+        "SELECT target, SUM(samples), SUM(rollups), MIN(since) FROM (
+             SELECT target, COUNT(*) AS samples, 0 AS rollups, MIN(time) AS since
+             FROM samples GROUP BY target
+             UNION ALL
+             SELECT target, 0, COUNT(*), MIN(minute) * ?1 FROM rollups GROUP BY target
+         ) GROUP BY target ORDER BY target",
+    )?;
+
+    let rows = statement.query_map(params![MINUTE], |row| {
+        Ok(Usage {
+            target: row.get(0)?,
+            samples: row.get::<_, i64>(1)? as u64,
+            rollups: row.get::<_, i64>(2)? as u64,
+            since: row
+                .get::<_, Option<i64>>(3)?
+                .and_then(DateTime::from_timestamp_micros),
+        })
+    })?;
+
+    rows.collect()
+}
+
+fn micros(duration: Duration) -> i64 {
+    duration.as_micros() as i64
+}
+
+fn duration(micros: i64) -> Duration {
+    Duration::from_micros(micros.max(0) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, time::Duration};
+
+    use chrono::{DateTime, TimeDelta, Utc};
+    use rusqlite::Connection;
+
+    use super::{adopt, compact, insert, key, load, migrate, range, SCHEMA};
+    use crate::{
+        check::{Check, Icmp},
+        tcp::Tcp,
+        Pong, Reply, Sample,
+    };
+
+    fn memory() -> Connection {
+        let db = Connection::open_in_memory().unwrap();
+        db.execute_batch(SCHEMA).unwrap();
+        migrate(&db).unwrap();
+        db
+    }
+
+    fn sample(check: &Check, secs: i64) -> Sample {
+        Sample {
+            time: DateTime::<Utc>::from_timestamp(secs, 0).unwrap(),
+            check: check.label(),
+            reply: Reply::new(Pong::Success(Duration::from_millis(secs as u64))),
+            jitter: None,
+        }
+    }
+
+    fn pong(sample: &Sample) -> Option<u128> {
+        match sample.reply.pong {
+            Pong::Success(rtt) => Some(rtt.as_millis()),
+            Pong::Failure => None,
+        }
+    }
+
+    #[test]
+    fn checks_on_one_address_keep_their_own_history() {
+        let mut db = memory();
+        let icmp = Check::Icmp(Icmp);
+        let tcp = Check::Tcp(Tcp { port: 443 });
+        let keys = [&icmp, &tcp].map(|check| key("10.0.0.1", check));
+
+        assert_ne!(keys[0], keys[1]);
+        assert_ne!(key("10.0.0.1", &Check::Tcp(Tcp { port: 22 })), keys[1]);
+
+        // One row from before the check was part of the key.
+        let legacy = [(String::from("10.0.0.1"), vec![sample(&tcp, 1)])];
+        insert(&mut db, &legacy).unwrap();
+        adopt(&mut db, "10.0.0.1", &keys[0], icmp.label()).unwrap();
+        adopt(&mut db, "10.0.0.1", &keys[1], tcp.label()).unwrap();
+
+        let batch = [
+            (keys[0].clone(), vec![sample(&icmp, 2), sample(&icmp, 3)]),
+            (keys[1].clone(), vec![sample(&tcp, 4)]),
+        ];
+        insert(&mut db, &batch).unwrap();
+
+        let times = |key: &str| {
+            load(&db, key, 10)
+                .unwrap()
+                .into_iter()
+                .map(|sample| (sample.check, sample.time.timestamp()))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(times(&keys[0]), [("ICMP", 2), ("ICMP", 3)]);
+        assert_eq!(times(&keys[1]), [("TCP", 1), ("TCP", 4)]);
+    }
+
+    #[test]
+    fn compacted_minutes_stay_in_range() {
+        let mut db = memory();
+        let tcp = Check::Tcp(Tcp { port: 443 });
+        let key = key("10.0.0.1", &tcp);
+        let now = DateTime::<Utc>::from_timestamp(2 * 24 * 3600, 0).unwrap();
+
+        let lost = Sample {
+            reply: Reply::new(Pong::Failure),
+            ..sample(&tcp, 5)
+        };
+
+        let recent = Sample {
+            time: now - TimeDelta::hours(1),
+            ..sample(&tcp, 7)
+        };
+
+        let old = vec![sample(&tcp, 10), sample(&tcp, 30), lost, sample(&tcp, 80)];
+        insert(&mut db, &[(key.clone(), old), (key.clone(), vec![recent])]).unwrap();
+
+        let groups = HashMap::from([(key.clone(), 0)]);
+        compact(
+            &mut db,
+            &groups,
+            &Default::default(),
+            &Default::default(),
+            now,
+            false,
+        )
+        .unwrap();
+
+        let from = DateTime::<Utc>::from_timestamp(0, 0).unwrap();
+        let samples = range(&db, &key, from, now).unwrap();
+
+        let minutes = samples
+            .iter()
+            .map(|sample| (sample.time.timestamp(), sample.check, pong(sample)))
+            .collect::<Vec<_>>();
+
+        let recent = recent.time.timestamp();
+
+        assert_eq!(
+            minutes,
+            [
+                (0, "TCP", Some(20)),
+                (0, "TCP", Some(20)),
+                (0, "TCP", None),
+                (60, "TCP", Some(80)),
+                (recent, "TCP", Some(7)),
+            ]
+        );
+
+        // Only the raw samples are left once the span starts after the compacted minutes.
+        assert_eq!(
+            range(&db, &key, now - TimeDelta::days(1), now)
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+}
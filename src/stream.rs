@@ -0,0 +1,247 @@
+use std::{
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+use futures_util::SinkExt;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::{
+    net::{TcpListener, TcpStream},
+    sync::{
+        broadcast::{self, error::RecvError},
+        oneshot,
+    },
+};
+use tokio_tungstenite::tungstenite::{self, Message};
+
+use crate::{worker, Pong, Sample};
+
+const BACKLOG: usize = 1024;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Format {
+    #[default]
+    Json,
+    Grafana,
+}
+
+#[derive(Default)]
+pub struct Streamer {
+    bind: Option<String>,
+    error: Arc<Mutex<Option<String>>>,
+    stop: Option<oneshot::Sender<()>>,
+    frames: Option<broadcast::Sender<String>>,
+}
+
+impl Format {
+    pub const ALL: [Format; 2] = [Format::Json, Format::Grafana];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Format::Json => "JSON",
+            Format::Grafana => "Grafana Live (protocolo de línea)",
+        }
+    }
+
+    fn frame(self, host: &str, address: &str, group: usize, sample: &Sample) -> String {
+        let rtt = rtt(sample);
+
+        match self {
+            Format::Json => json(host, address, group, sample).to_string(),
+            Format::Grafana => {
+                let mut fields =
+                    format!("success={}i,seq={}i", rtt.is_some() as u8, sample.reply.seq);
+
+                if let Some(rtt) = rtt {
+                    fields += &format!(",rtt_ms={}", rtt);
+                }
+
+                if let Some(ttl) = sample.reply.ttl {
+                    fields += &format!(",ttl={}i", ttl);
+                }
+
+                if let Some(offset) = offset(sample) {
+                    fields += &format!(",offset_ms={}", offset);
+                }
+
+                if let Some(setup) = setup(sample) {
+                    fields += &format!(",setup_ms={}", setup);
+                }
+
+                if let Some(players) = sample.reply.players {
+                    fields += &format!(",players={}i,players_max={}i", players.online, players.max);
+                }
+
+                format!(
+                    "pinga,host={},address={},group={},check={} {} {}",
+                    escape(host),
+                    escape(address),
+                    group + 1,
+                    escape(sample.check),
+                    fields,
+                    sample.time.timestamp_nanos_opt().unwrap_or_default(),
+                )
+            }
+        }
+    }
+}
+
+impl Streamer {
+    pub fn bind(&self) -> Option<&str> {
+        self.bind.as_deref()
+    }
+
+    pub fn error(&self) -> Option<String> {
+        self.error.lock().unwrap().clone()
+    }
+
+    pub fn start(&mut self, bind: &str) {
+        let (stop, stopped) = oneshot::channel();
+        let (frames, _) = broadcast::channel(BACKLOG);
+        let error = Arc::new(Mutex::new(None));
+
+        self.bind = Some(bind.into());
+        self.error = error.clone();
+        self.stop = Some(stop);
+        self.frames = Some(frames.clone());
+
+        let bind = bind.to_owned();
+
+        worker::spawn_task(async move {
+            if let Err(err) = serve(&bind, frames, stopped).await {
+                *error.lock().unwrap() = Some(err.to_string());
+            }
+        });
+    }
+
+    pub fn stop(&mut self) {
+        self.bind = None;
+        self.stop = None;
+        self.frames = None;
+        *self.error.lock().unwrap() = None;
+    }
+
+    // Gives the clients up to `patience` to take the frames still queued for them.
+    pub fn flush(&self, patience: Duration) {
+        let Some(frames) = &self.frames else {
+            return;
+        };
+
+        let start = Instant::now();
+
+        while !frames.is_empty() && start.elapsed() < patience {
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    pub fn clients(&self) -> usize {
+        self.frames
+            .as_ref()
+            .map_or(0, |frames| frames.receiver_count())
+    }
+
+    pub fn publish(
+        &self,
+        format: Format,
+        host: &str,
+        address: &str,
+        group: usize,
+        sample: &Sample,
+    ) {
+        let Some(frames) = &self.frames else {
+            return;
+        };
+
+        // Nobody is listening, so don't bother formatting.
+        if frames.receiver_count() > 0 {
+            let _ = frames.send(format.frame(host, address, group, sample));
+        }
+    }
+}
+
+pub fn json(host: &str, address: &str, group: usize, sample: &Sample) -> Value {
+    let rtt = rtt(sample);
+
+    json!({
+        "host": host,
+        "address": address,
+        "group": group + 1,
+        "time": sample.time.to_rfc3339(),
+        "check": sample.check,
+        "success": rtt.is_some(),
+        "rtt_ms": rtt,
+        "seq": sample.reply.seq,
+        "target": sample.reply.target.map(|target| target.to_string()),
+        "ttl": sample.reply.ttl,
+        "size": sample.reply.size,
+        "offset_ms": offset(sample),
+        "setup_ms": setup(sample),
+        "players": sample.reply.players.map(|players| players.online),
+        "players_max": sample.reply.players.map(|players| players.max),
+    })
+}
+
+async fn serve(
+    bind: &str,
+    frames: broadcast::Sender<String>,
+    mut stopped: oneshot::Receiver<()>,
+) -> std::io::Result<()> {
+    let listener = TcpListener::bind(bind).await?;
+
+    loop {
+        tokio::select! {
+            _ = &mut stopped => return Ok(()),
+            accepted = listener.accept() => {
+                let Ok((stream, _)) = accepted else {
+                    continue;
+                };
+
+                tokio::spawn(handle(stream, frames.subscribe()));
+            }
+        }
+    }
+}
+
+async fn handle(
+    stream: TcpStream,
+    mut frames: broadcast::Receiver<String>,
+) -> Result<(), tungstenite::Error> {
+    let mut socket = tokio_tungstenite::accept_async(stream).await?;
+
+    loop {
+        match frames.recv().await {
+            Ok(frame) => socket.send(Message::Text(frame)).await?,
+            Err(RecvError::Lagged(_)) => continue,
+            Err(RecvError::Closed) => return socket.close(None).await,
+        }
+    }
+}
+
+fn rtt(sample: &Sample) -> Option<f64> {
+    match sample.reply.pong {
+        Pong::Success(rtt) => Some(rtt.as_secs_f64() * 1000.),
+        Pong::Failure => None,
+    }
+}
+
+fn offset(sample: &Sample) -> Option<f64> {
+    let offset = sample.reply.offset?;
+    Some(offset.num_microseconds()? as f64 / 1000.)
+}
+
+fn setup(sample: &Sample) -> Option<f64> {
+    Some(sample.reply.setup?.as_secs_f64() * 1000.)
+}
+
+fn escape(tag: &str) -> String {
+    tag.chars().fold(String::new(), |mut escaped, c| {
+        if matches!(c, '\\' | ',' | '=' | ' ') {
+            escaped.push('\\');
+        }
+
+        escaped.push(c);
+        escaped
+    })
+}
@@ -0,0 +1,97 @@
+use std::{
+    env,
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant},
+};
+
+static STOP: AtomicBool = AtomicBool::new(false);
+
+pub struct Service {
+    watchdog: Option<Duration>,
+    petted: Instant,
+}
+
+impl Service {
+    pub fn new() -> Self {
+        trap();
+
+        // Pet at half the deadline, as sd_watchdog_enabled(3) recommends.
+        let watchdog = env::var("WATCHDOG_USEC")
+            .ok()
+            .and_then(|usec| usec.parse().ok())
+            .map(|usec| Duration::from_micros(usec) / 2);
+
+        Self {
+            watchdog,
+            petted: Instant::now(),
+        }
+    }
+
+    pub fn ready(&self) {
+        notify("READY=1");
+    }
+
+    pub fn stopping(&self) -> bool {
+        STOP.load(Ordering::Relaxed)
+    }
+
+    pub fn stop(&self) {
+        notify("STOPPING=1");
+    }
+
+    pub fn pet(&mut self) -> Duration {
+        let Some(watchdog) = self.watchdog else {
+            return Duration::MAX;
+        };
+
+        if self.petted.elapsed() >= watchdog {
+            notify("WATCHDOG=1");
+            self.petted = Instant::now();
+        }
+
+        watchdog.saturating_sub(self.petted.elapsed())
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn notify(state: &str) {
+    use std::os::{
+        linux::net::SocketAddrExt,
+        unix::net::{SocketAddr, UnixDatagram},
+    };
+
+    let Some(path) = env::var_os("NOTIFY_SOCKET") else {
+        return;
+    };
+
+    let path = path.to_string_lossy();
+
+    // A leading @ stands for the abstract namespace.
+    let addr = match path.strip_prefix('@') {
+        Some(name) => SocketAddr::from_abstract_name(name),
+        None => SocketAddr::from_pathname(&*path),
+    };
+
+    if let (Ok(socket), Ok(addr)) = (UnixDatagram::unbound(), addr) {
+        let _ = socket.send_to_addr(state.as_bytes(), &addr);
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn notify(_state: &str) {}
+
+#[cfg(unix)]
+fn trap() {
+    extern "C" fn stop(_: libc::c_int) {
+        STOP.store(true, Ordering::Relaxed);
+    }
+
+    for signal in [libc::SIGTERM, libc::SIGINT] {
+        unsafe {
+            libc::signal(signal, stop as *const () as libc::sighandler_t);
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn trap() {}
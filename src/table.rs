@@ -0,0 +1,590 @@
+use std::{collections::BTreeSet, time::Duration};
+
+use chrono::{DateTime, Local, NaiveTime, TimeDelta, Utc};
+use egui::{Align, DragValue, Event, Label, RichText, Sense, TextEdit, Ui};
+use egui_extras::{Column, TableBuilder};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::{game, ntp, store::Store, units::Units, Pong, Sample};
+
+// How far back each read from the store goes when scrolling past the first row.
+const PAGE: TimeDelta = TimeDelta::hours(1);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Field {
+    Time,
+    Check,
+    Status,
+    Seq,
+    Target,
+    Ttl,
+    Size,
+    Rtt,
+    Jitter,
+    Offset,
+    Players,
+    Setup,
+}
+
+impl Field {
+    pub const ALL: [Field; 12] = [
+        Field::Time,
+        Field::Check,
+        Field::Status,
+        Field::Seq,
+        Field::Target,
+        Field::Ttl,
+        Field::Size,
+        Field::Rtt,
+        Field::Jitter,
+        Field::Offset,
+        Field::Players,
+        Field::Setup,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Field::Time => "Hora",
+            Field::Check => "Tipo",
+            Field::Status => "Estado",
+            Field::Seq => "Seq",
+            Field::Target => "IP",
+            Field::Ttl => "TTL",
+            Field::Size => "Bytes",
+            Field::Rtt => "Respuesta",
+            Field::Jitter => "Jitter",
+            Field::Offset => "Desfase",
+            Field::Players => "Jugadores",
+            Field::Setup => "Conexión",
+        }
+    }
+
+    fn cell(self, sample: &Sample, units: Units) -> String {
+        let reply = sample.reply;
+        let optional = |value: Option<String>| value.unwrap_or_else(|| "—".into());
+
+        match self {
+            Field::Time => sample
+                .time
+                .with_timezone(&Local)
+                .format("%H:%M:%S")
+                .to_string(),
+            Field::Check => sample.check.to_owned(),
+            Field::Status => match reply.pong {
+                Pong::Success(_) => "OK".into(),
+                Pong::Failure => "Fallo".into(),
+            },
+            Field::Seq => reply.seq.to_string(),
+            Field::Target => optional(reply.target.map(|ip| ip.to_string())),
+            Field::Ttl => optional(reply.ttl.map(|ttl| ttl.to_string())),
+            Field::Size => optional((reply.size > 0).then(|| reply.size.to_string())),
+            Field::Rtt => match reply.pong {
+                Pong::Failure => String::from("Inalcanzable"),
+                Pong::Success(duration) => units.format(duration),
+            },
+            Field::Jitter => optional(sample.jitter.map(|jitter| units.format(jitter))),
+            Field::Offset => optional(reply.offset.map(|offset| ntp::format(offset, units))),
+            Field::Players => optional(reply.players.map(game::format)),
+            Field::Setup => optional(reply.setup.map(|setup| units.format(setup))),
+        }
+    }
+}
+
+pub fn default_columns() -> Vec<Field> {
+    vec![
+        Field::Time,
+        Field::Check,
+        Field::Seq,
+        Field::Target,
+        Field::Ttl,
+        Field::Size,
+        Field::Rtt,
+    ]
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Criteria {
+    failures: bool,
+    threshold: Option<Duration>,
+    from: String,
+    until: String,
+}
+
+#[derive(Clone, Debug, Default)]
+struct Cache {
+    criteria: Criteria,
+    span: Option<[DateTime<Utc>; 2]>,
+    rows: Vec<Sample>,
+}
+
+// Rows from before the history in memory, read back from the store a page at a time. They run
+// up to where the history started when last read, later gaps are filled as it is trimmed.
+#[derive(Clone, Debug, Default)]
+struct Older {
+    key: String,
+    span: Option<[DateTime<Utc>; 2]>,
+    samples: Vec<Sample>,
+    criteria: Criteria,
+    rows: Vec<Sample>,
+    exhausted: bool,
+    error: Option<String>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct State {
+    criteria: Criteria,
+    jump: String,
+    scroll: Option<NaiveTime>,
+    cache: Cache,
+    older: Older,
+    // Row to keep at the top after a page lands above it.
+    hold: Option<usize>,
+    shown: bool,
+    selected: BTreeSet<Key>,
+    anchor: Option<Key>,
+}
+
+// The older pages followed by the history in memory, without copying either.
+#[derive(Clone, Copy)]
+struct Rows<'a>([&'a [Sample]; 2]);
+
+type Key = (DateTime<Utc>, u16);
+
+#[derive(Clone, Copy)]
+enum Format {
+    Tsv,
+    Markdown,
+}
+
+impl Criteria {
+    fn active(&self) -> bool {
+        *self != Criteria::default()
+    }
+
+    fn matches(&self, sample: &Sample, from: Option<NaiveTime>, until: Option<NaiveTime>) -> bool {
+        if self.failures && matches!(sample.reply.pong, Pong::Success(_)) {
+            return false;
+        }
+
+        if let (Some(threshold), Pong::Success(rtt)) = (self.threshold, sample.reply.pong) {
+            if rtt <= threshold {
+                return false;
+            }
+        }
+
+        if from.is_none() && until.is_none() {
+            return true;
+        }
+
+        let time = sample.time.with_timezone(&Local).time();
+
+        match (from, until) {
+            (Some(from), Some(until)) if from > until => time >= from || time <= until,
+            (from, until) => {
+                from.is_none_or(|from| time >= from) && until.is_none_or(|until| time <= until)
+            }
+        }
+    }
+}
+
+impl Rows<'_> {
+    fn len(&self) -> usize {
+        self.0[0].len() + self.0[1].len()
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &Sample> {
+        self.0[0].iter().chain(self.0[1])
+    }
+
+    fn get(&self, idx: usize) -> &Sample {
+        match idx.checked_sub(self.0[0].len()) {
+            Some(idx) => &self.0[1][idx],
+            None => &self.0[0][idx],
+        }
+    }
+}
+
+impl Older {
+    fn sync(&mut self, store: &mut Store, key: &str, criteria: &Criteria, first: DateTime<Utc>) {
+        if self.key != key {
+            *self = Older {
+                key: key.to_owned(),
+                ..Default::default()
+            };
+        }
+
+        let behind = self
+            .span
+            .filter(|&[_, to]| to < first && self.error.is_none());
+
+        if let Some([from, to]) = behind {
+            match store.range(key, to, first) {
+                Ok(samples) => {
+                    self.samples.extend(samples);
+                    self.span = Some([from, first]);
+                    self.filter(criteria);
+                }
+                Err(err) => self.error = Some(err),
+            }
+        }
+
+        if self.criteria != *criteria {
+            self.filter(criteria);
+        }
+    }
+
+    // Goes back page by page over stretches with nothing stored, up to the oldest sample. Returns
+    // how many rows landed above the ones already shown.
+    fn page(&mut self, store: &mut Store, criteria: &Criteria, first: DateTime<Utc>) -> usize {
+        if self.exhausted || self.error.is_some() {
+            return 0;
+        }
+
+        let since = match store.since(&self.key) {
+            Ok(since) => since,
+            Err(err) => {
+                self.error = Some(err);
+                return 0;
+            }
+        };
+
+        let [mut from, to] = self.span.unwrap_or([first, first]);
+        let mut page = vec![];
+
+        while page.is_empty() {
+            if since.is_none_or(|since| since >= from) {
+                self.exhausted = true;
+                break;
+            }
+
+            let end = from;
+            from = end - PAGE;
+
+            match store.range(&self.key, from, end) {
+                Ok(samples) => page = samples,
+                Err(err) => {
+                    self.error = Some(err);
+                    return 0;
+                }
+            }
+        }
+
+        let shown = self.rows.len();
+        page.append(&mut self.samples);
+        self.samples = page;
+        self.span = Some([from, to]);
+        self.filter(criteria);
+        self.rows.len() - shown
+    }
+
+    fn filter(&mut self, criteria: &Criteria) {
+        let [from, until] = [&criteria.from, &criteria.until].map(|text| clock(text));
+
+        self.criteria = criteria.clone();
+        self.rows = self
+            .samples
+            .iter()
+            .filter(|sample| criteria.matches(sample, from, until))
+            .copied()
+            .collect();
+    }
+}
+
+impl Cache {
+    // Samples are only ever appended and trimmed from the front, so keep the matches found so
+    // far and only scan what is new since the last frame.
+    fn rows<'a>(&'a mut self, criteria: &Criteria, history: &'a [Sample]) -> &'a [Sample] {
+        if !criteria.active() {
+            return history;
+        }
+
+        let span = match (history.first(), history.last()) {
+            (Some(first), Some(last)) => [first.time, last.time],
+            _ => {
+                self.span = None;
+                self.rows.clear();
+                return &self.rows;
+            }
+        };
+
+        let stale = self
+            .span
+            .is_some_and(|[first, last]| span[0] < first || span[1] < last);
+
+        if self.criteria != *criteria || stale {
+            *self = Cache {
+                criteria: criteria.clone(),
+                ..Default::default()
+            };
+        }
+
+        let trimmed = self.rows.partition_point(|sample| sample.time < span[0]);
+        self.rows.drain(..trimmed);
+
+        let scanned = match self.span {
+            Some([_, last]) => history.partition_point(|sample| sample.time <= last),
+            None => 0,
+        };
+
+        let [from, until] = [&self.criteria.from, &self.criteria.until].map(|text| clock(text));
+
+        for sample in &history[scanned..] {
+            if self.criteria.matches(sample, from, until) {
+                self.rows.push(*sample);
+            }
+        }
+
+        self.span = Some(span);
+        &self.rows
+    }
+}
+
+// With a store and its key, scrolling past the first row reads older rows back from it.
+pub fn history(
+    ui: &mut Ui,
+    history: &[Sample],
+    state: &mut State,
+    columns: &mut Vec<Field>,
+    mut store: Option<(&mut Store, &str)>,
+    height: f32,
+    units: Units,
+) {
+    if columns.is_empty() {
+        columns.push(Field::Time);
+    }
+
+    ui.horizontal_wrapped(|ui| {
+        let criteria = &mut state.criteria;
+
+        ui.checkbox(&mut criteria.failures, "Solo fallos");
+
+        let mut slow = criteria.threshold.is_some();
+
+        if ui.checkbox(&mut slow, "RTT >").changed() {
+            criteria.threshold = slow.then_some(Duration::from_millis(100));
+        }
+
+        if let Some(threshold) = &mut criteria.threshold {
+            let mut millis = threshold.as_millis() as u64;
+
+            if ui.add(DragValue::new(&mut millis).suffix(" ms")).changed() {
+                *threshold = Duration::from_millis(millis);
+            }
+        }
+
+        ui.label("Desde");
+        ui.add(time_edit(&mut criteria.from));
+        ui.label("Hasta");
+        ui.add(time_edit(&mut criteria.until));
+        ui.label("Ir a");
+
+        let jump = ui.add(time_edit(&mut state.jump));
+
+        if jump.lost_focus() || jump.changed() {
+            state.scroll = clock(&state.jump);
+        }
+    });
+
+    let first = history.first().map(|sample| sample.time);
+
+    // Without a store the pages read so far stay for when the table gets one again.
+    let older = match (&mut store, first) {
+        (Some((store, key)), Some(first)) => {
+            state.older.sync(store, key, &state.criteria, first);
+            &state.older.rows[..]
+        }
+        _ => &[],
+    };
+
+    let scroll = state.scroll.take();
+    let hold = state.hold.take();
+    let rows = Rows([older, state.cache.rows(&state.criteria, history)]);
+    let selected = &mut state.selected;
+    let anchor = &mut state.anchor;
+    let mut copy = None;
+    let mut chosen = columns.clone();
+    let mut top = None;
+
+    if let Some(error) = &state.older.error {
+        ui.weak(error);
+    }
+
+    let mut table = TableBuilder::new(ui)
+        .striped(true)
+        .stick_to_bottom(scroll.is_none() && hold.is_none())
+        .max_scroll_height(height)
+        .columns(Column::auto(), columns.len() - 1)
+        .column(Column::remainder())
+        .resizable(true);
+
+    if let Some(target) = scroll {
+        let row = rows
+            .iter()
+            .position(|sample| sample.time.with_timezone(&Local).time() >= target)
+            .unwrap_or(rows.len().saturating_sub(1));
+
+        table = table.scroll_to_row(row, Some(Align::TOP));
+    } else if let Some(row) = hold {
+        table = table.scroll_to_row(row, Some(Align::TOP));
+    }
+
+    table
+        .header(18., |mut header| {
+            for &field in columns.iter() {
+                header.col(|ui| {
+                    let title = Label::new(RichText::new(field.label()).strong());
+
+                    ui.add(title.sense(Sense::click()))
+                        .context_menu(|ui| chooser(ui, &mut chosen));
+                });
+            }
+        })
+        .body(|body| {
+            body.rows(18., rows.len(), |idx, mut row| {
+                top = top.min(Some(idx)).or(Some(idx));
+
+                let id = key(rows.get(idx));
+                let mut cells = cells(rows.get(idx), columns, units).into_iter();
+                let is_selected = selected.contains(&id);
+
+                row.col(|ui| {
+                    let time = cells.next().unwrap_or_default();
+                    let response = ui.selectable_label(is_selected, time);
+                    let modifiers = ui.input(|input| input.modifiers);
+
+                    if response.clicked() && modifiers.shift {
+                        let from = anchor
+                            .and_then(|anchor| rows.iter().position(|sample| key(sample) == anchor))
+                            .unwrap_or(idx);
+
+                        let [lo, hi] = [from.min(idx), from.max(idx)];
+                        selected.extend(rows.iter().skip(lo).take(hi - lo + 1).map(key));
+                    } else if response.clicked() && modifiers.command {
+                        match is_selected {
+                            true => selected.remove(&id),
+                            false => selected.insert(id),
+                        };
+
+                        *anchor = Some(id);
+                    } else if response.clicked() || response.secondary_clicked() && !is_selected {
+                        selected.clear();
+                        selected.insert(id);
+                        *anchor = Some(id);
+                    }
+
+                    response.context_menu(|ui| {
+                        if ui.button("Copiar").clicked() {
+                            copy = Some(Format::Tsv);
+                            ui.close_menu();
+                        }
+
+                        if ui.button("Copiar como tabla Markdown").clicked() {
+                            copy = Some(Format::Markdown);
+                            ui.close_menu();
+                        }
+                    });
+                });
+
+                for cell in cells {
+                    row.col(|ui| {
+                        ui.add(Label::new(cell).wrap(false));
+                    });
+                }
+            })
+        });
+
+    let shortcut = ui.input(|input| input.events.contains(&Event::Copy));
+
+    if shortcut && ui.memory(|memory| memory.focus().is_none()) && !state.selected.is_empty() {
+        copy = copy.or(Some(Format::Tsv));
+    }
+
+    if let Some(format) = copy {
+        let rows = rows
+            .iter()
+            .filter(|sample| state.selected.contains(&key(sample)))
+            .map(|sample| cells(sample, columns, units))
+            .collect_vec();
+
+        ui.ctx().copy_text(export(format, columns, &rows));
+    }
+
+    // The first frame starts at the top before sticking to the bottom, that is no scroll yet.
+    if let (Some((store, _)), Some(first), Some(0), true) = (store, first, top, state.shown) {
+        let landed = state.older.page(store, &state.criteria, first);
+
+        if landed > 0 {
+            state.hold = Some(landed);
+            ui.ctx().request_repaint();
+        }
+    }
+
+    state.shown = true;
+    *columns = chosen;
+}
+
+fn chooser(ui: &mut Ui, columns: &mut Vec<Field>) {
+    for field in Field::ALL {
+        let mut shown = columns.contains(&field);
+
+        if ui.checkbox(&mut shown, field.label()).changed() {
+            columns.retain(|&other| other != field);
+
+            if shown {
+                columns.push(field);
+                columns.sort_by_key(|field| Field::ALL.iter().position(|other| other == field));
+            }
+        }
+    }
+}
+
+fn cells(sample: &Sample, columns: &[Field], units: Units) -> Vec<String> {
+    columns
+        .iter()
+        .map(|field| field.cell(sample, units))
+        .collect()
+}
+
+fn export(format: Format, columns: &[Field], rows: &[Vec<String>]) -> String {
+    let headers = columns
+        .iter()
+        .map(|field| field.label().to_owned())
+        .collect_vec();
+
+    match format {
+        Format::Tsv => {
+            let lines = rows.iter().map(|cells| cells.join("\t"));
+            [headers.join("\t")].into_iter().chain(lines).join("\n")
+        }
+        Format::Markdown => {
+            let line = |cells: &[String]| {
+                let cells = cells.iter().map(|cell| cell.replace('|', "\\|"));
+                format!("| {} |", cells.format(" | "))
+            };
+
+            let rule = format!("|{}", "---|".repeat(columns.len()));
+            let lines = rows.iter().map(|cells| line(cells));
+
+            [line(&headers), rule].into_iter().chain(lines).join("\n")
+        }
+    }
+}
+
+fn key(sample: &Sample) -> Key {
+    (sample.time, sample.reply.seq)
+}
+
+fn time_edit(text: &mut String) -> TextEdit<'_> {
+    TextEdit::singleline(text)
+        .desired_width(56.)
+        .hint_text("hh:mm")
+}
+
+fn clock(text: &str) -> Option<NaiveTime> {
+    let text = text.trim();
+
+    NaiveTime::parse_from_str(text, "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(text, "%H:%M"))
+        .ok()
+}
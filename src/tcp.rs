@@ -0,0 +1,58 @@
+use std::{
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
+
+use egui::{DragValue, Id, Ui};
+use tokio::net::TcpStream;
+
+use crate::{
+    check::{self, Kind, Target},
+    Pong, Reply,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Tcp {
+    pub port: u16,
+}
+
+impl Tcp {
+    pub const DEFAULT: Tcp = Tcp { port: 443 };
+}
+
+impl Kind for Tcp {
+    fn label(&self) -> &'static str {
+        "TCP"
+    }
+
+    fn ping(&self, target: &Target) -> Vec<Reply> {
+        ping(target.address, self.port, target.probes, target.timeout)
+    }
+
+    fn editor(&mut self, ui: &mut Ui, _id: Id, _address: &str, _bind: Option<&str>) {
+        ui.horizontal(|ui| {
+            ui.label("Puerto");
+            ui.add(DragValue::new(&mut self.port).clamp_range(1..=u16::MAX));
+        });
+    }
+}
+
+// The round trip is the time to complete the handshake, a refused connection counts as lost.
+pub fn ping(addr: &str, port: u16, probes: usize, timeout: Duration) -> Vec<Reply> {
+    let Some(ip) = check::resolve(addr) else {
+        return Reply::failures(None, probes);
+    };
+
+    let target = SocketAddr::new(ip, port);
+
+    check::fan_out(ip, probes, timeout, |_| async move {
+        let duration = probe(target).await?;
+        Some(Reply::new(Pong::Success(duration)))
+    })
+}
+
+async fn probe(target: SocketAddr) -> Option<Duration> {
+    let start = Instant::now();
+    let _stream = TcpStream::connect(target).await.ok()?;
+    Some(start.elapsed())
+}
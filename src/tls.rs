@@ -0,0 +1,235 @@
+use std::{
+    net::{SocketAddr, TcpStream},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Local, Utc};
+use egui::{Color32, Context, Ui};
+use rustls::{
+    client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+    crypto::{self, CryptoProvider},
+    pki_types::{CertificateDer, ServerName, UnixTime},
+    ClientConfig, ClientConnection, DigitallySignedStruct, RootCertStore, SignatureScheme,
+};
+use serde::{Deserialize, Serialize};
+use tokio_rustls::TlsConnector;
+
+use crate::{clock::Clock, dns, worker, FAIL, WARN};
+
+const RECHECK: Duration = Duration::from_secs(3600);
+const WARN_DAYS: i64 = 30;
+const FAIL_DAYS: i64 = 7;
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct Certificate {
+    enabled: bool,
+
+    #[serde(skip)]
+    job: Option<worker::Job<Result<DateTime<Utc>, String>>>,
+
+    #[serde(skip)]
+    checked: Option<Instant>,
+
+    #[serde(skip)]
+    endpoint: Option<(String, u16)>,
+
+    #[serde(skip)]
+    expiry: Option<Result<DateTime<Utc>, String>>,
+}
+
+// Certificates are accepted whatever their state, an expired one is exactly what this is for.
+#[derive(Debug)]
+struct Anything(Arc<CryptoProvider>);
+
+impl Certificate {
+    // Nothing is watched for a check that does not talk TLS, it has no certificate to look at.
+    pub fn poll(
+        &mut self,
+        ctx: &Context,
+        endpoint: Option<(String, u16)>,
+        timeout: Duration,
+        clock: &Clock,
+    ) {
+        let Some((address, port)) = endpoint.filter(|_| self.enabled) else {
+            self.checked = None;
+            self.expiry = None;
+            self.endpoint = None;
+            return;
+        };
+
+        if let Some(done) = self.job.as_ref().and_then(worker::Job::done) {
+            self.job = None;
+            self.expiry = done;
+        }
+
+        // A different endpoint has a different certificate, the old expiry says nothing about it.
+        if self.endpoint.as_ref() != Some(&(address.clone(), port)) {
+            self.checked = None;
+            self.expiry = None;
+            self.endpoint = Some((address.clone(), port));
+        }
+
+        let due = self
+            .checked
+            .is_none_or(|checked| clock.since(checked) >= RECHECK);
+
+        if due && self.job.is_none() {
+            self.checked = Some(clock.now());
+            self.job = Some(worker::spawn(ctx, move || expiry(&address, port, timeout)));
+        }
+    }
+
+    // Only set while expiry is close enough to be worth repainting the frame for.
+    pub fn color(&self, now: DateTime<Utc>) -> Option<Color32> {
+        let Some(Ok(expiry)) = self.expiry else {
+            return None;
+        };
+
+        match (expiry - now).num_days() {
+            days if days < FAIL_DAYS => Some(FAIL),
+            days if days < WARN_DAYS => Some(WARN),
+            _ => None,
+        }
+    }
+}
+
+pub fn editor(ui: &mut Ui, certificate: &mut Certificate, secure: bool) {
+    ui.add_enabled_ui(secure, |ui| {
+        ui.checkbox(&mut certificate.enabled, "Vigilar el certificado TLS")
+            .on_disabled_hover_text("El tipo de sondeo no usa TLS");
+    });
+}
+
+// Host and port of a URL, as long as it has the given scheme.
+pub fn endpoint(url: &str, scheme: &str) -> Option<(String, u16)> {
+    let uri = url.parse::<http::Uri>().ok()?;
+
+    if uri.scheme_str() != Some(scheme) {
+        return None;
+    }
+
+    let host = uri.host()?.trim_matches(['[', ']']).to_owned();
+    Some((host, uri.port_u16().unwrap_or(443)))
+}
+
+pub fn show(ui: &mut Ui, certificate: &Certificate, now: DateTime<Utc>) {
+    let text = match &certificate.expiry {
+        None => return,
+        Some(Err(error)) => {
+            ui.colored_label(FAIL, format!("🔒 {}", error));
+            return;
+        }
+        Some(Ok(expiry)) => {
+            let date = expiry.with_timezone(&Local).format("%Y-%m-%d");
+
+            match (*expiry - now).num_days() {
+                days if days < 0 => format!("🔒 Caducó el {}", date),
+                0 => format!("🔒 Caduca hoy ({})", date),
+                1 => format!("🔒 Caduca mañana ({})", date),
+                days => format!("🔒 Caduca en {} días ({})", days, date),
+            }
+        }
+    };
+
+    match certificate.color(now) {
+        Some(color) => ui.colored_label(color, text),
+        None => ui.label(text),
+    };
+}
+
+// Unlike the expiry check, checks riding on TLS want a certificate that actually verifies.
+pub fn connector(protocols: &[&[u8]]) -> Option<TlsConnector> {
+    let roots = RootCertStore {
+        roots: webpki_roots::TLS_SERVER_ROOTS.into(),
+    };
+
+    let mut config =
+        ClientConfig::builder_with_provider(Arc::new(crypto::ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .ok()?
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+
+    config.alpn_protocols = protocols.iter().map(|protocol| protocol.to_vec()).collect();
+    Some(TlsConnector::from(Arc::new(config)))
+}
+
+fn expiry(address: &str, port: u16, timeout: Duration) -> Result<DateTime<Utc>, String> {
+    let ip = dns::lookup(address)
+        .ok()
+        .and_then(|lookup| lookup.first().copied())
+        .ok_or("No se pudo resolver la dirección")?;
+
+    let name = ServerName::try_from(dns::ascii(address)).map_err(|err| err.to_string())?;
+
+    let provider = Arc::new(crypto::ring::default_provider());
+    let config = ClientConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .map_err(|err| err.to_string())?
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(Anything(provider)))
+        .with_no_client_auth();
+
+    let mut stream = TcpStream::connect_timeout(&SocketAddr::new(ip, port), timeout)
+        .map_err(|err| err.to_string())?;
+    stream
+        .set_read_timeout(Some(timeout))
+        .map_err(|err| err.to_string())?;
+
+    let mut connection =
+        ClientConnection::new(Arc::new(config), name).map_err(|err| err.to_string())?;
+
+    while connection.is_handshaking() {
+        connection
+            .complete_io(&mut stream)
+            .map_err(|err| err.to_string())?;
+    }
+
+    let leaf = connection
+        .peer_certificates()
+        .and_then(|certificates| certificates.first())
+        .ok_or("El servidor no presentó certificado")?;
+
+    let (_, parsed) = x509_parser::parse_x509_certificate(leaf).map_err(|err| err.to_string())?;
+    let timestamp = parsed.validity().not_after.timestamp();
+
+    DateTime::from_timestamp(timestamp, 0).ok_or("Fecha de caducidad no válida".into())
+}
+
+impl ServerCertVerifier for Anything {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer,
+        _intermediates: &[CertificateDer],
+        _server_name: &ServerName,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        let algorithms = &self.0.signature_verification_algorithms;
+        crypto::verify_tls12_signature(message, cert, dss, algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        let algorithms = &self.0.signature_verification_algorithms;
+        crypto::verify_tls13_signature(message, cert, dss, algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
@@ -0,0 +1,273 @@
+use std::{
+    net::{IpAddr, SocketAddr, UdpSocket},
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Local};
+use egui::{Context, Grid, Id, Spinner, Window};
+use socket2::{Domain, Protocol, SockRef, Socket, Type};
+
+use crate::{dns, units::Units, worker, PingWindow, FAIL};
+
+const MAX_HOPS: u8 = 30;
+const PROBES: u8 = 3;
+
+const ECHO_V4: u8 = 8;
+const REPLY_V4: u8 = 0;
+const ERRORS_V4: [u8; 2] = [3, 11];
+const ECHO_V6: u8 = 128;
+const REPLY_V6: u8 = 129;
+const ERRORS_V6: [u8; 2] = [1, 3];
+
+pub struct Hop {
+    ttl: u8,
+    from: Option<IpAddr>,
+    rtts: [Option<Duration>; PROBES as usize],
+}
+
+#[derive(Default)]
+pub struct Trace {
+    pub open: bool,
+    job: Option<worker::Job<Result<Vec<Hop>, String>>>,
+    hops: Option<Result<Vec<Hop>, String>>,
+    started: Option<DateTime<Local>>,
+    timeout: Duration,
+}
+
+impl Trace {
+    pub fn start(&mut self, ctx: &Context, address: &str, timeout: Duration) {
+        let address = address.to_owned();
+
+        self.open = true;
+        self.hops = None;
+        self.started = Some(Local::now());
+        self.timeout = timeout;
+        self.job = Some(worker::spawn(ctx, move || run(&address, timeout)));
+    }
+}
+
+pub fn show(ctx: &Context, win: &mut PingWindow, units: Units) {
+    let trace = &mut win.trace;
+
+    if let Some(done) = trace.job.as_ref().and_then(|job| job.done()) {
+        trace.hops = Some(done.unwrap_or_else(|| Err("La traza se interrumpió".into())));
+        trace.job = None;
+    }
+
+    if !trace.open {
+        return;
+    }
+
+    let title = [&win.hostname, &win.address][win.hostname.is_empty() as usize];
+    let mut again = false;
+
+    Window::new(format!("Ruta a {}", title))
+        .id(Id::new((win.ctime, "trace")))
+        .open(&mut trace.open)
+        .resizable(false)
+        .show(ctx, |ui| {
+            if let Some(started) = trace.started {
+                ui.weak(started.format("Iniciada a las %H:%M:%S").to_string());
+            }
+
+            match &trace.hops {
+                None => {
+                    ui.horizontal(|ui| {
+                        ui.add(Spinner::new());
+                        ui.label("Trazando…");
+                    });
+                }
+                Some(Err(error)) => {
+                    ui.colored_label(FAIL, error);
+                }
+                Some(Ok(hops)) => {
+                    Grid::new((win.ctime, "hops"))
+                        .striped(true)
+                        .num_columns(3 + PROBES as usize)
+                        .show(ui, |ui| {
+                            ui.strong("Salto");
+                            ui.strong("Dirección");
+
+                            for probe in 1..=PROBES {
+                                ui.strong(format!("RTT {}", probe));
+                            }
+
+                            ui.strong("Pérdida");
+                            ui.end_row();
+
+                            for hop in hops {
+                                let lost = hop.rtts.iter().filter(|rtt| rtt.is_none()).count();
+
+                                ui.monospace(hop.ttl.to_string());
+                                ui.monospace(hop.from.map_or("*".into(), |ip| ip.to_string()));
+
+                                for rtt in hop.rtts {
+                                    ui.monospace(rtt.map_or("*".into(), |rtt| units.format(rtt)));
+                                }
+
+                                ui.monospace(format!("{} %", lost * 100 / PROBES as usize));
+                                ui.end_row();
+                            }
+                        });
+                }
+            }
+
+            ui.add_enabled_ui(trace.job.is_none(), |ui| {
+                again = ui.button("Repetir").clicked();
+            });
+        });
+
+    if again {
+        let timeout = trace.timeout;
+        trace.start(ctx, &win.address, timeout);
+    }
+}
+
+// Every probe for every hop goes out at once, told apart by the sequence number echoed back in
+// Time Exceeded, so the whole trace takes about one timeout. Intermediate hops only answer raw
+// sockets.
+fn run(address: &str, timeout: Duration) -> Result<Vec<Hop>, String> {
+    let ip = dns::lookup(address)
+        .ok()
+        .and_then(|lookup| lookup.first().copied())
+        .ok_or("No se pudo resolver la dirección")?;
+
+    let (domain, protocol, echo) = match ip {
+        IpAddr::V4(_) => (Domain::IPV4, Protocol::ICMPV4, ECHO_V4),
+        IpAddr::V6(_) => (Domain::IPV6, Protocol::ICMPV6, ECHO_V6),
+    };
+
+    let socket = Socket::new(domain, Type::RAW, Some(protocol))
+        .map_err(|_| "Hace falta permiso para ICMP crudo (Sondeo → Permisos ICMP)")?;
+    let socket = UdpSocket::from(socket);
+    let target = SocketAddr::new(ip, 0);
+    let ident = rand::random::<u16>();
+
+    let mut sent = vec![];
+
+    for ttl in 1..=MAX_HOPS {
+        match ip {
+            IpAddr::V4(_) => socket.set_ttl(ttl as u32),
+            IpAddr::V6(_) => SockRef::from(&socket).set_unicast_hops_v6(ttl as u32),
+        }
+        .map_err(|err| err.to_string())?;
+
+        for probe in 0..PROBES {
+            let seq = ttl as u16 * PROBES as u16 + probe as u16;
+            let packet = echo_request(echo, ident, seq, ip.is_ipv4());
+
+            sent.push((seq, Instant::now()));
+            socket
+                .send_to(&packet, target)
+                .map_err(|err| err.to_string())?;
+        }
+    }
+
+    let mut hops = (1..=MAX_HOPS)
+        .map(|ttl| Hop {
+            ttl,
+            from: None,
+            rtts: [None; PROBES as usize],
+        })
+        .collect::<Vec<_>>();
+
+    let deadline = Instant::now() + timeout;
+    let mut buf = [0; 1500];
+
+    while let Some(left) = deadline.checked_duration_since(Instant::now()) {
+        if left.is_zero() || socket.set_read_timeout(Some(left)).is_err() {
+            break;
+        }
+
+        let Ok((len, from)) = socket.recv_from(&mut buf) else {
+            break;
+        };
+
+        let Some(seq) = matching(&buf[..len], ip.is_ipv4(), ident) else {
+            continue;
+        };
+
+        let Some(&(_, at)) = sent.iter().find(|(sent, _)| *sent == seq) else {
+            continue;
+        };
+
+        let (ttl, probe) = (seq / PROBES as u16, seq % PROBES as u16);
+        let Some(hop) = hops.get_mut(ttl as usize - 1) else {
+            continue;
+        };
+
+        hop.from = Some(from.ip());
+        hop.rtts[probe as usize] = Some(at.elapsed());
+    }
+
+    // Past the target every hop is the target again.
+    if let Some(reached) = hops.iter().position(|hop| hop.from == Some(ip)) {
+        hops.truncate(reached + 1);
+    }
+
+    while hops.len() > 1 && hops.last().is_some_and(|hop| hop.from.is_none()) {
+        hops.pop();
+    }
+
+    Ok(hops)
+}
+
+fn echo_request(kind: u8, ident: u16, seq: u16, v4: bool) -> Vec<u8> {
+    let mut packet = vec![kind, 0, 0, 0];
+    packet.extend(ident.to_be_bytes());
+    packet.extend(seq.to_be_bytes());
+    packet.extend(b"pinga-trace");
+
+    // The kernel fills in the ICMPv6 checksum, it covers a pseudo-header only it knows.
+    if v4 {
+        let checksum = checksum(&packet);
+        packet[2..4].copy_from_slice(&checksum.to_be_bytes());
+    }
+
+    packet
+}
+
+fn checksum(data: &[u8]) -> u16 {
+    let mut sum = data
+        .chunks(2)
+        .map(|pair| u16::from_be_bytes([pair[0], *pair.get(1).unwrap_or(&0)]) as u32)
+        .sum::<u32>();
+
+    while sum > 0xFFFF {
+        sum = (sum & 0xFFFF) + (sum >> 16);
+    }
+
+    !(sum as u16)
+}
+
+// The sequence number of our probe an ICMP message answers, be it the echo reply itself or an
+// error quoting the original request.
+fn matching(packet: &[u8], v4: bool, ident: u16) -> Option<u16> {
+    let icmp = match v4 {
+        true => packet.get((packet.first()? & 0x0F) as usize * 4..)?,
+        false => packet,
+    };
+
+    let (reply, errors) = match v4 {
+        true => (REPLY_V4, ERRORS_V4),
+        false => (REPLY_V6, ERRORS_V6),
+    };
+
+    let echo = match *icmp.first()? {
+        kind if kind == reply => icmp,
+        kind if errors.contains(&kind) => {
+            let inner = icmp.get(8..)?;
+
+            match v4 {
+                true => inner.get((inner.first()? & 0x0F) as usize * 4..)?,
+                false => inner.get(40..)?,
+            }
+        }
+        _ => return None,
+    };
+
+    let id = u16::from_be_bytes([*echo.get(4)?, *echo.get(5)?]);
+    let seq = u16::from_be_bytes([*echo.get(6)?, *echo.get(7)?]);
+
+    (id == ident).then_some(seq)
+}
@@ -0,0 +1,137 @@
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    time::{Duration, Instant},
+};
+
+use egui::{DragValue, Id, TextEdit, TextStyle, Ui, WidgetText};
+use tokio::net::UdpSocket;
+
+use crate::{
+    check::{self, Kind, Target},
+    Pong, Reply,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Udp {
+    pub port: u16,
+    pub payload: String,
+    pub expect: Option<String>,
+}
+
+impl Udp {
+    pub const DEFAULT: Udp = Udp {
+        port: 7,
+        payload: String::new(),
+        expect: None,
+    };
+}
+
+impl Kind for Udp {
+    fn label(&self) -> &'static str {
+        "UDP"
+    }
+
+    fn ping(&self, target: &Target) -> Vec<Reply> {
+        ping(
+            target.address,
+            self.port,
+            &self.payload,
+            self.expect.as_deref(),
+            target.probes,
+            target.timeout,
+        )
+    }
+
+    fn editor(&mut self, ui: &mut Ui, _id: Id, _address: &str, _bind: Option<&str>) {
+        ui.horizontal(|ui| {
+            ui.label("Puerto");
+            ui.add(DragValue::new(&mut self.port).clamp_range(1..=u16::MAX));
+        });
+
+        let payload_input = TextEdit::singleline(&mut self.payload)
+            .hint_text(WidgetText::italics("Carga".into()))
+            .font(TextStyle::Monospace);
+
+        ui.add(payload_input);
+
+        let mut matching = self.expect.is_some();
+
+        if ui
+            .checkbox(&mut matching, "Exigir respuesta concreta")
+            .changed()
+        {
+            self.expect = matching.then(String::new);
+        }
+
+        if let Some(expect) = &mut self.expect {
+            let expect_input = TextEdit::singleline(expect)
+                .hint_text(WidgetText::italics("Respuesta esperada".into()))
+                .font(TextStyle::Monospace);
+
+            ui.add(expect_input);
+        }
+    }
+}
+
+pub fn ping(
+    addr: &str,
+    port: u16,
+    payload: &str,
+    expect: Option<&str>,
+    probes: usize,
+    timeout: Duration,
+) -> Vec<Reply> {
+    let Some(ip) = check::resolve(addr) else {
+        return Reply::failures(None, probes);
+    };
+
+    let target = SocketAddr::new(ip, port);
+    let payload = payload.as_bytes().to_vec();
+    let expect = expect.map(|expect| expect.as_bytes().to_vec());
+
+    check::fan_out(ip, probes, timeout, |_| {
+        let payload = payload.clone();
+        let expect = expect.clone();
+
+        async move {
+            let (duration, size) = probe(target, &payload, expect.as_deref()).await?;
+
+            Some(Reply {
+                size,
+                ..Reply::new(Pong::Success(duration))
+            })
+        }
+    })
+}
+
+async fn probe(
+    target: SocketAddr,
+    payload: &[u8],
+    expect: Option<&[u8]>,
+) -> Option<(Duration, usize)> {
+    let local = match target.ip() {
+        IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+        IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+    };
+
+    let socket = UdpSocket::bind((local, 0)).await.ok()?;
+    socket.connect(target).await.ok()?;
+
+    let start = Instant::now();
+    socket.send(payload).await.ok()?;
+
+    let mut buf = [0; 65536];
+
+    loop {
+        let len = socket.recv(&mut buf).await.ok()?;
+        let reply = &buf[..len];
+
+        let matches = expect.is_none_or(|expect| {
+            expect.is_empty() || reply.windows(expect.len()).any(|window| window == expect)
+        });
+
+        if matches {
+            return Some((start.elapsed(), len));
+        }
+    }
+}
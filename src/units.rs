@@ -0,0 +1,73 @@
+use std::time::Duration;
+
+use egui::{ComboBox, DragValue, Ui};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Unit {
+    Micros,
+    #[default]
+    Millis,
+    Secs,
+}
+
+impl Unit {
+    pub const ALL: [Unit; 3] = [Unit::Micros, Unit::Millis, Unit::Secs];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Unit::Micros => "µs",
+            Unit::Millis => "ms",
+            Unit::Secs => "s",
+        }
+    }
+
+    fn per_sec(self) -> f64 {
+        match self {
+            Unit::Micros => 1e6,
+            Unit::Millis => 1e3,
+            Unit::Secs => 1.,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Units {
+    pub unit: Unit,
+    pub decimals: usize,
+}
+
+impl Default for Units {
+    fn default() -> Self {
+        Self {
+            unit: Unit::Millis,
+            decimals: 1,
+        }
+    }
+}
+
+impl Units {
+    pub fn format(&self, duration: Duration) -> String {
+        self.format_secs(duration.as_secs_f64())
+    }
+
+    pub fn format_secs(&self, secs: f64) -> String {
+        let value = secs * self.unit.per_sec();
+        format!("{:.*} {}", self.decimals, value, self.unit.label())
+    }
+}
+
+pub fn editor(ui: &mut Ui, units: &mut Units) {
+    ui.horizontal(|ui| {
+        ComboBox::from_id_source("unit")
+            .selected_text(units.unit.label())
+            .show_ui(ui, |ui| {
+                for unit in Unit::ALL {
+                    ui.selectable_value(&mut units.unit, unit, unit.label());
+                }
+            });
+
+        ui.label("Decimales");
+        ui.add(DragValue::new(&mut units.decimals).clamp_range(0..=3));
+    });
+}
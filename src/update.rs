@@ -0,0 +1,124 @@
+use egui::{Context, TopBottomPanel, Ui};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{worker, FAIL};
+
+const RELEASES: &str = "https://api.github.com/repos/jmi2k/pinga/releases/latest";
+
+// Only a single anonymous request per run, nothing about the user or their hosts is sent.
+#[derive(Default, Serialize, Deserialize)]
+pub struct Checker {
+    enabled: bool,
+
+    #[serde(skip)]
+    job: Option<worker::Job<Result<Option<Release>, String>>>,
+
+    #[serde(skip)]
+    checked: bool,
+
+    #[serde(skip)]
+    release: Option<Release>,
+
+    #[serde(skip)]
+    error: Option<String>,
+
+    #[serde(skip)]
+    dismissed: bool,
+}
+
+struct Release {
+    tag: String,
+    url: String,
+}
+
+impl Checker {
+    pub fn poll(&mut self, ctx: &Context) {
+        if self.enabled && !self.checked {
+            self.checked = true;
+            self.job = Some(worker::spawn(ctx, latest));
+        }
+
+        let Some(done) = self.job.as_ref().and_then(|job| job.done()) else {
+            return;
+        };
+
+        self.job = None;
+
+        match done {
+            Some(Ok(release)) => self.release = release,
+            Some(Err(err)) => self.error = Some(err),
+            None => {}
+        }
+    }
+}
+
+pub fn banner(ctx: &Context, checker: &mut Checker) {
+    let Some(release) = checker.release.as_ref().filter(|_| checker.enabled) else {
+        return;
+    };
+
+    if checker.dismissed {
+        return;
+    }
+
+    TopBottomPanel::top("update").show(ctx, |ui| {
+        ui.horizontal(|ui| {
+            ui.label(format!("Hay una versión nueva de PingA: {}", release.tag));
+            ui.hyperlink_to("Descargar", &release.url);
+
+            if ui.small_button("✖").on_hover_text("Descartar").clicked() {
+                checker.dismissed = true;
+            }
+        });
+    });
+}
+
+pub fn editor(ui: &mut Ui, checker: &mut Checker) {
+    if ui
+        .checkbox(&mut checker.enabled, "Buscar actualizaciones")
+        .on_hover_text("Consulta las versiones publicadas en GitHub al arrancar")
+        .changed()
+    {
+        checker.checked = false;
+        checker.error = None;
+    }
+
+    if let Some(error) = checker.error.as_ref().filter(|_| checker.enabled) {
+        ui.colored_label(FAIL, error);
+    }
+}
+
+fn latest() -> Result<Option<Release>, String> {
+    let response: Value = ureq::get(RELEASES)
+        .set("Accept", "application/vnd.github+json")
+        .set("User-Agent", concat!("pinga/", env!("CARGO_PKG_VERSION")))
+        .call()
+        .map_err(|err| err.to_string())?
+        .into_json()
+        .map_err(|err| err.to_string())?;
+
+    let tag = response["tag_name"]
+        .as_str()
+        .ok_or("Respuesta de GitHub sin versión")?;
+
+    let url = response["html_url"].as_str().unwrap_or_default();
+
+    let newer = version(tag) > version(env!("CARGO_PKG_VERSION"));
+
+    Ok(newer.then(|| Release {
+        tag: tag.into(),
+        url: url.into(),
+    }))
+}
+
+// Tags look like `v1.2.3`, anything after a dash (pre-releases) is ignored.
+fn version(text: &str) -> Vec<u64> {
+    text.trim_start_matches('v')
+        .split('-')
+        .next()
+        .unwrap_or_default()
+        .split('.')
+        .map(|part| part.parse().unwrap_or(0))
+        .collect()
+}
@@ -0,0 +1,96 @@
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+
+use crate::clock::Clock;
+
+// Wall time running ahead of the monotonic clock means the machine was asleep, on the platforms
+// where the latter stops with it. Elsewhere a long enough gap between frames gives it away.
+const SLEPT: Duration = Duration::from_secs(10);
+const STALLED: Duration = Duration::from_secs(120);
+
+pub struct Warmup {
+    since: Instant,
+    settling: bool,
+    wall: DateTime<Utc>,
+    tick: Instant,
+}
+
+impl Default for Warmup {
+    fn default() -> Self {
+        Self::new(&Clock::System)
+    }
+}
+
+impl Warmup {
+    pub fn new(clock: &Clock) -> Self {
+        Self {
+            since: clock.now(),
+            settling: true,
+            wall: clock.utc(),
+            tick: clock.now(),
+        }
+    }
+
+    // Whether alerts are being held back, and whether this is the frame where that stopped.
+    pub fn tick(&mut self, period: Duration, clock: &Clock) -> (bool, bool) {
+        let wall = (clock.utc() - self.wall).to_std().unwrap_or_default();
+        let elapsed = clock.since(self.tick);
+
+        if wall.saturating_sub(elapsed) > SLEPT || elapsed > STALLED {
+            self.since = clock.now();
+            self.settling = true;
+        }
+
+        self.wall = clock.utc();
+        self.tick = clock.now();
+
+        let settling = clock.since(self.since) < period;
+        let settled = self.settling && !settling;
+
+        self.settling = settling;
+        (settling, settled)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{Warmup, STALLED};
+    use crate::clock::Clock;
+
+    const GRACE: Duration = Duration::from_secs(30);
+
+    #[test]
+    fn settles_once_the_grace_period_is_over() {
+        let clock = Clock::manual();
+        let mut warmup = Warmup::new(&clock);
+
+        assert_eq!(warmup.tick(GRACE, &clock), (true, false));
+
+        clock.advance(GRACE - Duration::from_secs(1));
+        assert_eq!(warmup.tick(GRACE, &clock), (true, false));
+
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(warmup.tick(GRACE, &clock), (false, true));
+
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(warmup.tick(GRACE, &clock), (false, false));
+    }
+
+    #[test]
+    fn a_stalled_frame_starts_over() {
+        let clock = Clock::manual();
+        let mut warmup = Warmup::new(&clock);
+
+        clock.advance(GRACE);
+        assert_eq!(warmup.tick(GRACE, &clock), (false, true));
+
+        clock.advance(STALLED + Duration::from_secs(1));
+        assert_eq!(warmup.tick(GRACE, &clock), (true, false));
+
+        clock.advance(GRACE);
+        assert_eq!(warmup.tick(GRACE, &clock), (false, true));
+    }
+}
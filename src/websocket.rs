@@ -0,0 +1,157 @@
+use std::{
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
+
+use egui::{Checkbox, Id, TextEdit, TextStyle, Ui, WidgetText};
+use futures_util::{SinkExt, StreamExt};
+use rustls::pki_types::ServerName;
+use serde::{Deserialize, Serialize};
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    net::TcpStream,
+};
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::{
+    check::{self, Kind, Target},
+    dns, tls, Pong, Reply,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebSocket {
+    pub url: String,
+    pub ping: bool,
+}
+
+impl WebSocket {
+    pub const DEFAULT: WebSocket = WebSocket {
+        url: String::new(),
+        ping: false,
+    };
+}
+
+impl Kind for WebSocket {
+    fn label(&self) -> &'static str {
+        "WebSocket"
+    }
+
+    fn ping(&self, target: &Target) -> Vec<Reply> {
+        let url = match self.url.trim() {
+            "" => format!("ws://{}/", target.address),
+            url => url.to_owned(),
+        };
+
+        ping(&url, self.ping, target.probes, target.timeout)
+    }
+
+    fn tls(&self, _address: &str) -> Option<(String, u16)> {
+        tls::endpoint(self.url.trim(), "wss")
+    }
+
+    fn editor(&mut self, ui: &mut Ui, _id: Id, address: &str, _bind: Option<&str>) {
+        let hint = format!("URL (ws://{}/)", address);
+        let url_input = TextEdit::singleline(&mut self.url)
+            .hint_text(WidgetText::italics(hint.into()))
+            .font(TextStyle::Monospace);
+
+        ui.add(url_input);
+        ui.add(Checkbox::new(&mut self.ping, "Enviar ping tras conectar"));
+    }
+}
+
+// The round trip is the opening handshake. With `ping` set the socket must also answer one.
+pub fn ping(url: &str, ping: bool, probes: usize, timeout: Duration) -> Vec<Reply> {
+    let Ok(uri) = url.parse::<http::Uri>() else {
+        return Reply::failures(None, probes);
+    };
+
+    let secure = match uri.scheme_str() {
+        Some("ws") => false,
+        Some("wss") => true,
+        _ => return Reply::failures(None, probes),
+    };
+
+    let Some(host) = uri
+        .host()
+        .map(|host| host.trim_matches(['[', ']']).to_owned())
+    else {
+        return Reply::failures(None, probes);
+    };
+
+    let Some(ip) = check::resolve(&host) else {
+        return Reply::failures(None, probes);
+    };
+
+    let port = uri.port_u16().unwrap_or([80, 443][secure as usize]);
+    let target = SocketAddr::new(ip, port);
+
+    let name = secure
+        .then(|| ServerName::try_from(dns::ascii(&host)).ok())
+        .flatten();
+
+    let url = url.to_owned();
+
+    check::fan_out(ip, probes, timeout, |seq| {
+        let url = url.clone();
+        let name = name.clone();
+
+        async move {
+            let duration = probe(target, name, url, ping, seq).await?;
+            Some(Reply::new(Pong::Success(duration)))
+        }
+    })
+}
+
+async fn probe(
+    target: SocketAddr,
+    name: Option<ServerName<'static>>,
+    url: String,
+    ping: bool,
+    seq: usize,
+) -> Option<Duration> {
+    let start = Instant::now();
+    let stream = TcpStream::connect(target).await.ok()?;
+
+    match name {
+        None => handshake(stream, &url, ping, seq, start).await,
+        Some(name) => {
+            let stream = tls::connector(&[b"http/1.1"])?
+                .connect(name, stream)
+                .await
+                .ok()?;
+
+            handshake(stream, &url, ping, seq, start).await
+        }
+    }
+}
+
+async fn handshake<T>(
+    stream: T,
+    url: &str,
+    ping: bool,
+    seq: usize,
+    start: Instant,
+) -> Option<Duration>
+where
+    T: AsyncRead + AsyncWrite + Unpin,
+{
+    let (mut socket, _) = tokio_tungstenite::client_async(url, stream).await.ok()?;
+    let elapsed = start.elapsed();
+
+    if ping {
+        let payload = (seq as u32).to_be_bytes().to_vec();
+        socket.send(Message::Ping(payload.clone())).await.ok()?;
+
+        loop {
+            match socket.next().await?.ok()? {
+                Message::Pong(data) if data == payload => break,
+                Message::Close(_) => return None,
+                _ => {}
+            }
+        }
+    }
+
+    let _ = socket.close(None).await;
+    Some(elapsed)
+}
@@ -0,0 +1,295 @@
+use std::{
+    mem,
+    time::{Duration, Instant},
+};
+
+use egui::{
+    ComboBox, Context, DragValue, Id, Pos2, RichText, TextEdit, TextStyle, WidgetText, Window,
+};
+use egui_plot::{Line, Plot};
+use itertools::Itertools;
+use rand::random;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    clock::Clock, expr, plot, range::TimeRange, units::Units, Canvas, Pong, FAIL, GROUPS, PASS,
+};
+
+const SAMPLE_EVERY: Duration = Duration::from_secs(1);
+const MAX_POINTS: usize = 3600;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Metric {
+    Clock,
+    Down { group: Option<usize> },
+    Mean { group: usize },
+    P95 { group: usize },
+    Expr(String),
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Widget {
+    id: u64,
+    metric: Metric,
+    origin: Option<Pos2>,
+
+    #[serde(default)]
+    alert: Option<f64>,
+
+    #[serde(skip, default = "crate::default_true")]
+    open: bool,
+
+    #[serde(skip)]
+    points: Vec<[f64; 2]>,
+
+    #[serde(skip)]
+    sampled: Option<Instant>,
+
+    #[serde(skip)]
+    firing: bool,
+}
+
+impl Metric {
+    fn all() -> Vec<Metric> {
+        let groups = 0..GROUPS.len();
+
+        [Metric::Clock, Metric::Down { group: None }]
+            .into_iter()
+            .chain(
+                groups
+                    .clone()
+                    .map(|group| Metric::Down { group: Some(group) }),
+            )
+            .chain(groups.clone().map(|group| Metric::Mean { group }))
+            .chain(groups.map(|group| Metric::P95 { group }))
+            .chain([Metric::Expr(String::new())])
+            .collect()
+    }
+
+    fn label(&self) -> String {
+        match *self {
+            Metric::Clock => "Reloj".into(),
+            Metric::Down { group: None } => "Hosts caídos".into(),
+            Metric::Down { group: Some(group) } => format!("Hosts caídos del grupo {}", group + 1),
+            Metric::Mean { group } => format!("Media del grupo {}", group + 1),
+            Metric::P95 { group } => format!("p95 del grupo {}", group + 1),
+            Metric::Expr(ref source) if source.is_empty() => "Expresión".into(),
+            Metric::Expr(ref source) => source.clone(),
+        }
+    }
+
+    fn value(
+        &self,
+        canvases: &[Canvas],
+        range: TimeRange,
+        units: Units,
+        clock: &Clock,
+    ) -> RichText {
+        let windows = canvases
+            .iter()
+            .flat_map(|canvas| &canvas.windows)
+            .filter(|win| win.scanning);
+
+        let rtts = |group: usize| {
+            let now = clock.utc();
+
+            windows
+                .clone()
+                .filter(|win| win.group == group)
+                .flat_map(|win| win.range.unwrap_or(range).slice(&win.history, now))
+                .filter_map(|sample| match sample.reply.pong {
+                    Pong::Success(rtt) => Some(rtt),
+                    Pong::Failure => None,
+                })
+                .sorted()
+                .collect_vec()
+        };
+
+        match *self {
+            Metric::Clock => RichText::new(clock.local().format("%H:%M:%S").to_string()),
+            Metric::Down { group } => {
+                let down = windows
+                    .clone()
+                    .filter(|win| group.is_none_or(|group| win.group == group))
+                    .filter(|win| win.success == Some(false))
+                    .count();
+
+                RichText::new(down.to_string()).color([PASS, FAIL][(down > 0) as usize])
+            }
+            Metric::Mean { group } => match &rtts(group)[..] {
+                [] => RichText::new("—"),
+                rtts => {
+                    RichText::new(units.format(rtts.iter().sum::<Duration>() / rtts.len() as u32))
+                }
+            },
+            Metric::P95 { group } => match &rtts(group)[..] {
+                [] => RichText::new("—"),
+                rtts => RichText::new(units.format(rtts[(rtts.len() - 1) * 95 / 100])),
+            },
+            Metric::Expr(ref source) => match expr::parse(source) {
+                Ok(expr) => match expr::eval(&expr, canvases, range, clock) {
+                    Some(value) => RichText::new(format!("{:.2}", value)),
+                    None => RichText::new("—"),
+                },
+                Err(_) => RichText::new("—"),
+            },
+        }
+    }
+}
+
+impl Widget {
+    pub fn new(origin: Option<Pos2>) -> Self {
+        Self {
+            id: random(),
+            metric: Metric::Clock,
+            origin,
+            alert: None,
+            open: true,
+            points: vec![],
+            sampled: None,
+            firing: false,
+        }
+    }
+
+    // Samples the expression once per second and reports threshold crossings as (title, ok).
+    fn sample(
+        &mut self,
+        canvases: &[Canvas],
+        range: TimeRange,
+        clock: &Clock,
+    ) -> Option<(String, bool)> {
+        let Metric::Expr(source) = &self.metric else {
+            return None;
+        };
+
+        if self
+            .sampled
+            .is_some_and(|sampled| clock.since(sampled) < SAMPLE_EVERY)
+        {
+            return None;
+        }
+
+        self.sampled = Some(clock.now());
+
+        let value = expr::eval(&expr::parse(source).ok()?, canvases, range, clock)?;
+
+        self.points.push([plot::timestamp(clock.utc()), value]);
+
+        if self.points.len() > MAX_POINTS {
+            self.points.remove(0);
+        }
+
+        let firing = self.alert.is_some_and(|limit| value > limit);
+        let crossed = firing != self.firing;
+        self.firing = firing;
+
+        crossed.then(|| (source.clone(), !firing))
+    }
+}
+
+pub fn show(
+    ctx: &Context,
+    canvases: &mut [Canvas],
+    active: usize,
+    range: TimeRange,
+    units: Units,
+    locked: bool,
+    clock: &Clock,
+) -> Vec<(String, bool)> {
+    // Metrics only look at host windows, so the widgets can be set aside while they are evaluated.
+    let mut widgets = mem::take(&mut canvases[active].widgets);
+
+    let events = widgets
+        .iter_mut()
+        .filter_map(|widget| widget.sample(canvases, range, clock))
+        .collect_vec();
+
+    let values = widgets
+        .iter()
+        .map(|widget| widget.metric.value(canvases, range, units, clock))
+        .collect_vec();
+
+    for (widget, value) in widgets.iter_mut().zip(values) {
+        let mut window = Window::new(widget.metric.label())
+            .id(Id::new("widget").with(widget.id))
+            .movable(!locked)
+            .resizable(false)
+            .collapsible(false);
+
+        if !locked {
+            window = window.open(&mut widget.open);
+        }
+
+        if let Some(origin) = widget.origin {
+            window = window.default_pos(origin);
+        }
+
+        window.show(ctx, |ui| {
+            let color = [ui.visuals().text_color(), FAIL][widget.firing as usize];
+            ui.label(value.size(36.).monospace().color(color));
+
+            if let (Metric::Expr(_), false) = (&widget.metric, widget.points.is_empty()) {
+                Plot::new(Id::new("expr").with(widget.id))
+                    .height(80.)
+                    .width(240.)
+                    .show_axes([false, true])
+                    .allow_drag(false)
+                    .allow_zoom(false)
+                    .show(ui, |ui| ui.line(Line::new(widget.points.clone())));
+            }
+
+            if locked {
+                return;
+            }
+
+            ComboBox::from_id_source(Id::new("metric").with(widget.id))
+                .selected_text(widget.metric.label())
+                .show_ui(ui, |ui| {
+                    for metric in Metric::all() {
+                        let selected = match (&widget.metric, &metric) {
+                            (Metric::Expr(_), Metric::Expr(_)) => true,
+                            (current, metric) => current == metric,
+                        };
+
+                        if ui.selectable_label(selected, metric.label()).clicked() && !selected {
+                            widget.metric = metric;
+                            widget.points.clear();
+                        }
+                    }
+                });
+
+            let Metric::Expr(source) = &mut widget.metric else {
+                return;
+            };
+
+            let source_input = TextEdit::singleline(source)
+                .font(TextStyle::Monospace)
+                .hint_text(WidgetText::italics("max(rtt[wan]) - rtt[gateway]".into()));
+
+            if ui.add(source_input).changed() {
+                widget.points.clear();
+            }
+
+            if let (false, Err(error)) = (source.is_empty(), expr::parse(source)) {
+                ui.colored_label(FAIL, error);
+            }
+
+            ui.horizontal(|ui| {
+                let mut alert = widget.alert.is_some();
+
+                if ui.checkbox(&mut alert, "Avisar por encima de").changed() {
+                    widget.alert = alert.then_some(100.);
+                }
+
+                if let Some(limit) = &mut widget.alert {
+                    ui.add(DragValue::new(limit).speed(1.));
+                }
+            });
+        });
+    }
+
+    widgets.retain(|widget| widget.open);
+    canvases[active].widgets = widgets;
+
+    events
+}
@@ -0,0 +1,65 @@
+use std::{
+    future::Future,
+    sync::{
+        mpsc::{self, TryRecvError},
+        OnceLock,
+    },
+};
+
+use egui::Context;
+use tokio::runtime::Runtime;
+
+static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+
+pub struct Job<T>(mpsc::Receiver<T>);
+
+impl<T> Job<T> {
+    // None while the job is still running, Some(None) if it died without a result.
+    pub fn done(&self) -> Option<Option<T>> {
+        match self.0.try_recv() {
+            Ok(result) => Some(Some(result)),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(None),
+        }
+    }
+
+    // Blocks until the job ends, None if it died without a result.
+    pub fn wait(self) -> Option<T> {
+        self.0.recv().ok()
+    }
+}
+
+pub fn spawn<T: Send + 'static>(
+    ctx: &Context,
+    task: impl FnOnce() -> T + Send + 'static,
+) -> Job<T> {
+    let (sender, receiver) = mpsc::channel();
+    let ctx = ctx.clone();
+
+    runtime().spawn_blocking(move || {
+        let _ = sender.send(task());
+        ctx.request_repaint();
+    });
+
+    Job(receiver)
+}
+
+pub fn spawn_task(future: impl Future<Output = ()> + Send + 'static) {
+    runtime().spawn(future);
+}
+
+// Probes are blocking code (DNS, raw sockets) sprinkled with async bits, so they run on the
+// blocking pool and borrow the shared runtime for the async parts.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    runtime().handle().block_on(future)
+}
+
+fn runtime() -> &'static Runtime {
+    RUNTIME.get_or_init(|| {
+        tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .thread_name("pinga-worker")
+            .build()
+            .unwrap()
+    })
+}